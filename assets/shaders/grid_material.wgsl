@@ -5,6 +5,7 @@ struct GridUniforms {
     grid_visible: f32,
     chunk_cells: f32,
     grid_line_width: f32,
+    hex_rows: f32,
     grid_color: vec4<f32>,
 }
 
@@ -23,7 +24,12 @@ fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
     }
 
     // UV座標をセル単位に変換 (0..chunk_cells の範囲)
-    let cell_coord = mesh.uv * uniforms.chunk_cells;
+    var cell_coord = mesh.uv * uniforms.chunk_cells;
+
+    // 六角形近傍では奇数行のセルが半セル右にずれるため、縦の境界も半セルずらす
+    if uniforms.hex_rows > 0.5 && u32(floor(cell_coord.y)) % 2u == 1u {
+        cell_coord.x -= 0.5;
+    }
 
     // fwidth: フラグメント間のcell_coordの変化量 = 1スクリーンピクセルあたりのセル単位サイズ
     let fw = fwidth(cell_coord);
@@ -1,5 +1,7 @@
 //! ゲーム全体で使用する定数定義
 
+use std::time::Duration;
+
 use bevy::color::Color;
 
 // ウィンドウサイズ
@@ -41,8 +43,7 @@ pub fn calc_viewport_sizes(physical_width: u32, physical_height: u32) -> Viewpor
 }
 
 // チャンク設定
-/// 1チャンクの1辺のセル数
-pub const CHUNK_SIZE: i32 = 64;
+pub use life_core::consts::CHUNK_SIZE;
 /// 1セルのワールド空間サイズ
 pub const CELL_WORLD_SIZE: f32 = 1.0;
 /// 1チャンクのワールド空間サイズ
@@ -56,6 +57,64 @@ pub const MIN_TICK_INTERVAL: f32 = 0.1;
 /// ティック間隔の最大値（秒）
 pub const MAX_TICK_INTERVAL: f32 = 2.0;
 
+// 編集履歴
+/// 取り消し可能な編集操作の最大数
+pub const EDIT_HISTORY_LIMIT: usize = 256;
+
+// 巻き戻し
+/// 巻き戻し用に保持する過去世代の差分のメモリ上限（バイト）
+pub const REWIND_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+// 早送り
+/// 早送り中に1フレームで世代計算に使う時間の目安
+pub const FAST_FORWARD_FRAME_BUDGET: Duration = Duration::from_millis(8);
+
+// 統計パネル
+/// 人口推移グラフに保持する世代数
+pub const STATS_HISTORY_LENGTH: usize = 120;
+/// 世代進行速度（世代/秒）を平均する時間幅（秒）
+pub const STATS_RATE_WINDOW: f64 = 1.0;
+/// 統計パネルの幅（ピクセル）
+pub const STATS_PANEL_WIDTH: f32 = 240.0;
+/// 人口推移グラフの高さ（ピクセル）
+pub const STATS_GRAPH_HEIGHT: f32 = 60.0;
+/// 物体のセンサスを集計し直す最短間隔（秒）
+pub const CENSUS_REFRESH_INTERVAL: f32 = 1.0;
+/// 統計パネルに表示するセンサスの項目数
+pub const CENSUS_DISPLAY_LIMIT: usize = 8;
+
+// トポロジー
+/// メニューで選択できる有限トポロジーの1辺のセル数
+pub const TOPOLOGY_SIZE_PRESETS: [u32; 4] = [64, 128, 256, 512];
+/// 有限トポロジーの1辺のセル数の初期値
+pub const TOPOLOGY_DEFAULT_SIZE: u32 = 128;
+
+// 活動度ヒートマップ
+/// ヒートマップの集計窓の世代数の最小値
+pub const HEATMAP_MIN_WINDOW: usize = 1;
+/// ヒートマップの集計窓の世代数の最大値
+pub const HEATMAP_MAX_WINDOW: usize = 1024;
+/// ヒートマップの1世代ごとの減衰係数の最小値
+pub const HEATMAP_MIN_DECAY: f32 = 0.5;
+/// ヒートマップの1世代ごとの減衰係数の最大値（減衰なし）
+pub const HEATMAP_MAX_DECAY: f32 = 1.0;
+
+// 設定保存
+/// 設定・データディレクトリの特定に使うアプリケーション名
+pub const APP_NAME: &str = "bevy-life-game";
+/// ネイティブ版で設定を保存するファイル名（プラットフォームの設定ディレクトリ直下）
+pub const SETTINGS_FILE_NAME: &str = "settings.ron";
+/// Web版で設定を保存するlocalStorageのキー
+pub const SETTINGS_STORAGE_KEY: &str = "bevy-life-game.settings";
+/// 設定の変更が止まってから保存するまでの待ち時間（ズーム中などに毎フレーム書き込まないため）
+pub const SETTINGS_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+// セッション保存
+/// ネイティブ版でセッションの保存先の初期値にするファイル名（プラットフォームのデータディレクトリ直下）
+pub const SESSION_FILE_NAME: &str = "session.ron";
+/// Web版でセッションを保存するlocalStorageのキー
+pub const SESSION_STORAGE_KEY: &str = "bevy-life-game.session";
+
 // スペースキー長押し判定
 /// スペースキーを「長押し」と判定するまでの時間（秒）
 pub const SPACE_KEY_HOLD_DURATION: f32 = 0.5;
@@ -91,6 +150,10 @@ pub const ACTION_BUTTON_HEIGHT: f32 = 44.0;
 pub const GEN_COUNTER_MIN_WIDTH: f32 = 120.0;
 /// アクションボタンの最小幅（ピクセル）
 pub const ACTION_BUTTON_MIN_WIDTH: f32 = 90.0;
+/// 早送り先世代の入力欄の幅（ピクセル）
+pub const FAST_FORWARD_INPUT_WIDTH: f32 = 120.0;
+/// 早送り先世代の入力欄に入力できる最大桁数
+pub const FAST_FORWARD_MAX_DIGITS: usize = 12;
 /// ボタンの角丸半径
 pub const BORDER_RADIUS: f32 = 8.0;
 /// タイトル画面のボタン幅
@@ -162,19 +225,48 @@ pub const CELL_ALIVE_RGB: (u8, u8, u8) = (0, 217, 115);
 /// 死亡セルのRGB値（ほぼ黒）
 pub const CELL_DEAD_RGB: (u8, u8, u8) = (15, 15, 20);
 
-/// ムーア近傍の8方向の相対座標
+// テーマカラー: セルの年齢
+/// 年齢による色分けのグラデーションの区切り点（生存世代数, RGB値）
+///
+/// 誕生直後は明るい黄緑、数世代で通常の生存セルの色になり、長く生き残るほど青紫に寄る。
+pub const CELL_AGE_GRADIENT: [(u32, (u8, u8, u8)); 4] = [
+    (0, (210, 255, 140)),
+    (4, CELL_ALIVE_RGB),
+    (32, (0, 150, 210)),
+    (256, (110, 80, 220)),
+];
+/// 死亡した直後のセルの軌跡のRGB値（ここから死亡セルの色へ薄れていく）
+pub const CELL_TRAIL_RGB: (u8, u8, u8) = (130, 45, 70);
+
+// テーマカラー: Generations の衰退中のセル
+/// 衰退し始めたセル（状態2）のRGB値（青）
 ///
-/// あるセルの周囲8マスを走査するために使用する。
-pub const SQUARE_COORDINATES: [(i8, i8); 8] = [
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-    (-1, 0),
-    (1, 0),
-    (-1, 1),
-    (0, 1),
-    (1, 1),
+/// 状態が進むほど死亡セルの色に近づく。
+pub const CELL_DECAYING_RGB: (u8, u8, u8) = (60, 130, 255);
+
+// テーマカラー: 活動度ヒートマップ
+/// 活動度を色に変換するカラーランプの区切り点（活動度0.0〜1.0, RGB値）
+///
+/// 活動の少ないセルは青、活動が増えるほど黄色を経て赤に近づく。
+pub const HEATMAP_RAMP: [(f32, (u8, u8, u8)); 3] = [
+    (0.0, (40, 70, 220)),
+    (0.5, (240, 210, 40)),
+    (1.0, (235, 40, 30)),
 ];
+/// セルの色に重ねるカラーランプの不透明度（0.0〜1.0）
+pub const HEATMAP_OVERLAY_ALPHA: f32 = 0.7;
+
+// テーマカラー: 範囲選択
+/// 選択範囲のオーバーレイ色（半透明の青）
+pub const SELECTION_OVERLAY_COLOR: Color = Color::srgba(0.30, 0.60, 1.0, 0.20);
+/// 貼り付けプレビューのセル色（半透明のネオングリーン）
+pub const PASTE_GHOST_COLOR: Color = Color::srgba(0.0, 0.85, 0.45, 0.45);
+
+// テーマカラー: トポロジー
+/// 有限トポロジーの境界線の色（アンバー）
+pub const TOPOLOGY_BORDER_COLOR: Color = Color::srgb(0.95, 0.65, 0.15);
+/// 有限トポロジーの境界線のスクリーンピクセル幅（ズームレベルに依存しない一定幅）
+pub const TOPOLOGY_BORDER_SCREEN_WIDTH: f32 = 2.0;
 
 /// セル1個を表現するテクスチャピクセル数（幅・高さ）
 pub const CELL_PIXELS: u32 = 8;
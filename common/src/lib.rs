@@ -3,8 +3,12 @@
 //! 全プラグイン（タイトル・ゲーム・メニュー）で共有される定数・リソース・ステート・システムを提供する。
 
 pub mod consts;
-pub mod patterns;
 pub mod resources;
+pub mod session;
+pub mod settings;
 pub mod states;
 pub mod systems;
 pub mod ui;
+
+// NOTE: ルール・パターン・ファイル形式はBevyに依存しない life-core に置き、従来のパスで参照できるよう再公開する
+pub use life_core::{formats, patterns, rules, topology};
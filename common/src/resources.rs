@@ -2,8 +2,11 @@
 
 use bevy::prelude::{AssetServer, AudioSource, Font, FromWorld, Handle, Resource, World};
 
-use crate::consts::DEFAULT_TICK_INTERVAL;
-use crate::patterns::LifePattern;
+use crate::consts::TOPOLOGY_DEFAULT_SIZE;
+use crate::patterns::{LifePattern, WireworldCircuit};
+use crate::rules::Rule;
+use crate::settings::Settings;
+use crate::topology::{Surface, Topology};
 
 /// ゲーム全体で使用するアセットと設定を保持するリソース
 ///
@@ -18,31 +21,95 @@ pub struct GameAssets {
     /// ホバー時の効果音
     pub audio_hover: Handle<AudioSource>,
     /// シミュレーションのティック間隔（秒）
+    ///
+    /// 初期値は `Settings` の保存値（未登録ならデフォルト値）。
     pub tick_interval: f32,
 }
 
 /// オーディオのミュート状態を管理するリソース
 ///
 /// `true` の場合、効果音の再生をスキップする。
-/// メニュー画面のトグルボタンで切り替え可能。
+/// メニュー画面のトグルボタンで切り替え可能。起動時は `Settings` の保存値で初期化される。
 #[derive(Resource, Debug, Clone, Default)]
 pub struct AudioMuted(pub bool);
 
 /// メニューから選択されたパターンを保持するリソース
 ///
-/// パターンが選択されると `GameState::Game` 遷移時にワールドに配置される。
-/// 配置後は `None` にリセットされる。
+/// パターンが選択されると `GameState::Game` 遷移時にスタンプモードが始まり、
+/// クリックした位置にパターンを配置できる。スタンプ開始後は `None` にリセットされる。
 #[derive(Resource, Debug, Clone, Default)]
 pub struct SelectedPattern(pub LifePattern);
 
+/// メニューから選択された Wireworld の回路を保持するリソース
+///
+/// `SelectedPattern` と同じく `GameState::Game` 遷移時にスタンプモードが始まり、
+/// スタンプ開始後は `None` にリセットされる。回路を選ぶとルールも Wireworld になる。
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SelectedCircuit(pub WireworldCircuit);
+
+/// メニューから選択されたシミュレーションルールを保持するリソース
+///
+/// `GameState::Game` 遷移時にワールドへ適用される。
+/// パターンと異なり、選択後もリセットせず保持し続ける。
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SelectedRule(pub Rule);
+
+/// メニューから選択されたワールドのトポロジーを保持するリソース
+///
+/// `GameState::Game` 遷移時にワールドへ適用される。
+/// 無限平面を選んでいる間も、次に有限の面を選んだときのために大きさを保持する。
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedTopology {
+    /// 有限の面の種類（`None` は無限平面）
+    pub surface: Option<Surface>,
+    /// 有限の面の横方向のセル数
+    pub width: u32,
+    /// 有限の面の縦方向のセル数
+    pub height: u32,
+}
+
+impl Default for SelectedTopology {
+    fn default() -> Self {
+        Self {
+            surface: None,
+            width: TOPOLOGY_DEFAULT_SIZE,
+            height: TOPOLOGY_DEFAULT_SIZE,
+        }
+    }
+}
+
+impl SelectedTopology {
+    /// 選択内容からトポロジーを生成する
+    pub fn topology(&self) -> Topology {
+        match self.surface {
+            None => Topology::Infinite,
+            Some(surface) => Topology::finite(surface, self.width, self.height),
+        }
+    }
+
+    /// トポロジーを選択状態に反映する（無限平面では大きさを変えない）
+    pub fn select(&mut self, topology: Topology) {
+        self.surface = topology.surface();
+        if let Topology::Finite { width, height, .. } = topology {
+            self.width = width;
+            self.height = height;
+        }
+    }
+}
+
 impl FromWorld for GameAssets {
     fn from_world(world: &mut World) -> Self {
+        let tick_interval = world
+            .get_resource::<Settings>()
+            .copied()
+            .unwrap_or_default()
+            .tick_interval;
         let asset_server = world.resource::<AssetServer>();
         Self {
             font: asset_server.load("fonts/PixelMplus12-Regular.ttf"),
             font_bold: asset_server.load("fonts/PixelMplus12-Bold.ttf"),
             audio_hover: asset_server.load("audios/appear-online.ogg"),
-            tick_interval: DEFAULT_TICK_INTERVAL,
+            tick_interval,
         }
     }
 }
@@ -0,0 +1,304 @@
+//! ワールドのセッション（盤面・ルール・トポロジー・カメラ・速度）の保存形式
+//!
+//! セッションはバージョン付きのRONテキストとして保存する。
+//! 読み込み時はまずバージョンだけを確認し、未対応のバージョンは本体を解析せずに拒否する。
+//! 保存先（ネイティブ版はファイル、Web版はlocalStorage）は [`storage`] が扱う。
+
+pub mod storage;
+
+use std::fmt;
+
+use bevy::prelude::{Message, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{Rule, RuleParseError};
+use crate::topology::{Topology, TopologyParseError};
+
+pub use storage::{SessionStorage, SessionStore};
+
+/// 現在のセッション形式のバージョン
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+/// 保存・読み込みするワールドのセッション
+///
+/// セル座標は `(x, y)`（Y+が下方向）のワールド座標。
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Session {
+    /// セッション形式のバージョン
+    pub version: u32,
+    /// シミュレーションルール（`B3/S23` 形式の文字列で保存する）
+    #[serde(serialize_with = "serialize_notation")]
+    pub rule: Rule,
+    /// ワールドのトポロジー（`T128,128` 形式の文字列で保存し、無限平面は空文字列）
+    #[serde(serialize_with = "serialize_notation")]
+    pub topology: Topology,
+    /// 世代数
+    pub generation: u64,
+    /// ティック間隔（秒）
+    pub tick_interval: f32,
+    /// ワールドカメラの位置と拡大率
+    pub camera: CameraSnapshot,
+    /// 現在の生存セルの座標
+    pub cells: Vec<(i32, i32)>,
+    /// リセット時に戻る初期状態の生存セルの座標
+    pub initial_cells: Vec<(i32, i32)>,
+    /// Generations ルールの衰退中のセルの座標と状態
+    pub decaying: Vec<((i32, i32), u8)>,
+    /// リセット時に戻る初期状態の衰退中のセルの座標と状態
+    pub initial_decaying: Vec<((i32, i32), u8)>,
+}
+
+/// ワールドカメラの位置と拡大率
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraSnapshot {
+    /// ワールド座標でのX位置
+    pub x: f32,
+    /// ワールド座標でのY位置
+    pub y: f32,
+    /// 正射影のスケール
+    pub scale: f32,
+}
+
+/// バージョンの確認だけを行うための部分的なセッション
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+/// ルールを文字列のまま読み込んだセッション
+#[derive(Deserialize)]
+struct RawSession {
+    version: u32,
+    rule: String,
+    // NOTE: トポロジー追加前に保存したセッションにはこの項目がないため、無限平面として読み込む
+    #[serde(default)]
+    topology: String,
+    generation: u64,
+    tick_interval: f32,
+    camera: CameraSnapshot,
+    cells: Vec<(i32, i32)>,
+    initial_cells: Vec<(i32, i32)>,
+    // NOTE: Generations ルール対応前に保存したセッションには衰退中のセルがない
+    #[serde(default)]
+    decaying: Vec<((i32, i32), u8)>,
+    #[serde(default)]
+    initial_decaying: Vec<((i32, i32), u8)>,
+}
+
+impl Session {
+    /// RONテキストに変換する
+    pub fn to_ron(&self) -> Result<String, SessionError> {
+        // NOTE: セル座標のタプルまで改行すると巨大になるため、2階層目までに留める
+        let config = ron::ser::PrettyConfig::new().depth_limit(2);
+        ron::ser::to_string_pretty(self, config).map_err(|e| SessionError::Parse(e.to_string()))
+    }
+
+    /// RONテキストから読み込む
+    pub fn from_ron(text: &str) -> Result<Self, SessionError> {
+        let probe: VersionProbe =
+            ron::from_str(text).map_err(|e| SessionError::Parse(e.to_string()))?;
+        if probe.version != SESSION_FORMAT_VERSION {
+            return Err(SessionError::UnsupportedVersion(probe.version));
+        }
+        let raw: RawSession =
+            ron::from_str(text).map_err(|e| SessionError::Parse(e.to_string()))?;
+        Ok(Self {
+            version: raw.version,
+            rule: Rule::parse(&raw.rule).map_err(SessionError::InvalidRule)?,
+            topology: raw
+                .topology
+                .parse()
+                .map_err(SessionError::InvalidTopology)?,
+            generation: raw.generation,
+            tick_interval: raw.tick_interval,
+            camera: raw.camera,
+            cells: raw.cells,
+            initial_cells: raw.initial_cells,
+            decaying: raw.decaying,
+            initial_decaying: raw.initial_decaying,
+        })
+    }
+}
+
+/// ルール・トポロジーを表記文字列（`B3/S23`, `T128,128`）としてシリアライズする
+fn serialize_notation<T: fmt::Display, S: serde::Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// セッションの保存・読み込みエラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    /// 保存先の読み書きに失敗した
+    Io(String),
+    /// まだ何も保存されていない
+    NotFound,
+    /// RONテキストの書式誤り
+    Parse(String),
+    /// 未対応のセッション形式のバージョン
+    UnsupportedVersion(u32),
+    /// ルール文字列の解析失敗
+    InvalidRule(RuleParseError),
+    /// トポロジー文字列の解析失敗
+    InvalidTopology(TopologyParseError),
+    /// 保存先の選択が取り消された
+    Cancelled,
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "storage error: {e}"),
+            Self::NotFound => write!(f, "nothing has been saved yet"),
+            Self::Parse(e) => write!(f, "invalid session file: {e}"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported session version {v} (expected {SESSION_FORMAT_VERSION})"
+            ),
+            Self::InvalidRule(e) => write!(f, "invalid rule: {e}"),
+            Self::InvalidTopology(e) => write!(f, "invalid topology: {e}"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// セッションの保存・読み込みを要求するメッセージ
+///
+/// メニュー画面のボタンから送信し、ゲームプラグインが現在のワールドを保存・置き換える。
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionRequest {
+    /// 現在のワールドを保存する
+    Save,
+    /// 保存したワールドを読み込む
+    Load,
+}
+
+/// 直前のセッション保存・読み込みの結果を表示用に保持するリソース
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SessionStatus(pub Option<String>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Surface;
+
+    fn sample() -> Session {
+        Session {
+            version: SESSION_FORMAT_VERSION,
+            rule: Rule::parse("B2/S345/C4").unwrap(),
+            topology: Topology::finite(Surface::KleinBottle, 200, 100),
+            generation: 42,
+            tick_interval: 0.25,
+            camera: CameraSnapshot {
+                x: 12.5,
+                y: -3.0,
+                scale: 0.5,
+            },
+            cells: vec![(0, 0), (1, 0), (-70, 128)],
+            initial_cells: vec![(0, 1), (1, 1)],
+            decaying: vec![((2, 0), 2), ((3, 0), 3)],
+            initial_decaying: vec![((2, 1), 3)],
+        }
+    }
+
+    #[test]
+    fn roundtrip_preserves_session() {
+        let session = sample();
+        let text = session.to_ron().unwrap();
+        assert_eq!(Session::from_ron(&text).unwrap(), session);
+    }
+
+    #[test]
+    fn rule_is_saved_as_notation() {
+        let text = sample().to_ron().unwrap();
+        assert!(text.contains("\"B2/S345/C4\""), "{text}");
+    }
+
+    #[test]
+    fn topology_is_saved_as_notation() {
+        let text = sample().to_ron().unwrap();
+        assert!(text.contains("\"K200*,100\""), "{text}");
+    }
+
+    #[test]
+    fn session_without_topology_loads_as_infinite() {
+        let text = sample()
+            .to_ron()
+            .unwrap()
+            .lines()
+            .filter(|line| !line.contains("topology"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            Session::from_ron(&text).unwrap().topology,
+            Topology::Infinite
+        );
+        let text = sample().to_ron().unwrap().replace("K200*,100", "Q1,1");
+        assert_eq!(
+            Session::from_ron(&text),
+            Err(SessionError::InvalidTopology(
+                TopologyParseError::UnknownSurface('Q')
+            ))
+        );
+    }
+
+    #[test]
+    fn session_without_decaying_cells_loads_empty() {
+        let session = Session {
+            decaying: Vec::new(),
+            initial_decaying: Vec::new(),
+            ..sample()
+        };
+        let text = session
+            .to_ron()
+            .unwrap()
+            .lines()
+            .filter(|line| !line.contains("decaying"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(!text.contains("decaying"), "{text}");
+        assert_eq!(Session::from_ron(&text).unwrap(), session);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let text = sample()
+            .to_ron()
+            .unwrap()
+            .replace("version: 1", "version: 99");
+        assert_eq!(
+            Session::from_ron(&text),
+            Err(SessionError::UnsupportedVersion(99))
+        );
+    }
+
+    #[test]
+    fn invalid_rule_is_reported() {
+        let text = sample().to_ron().unwrap().replace("B2/S345/C4", "B0/S23");
+        assert_eq!(
+            Session::from_ron(&text),
+            Err(SessionError::InvalidRule(RuleParseError::UnsupportedB0))
+        );
+        let text = sample().to_ron().unwrap().replace("B2/S345/C4", "B39/S23");
+        assert_eq!(
+            Session::from_ron(&text),
+            Err(SessionError::InvalidRule(RuleParseError::InvalidDigit('9')))
+        );
+    }
+
+    #[test]
+    fn malformed_text_is_parse_error() {
+        assert!(matches!(
+            Session::from_ron("not a session"),
+            Err(SessionError::Parse(_))
+        ));
+        assert!(matches!(
+            Session::from_ron("(version: 1)"),
+            Err(SessionError::Parse(_))
+        ));
+    }
+}
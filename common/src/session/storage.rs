@@ -0,0 +1,265 @@
+//! セッションの保存先
+//!
+//! ネイティブ版はファイル、Web版はブラウザのlocalStorageにRONテキストを保存する。
+//! ファイルの保存先は [`PathPicker`] で選び、デフォルトはファイルダイアログ（[`DialogPathPicker`]）を出す。
+//! ダイアログを出せない環境（テストやヘッドレス実行）では固定パスを返す [`FixedPathPicker`] を使う。
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::Resource;
+
+use super::SessionError;
+
+/// セッションのRONテキストを読み書きする保存先
+pub trait SessionStore: Send + Sync {
+    /// テキストを保存し、保存先の説明（パスなど）を返す
+    fn save(&self, text: &str) -> Result<String, SessionError>;
+
+    /// 保存したテキストを読み込む
+    fn load(&self) -> Result<String, SessionError>;
+}
+
+/// 保存・読み込みするファイルのパスを選ぶ
+///
+/// `None` を返すと選択を取り消したものとして扱う。
+#[cfg(not(target_arch = "wasm32"))]
+pub trait PathPicker: Send + Sync {
+    /// 保存先のパスを選ぶ
+    fn pick_save_path(&self) -> Option<PathBuf>;
+
+    /// 読み込むファイルのパスを選ぶ
+    fn pick_load_path(&self) -> Option<PathBuf>;
+}
+
+/// ファイルダイアログでパスを選ぶパス選択
+///
+/// ダイアログは `default_path` のディレクトリとファイル名を初期値にして開く。
+/// ダイアログを閉じた場合は選択の取り消しになる。
+/// 呼び出し元のスレッドをブロックするため、メインスレッドから呼び出す。
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct DialogPathPicker {
+    default_path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DialogPathPicker {
+    /// ダイアログの初期値にするパスを指定して生成する
+    pub fn new(default_path: impl Into<PathBuf>) -> Self {
+        Self {
+            default_path: default_path.into(),
+        }
+    }
+
+    /// 初期値とRONファイルの絞り込みを設定したダイアログを生成する
+    fn dialog(&self) -> rfd::FileDialog {
+        let mut dialog = rfd::FileDialog::new().add_filter("RON", &["ron"]);
+        if let Some(directory) = self.default_path.parent() {
+            // NOTE: 初回はデータディレクトリがまだないため、作成してから開く
+            let _ = std::fs::create_dir_all(directory);
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = self.default_path.file_name() {
+            dialog = dialog.set_file_name(file_name.to_string_lossy());
+        }
+        dialog
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PathPicker for DialogPathPicker {
+    fn pick_save_path(&self) -> Option<PathBuf> {
+        self.dialog().save_file()
+    }
+
+    fn pick_load_path(&self) -> Option<PathBuf> {
+        self.dialog().pick_file()
+    }
+}
+
+/// 常に同じパスを返すパス選択
+///
+/// ファイルダイアログを使わないため、テストやヘッドレス環境で使う。
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FixedPathPicker(pub PathBuf);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PathPicker for FixedPathPicker {
+    fn pick_save_path(&self) -> Option<PathBuf> {
+        Some(self.0.clone())
+    }
+
+    fn pick_load_path(&self) -> Option<PathBuf> {
+        Some(self.0.clone())
+    }
+}
+
+/// ファイルにセッションを保存する保存先（ネイティブ版）
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileSessionStore {
+    picker: Box<dyn PathPicker>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSessionStore {
+    /// パス選択を指定して生成する
+    pub fn new(picker: impl PathPicker + 'static) -> Self {
+        Self {
+            picker: Box::new(picker),
+        }
+    }
+
+    /// 固定パスに保存する保存先を生成する
+    pub fn at(path: impl AsRef<Path>) -> Self {
+        Self::new(FixedPathPicker(path.as_ref().to_path_buf()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SessionStore for FileSessionStore {
+    fn save(&self, text: &str) -> Result<String, SessionError> {
+        let path = self
+            .picker
+            .pick_save_path()
+            .ok_or(SessionError::Cancelled)?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| SessionError::Io(e.to_string()))?;
+        }
+        std::fs::write(&path, text).map_err(|e| SessionError::Io(e.to_string()))?;
+        Ok(path.display().to_string())
+    }
+
+    fn load(&self) -> Result<String, SessionError> {
+        let path = self
+            .picker
+            .pick_load_path()
+            .ok_or(SessionError::Cancelled)?;
+        std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => SessionError::NotFound,
+            _ => SessionError::Io(e.to_string()),
+        })
+    }
+}
+
+/// ブラウザのlocalStorageにセッションを保存する保存先（Web版）
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorageSessionStore {
+    key: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageSessionStore {
+    /// localStorageのキーを指定して生成する
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+
+    fn storage() -> Result<web_sys::Storage, SessionError> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| SessionError::Io("localStorage is not available".to_string()))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SessionStore for LocalStorageSessionStore {
+    fn save(&self, text: &str) -> Result<String, SessionError> {
+        Self::storage()?
+            .set_item(&self.key, text)
+            .map_err(|e| SessionError::Io(format!("{e:?}")))?;
+        Ok(format!("localStorage[{}]", self.key))
+    }
+
+    fn load(&self) -> Result<String, SessionError> {
+        Self::storage()?
+            .get_item(&self.key)
+            .map_err(|e| SessionError::Io(format!("{e:?}")))?
+            .ok_or(SessionError::NotFound)
+    }
+}
+
+/// セッションの保存先を保持するリソース
+///
+/// デフォルトはネイティブ版がプラットフォームのデータディレクトリの `SESSION_FILE_NAME` を
+/// 初期値にしたファイルダイアログ、Web版が `SESSION_STORAGE_KEY` を使う。
+#[derive(Resource)]
+pub struct SessionStorage(pub Box<dyn SessionStore>);
+
+impl Default for SessionStorage {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let store = FileSessionStore::new(DialogPathPicker::new(default_session_path()));
+        #[cfg(target_arch = "wasm32")]
+        let store = LocalStorageSessionStore::new(crate::consts::SESSION_STORAGE_KEY);
+        Self(Box::new(store))
+    }
+}
+
+/// セッションファイルの初期パスを返す（プラットフォームのデータディレクトリ直下）
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_session_path() -> PathBuf {
+    use crate::consts::{APP_NAME, SESSION_FILE_NAME};
+    // NOTE: データディレクトリを特定できない環境では作業ディレクトリに保存する
+    directories::ProjectDirs::from("", "", APP_NAME)
+        .map(|dirs| dirs.data_dir().join(SESSION_FILE_NAME))
+        .unwrap_or_else(|| SESSION_FILE_NAME.into())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    /// テストごとに異なる一時ディレクトリのパスを返す
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("life-session-test-{}", std::process::id()))
+            .join(name)
+    }
+
+    /// 常に取り消すパス選択
+    struct CancelPicker;
+
+    impl PathPicker for CancelPicker {
+        fn pick_save_path(&self) -> Option<PathBuf> {
+            None
+        }
+
+        fn pick_load_path(&self) -> Option<PathBuf> {
+            None
+        }
+    }
+
+    #[test]
+    fn file_store_roundtrip_creates_parent_directory() {
+        let path = temp_path("nested/session.ron");
+        let store = FileSessionStore::at(&path);
+        let location = store.save("(version: 1)").unwrap();
+        assert_eq!(location, path.display().to_string());
+        assert_eq!(store.load().unwrap(), "(version: 1)");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loading_missing_file_is_not_found() {
+        let store = FileSessionStore::at(temp_path("missing.ron"));
+        assert_eq!(store.load(), Err(SessionError::NotFound));
+    }
+
+    #[test]
+    fn default_session_path_is_session_file_in_data_dir() {
+        let path = default_session_path();
+        assert_eq!(path.file_name().unwrap(), crate::consts::SESSION_FILE_NAME);
+        if let Some(dirs) = directories::ProjectDirs::from("", "", crate::consts::APP_NAME) {
+            assert!(path.starts_with(dirs.data_dir()));
+        }
+    }
+
+    #[test]
+    fn cancelled_pick_is_reported() {
+        let store = FileSessionStore::new(CancelPicker);
+        assert_eq!(store.save(""), Err(SessionError::Cancelled));
+        assert_eq!(store.load(), Err(SessionError::Cancelled));
+    }
+}
@@ -0,0 +1,398 @@
+//! 起動をまたいで保持するユーザー設定
+//!
+//! シミュレーション速度・ズーム・ミュート・グリッド表示・自動一時停止・年齢による色分け・
+//! 活動度ヒートマップの表示と集計方法・操作に割り当てるキーを設定ファイルに保存する。
+//! ネイティブ版はプラットフォームの設定ディレクトリ、Web版はlocalStorageを使い、
+//! 保存先の読み書きはセッションと同じ [`SessionStore`] に任せる。
+//! 起動時に読み込んで各リソースの初期値に反映し、`Settings` の変更が `SETTINGS_SAVE_DELAY` の間
+//! 止まったとき（ズームやスライダー操作の終了時）とアプリケーションの終了時に書き戻す。
+//! 未知の項目や不正な値は警告を出したうえで無視し、デフォルト値を使う。
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use life_core::heatmap::HeatmapConfig;
+use serde::Serialize;
+
+use crate::consts::{
+    DEFAULT_TICK_INTERVAL, HEATMAP_MAX_DECAY, HEATMAP_MAX_WINDOW, HEATMAP_MIN_DECAY,
+    HEATMAP_MIN_WINDOW, INITIAL_CAMERA_SCALE, MAX_CAMERA_SCALE, MAX_TICK_INTERVAL,
+    MIN_CAMERA_SCALE, MIN_TICK_INTERVAL, SETTINGS_SAVE_DELAY,
+};
+pub mod key_bindings;
+
+use key_bindings::KeyBindings;
+
+use crate::resources::AudioMuted;
+use crate::session::{SessionError, SessionStore};
+
+/// 起動をまたいで保持するユーザー設定のリソース
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Settings {
+    /// シミュレーションのティック間隔（秒）
+    pub tick_interval: f32,
+    /// ワールドカメラの正射影のスケール
+    pub camera_scale: f32,
+    /// 効果音をミュートするか
+    pub muted: bool,
+    /// グリッド線を表示するか
+    pub grid_visible: bool,
+    /// 全滅・固定物体を検出したらシミュレーションを自動で一時停止するか
+    pub auto_pause: bool,
+    /// セルを年齢によって色分けするか
+    pub age_coloring: bool,
+    /// 活動度ヒートマップを表示するか
+    pub heatmap: bool,
+    /// 活動度ヒートマップで反転を数える直近の世代数
+    pub heatmap_window: usize,
+    /// 活動度ヒートマップで1世代古くなるごとに反転1回の重みに掛ける係数
+    pub heatmap_decay: f32,
+    /// 操作に割り当てるキー
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            camera_scale: INITIAL_CAMERA_SCALE,
+            muted: false,
+            grid_visible: true,
+            auto_pause: false,
+            age_coloring: false,
+            heatmap: false,
+            heatmap_window: HeatmapConfig::default().window,
+            heatmap_decay: HeatmapConfig::default().decay,
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// 活動度ヒートマップの集計方法を返す
+    pub fn heatmap_config(&self) -> HeatmapConfig {
+        HeatmapConfig {
+            window: self.heatmap_window,
+            decay: self.heatmap_decay,
+        }
+    }
+
+    /// RONテキストに変換する
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .expect("settings contain only plain values")
+    }
+
+    /// RONテキストから読み込み、読み飛ばした項目についての警告とともに返す
+    ///
+    /// 項目ごとに検証し、未知の項目・型や範囲が不正な項目はデフォルト値のままにする。
+    /// テキスト全体を解析できなければ全てデフォルト値になる。
+    pub fn from_ron(text: &str) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let map = match ron::from_str::<ron::Value>(text) {
+            Ok(ron::Value::Map(map)) => map,
+            Ok(_) => return (settings, vec!["settings must be a struct".to_string()]),
+            Err(e) => return (settings, vec![format!("invalid settings file: {e}")]),
+        };
+
+        let mut warnings = Vec::new();
+        for (key, value) in map {
+            let ron::Value::String(key) = key else {
+                warnings.push(format!("ignoring non-string key {key:?}"));
+                continue;
+            };
+            let applied = match key.as_str() {
+                "tick_interval" => value
+                    .into_rust::<f32>()
+                    .ok()
+                    .filter(|v| (MIN_TICK_INTERVAL..=MAX_TICK_INTERVAL).contains(v))
+                    .map(|v| settings.tick_interval = v),
+                "camera_scale" => value
+                    .into_rust::<f32>()
+                    .ok()
+                    .filter(|v| (MIN_CAMERA_SCALE..=MAX_CAMERA_SCALE).contains(v))
+                    .map(|v| settings.camera_scale = v),
+                "muted" => value.into_rust().ok().map(|v| settings.muted = v),
+                "grid_visible" => value.into_rust().ok().map(|v| settings.grid_visible = v),
+                "auto_pause" => value.into_rust().ok().map(|v| settings.auto_pause = v),
+                "age_coloring" => value.into_rust().ok().map(|v| settings.age_coloring = v),
+                "heatmap" => value.into_rust().ok().map(|v| settings.heatmap = v),
+                "heatmap_window" => value
+                    .into_rust::<usize>()
+                    .ok()
+                    .filter(|v| (HEATMAP_MIN_WINDOW..=HEATMAP_MAX_WINDOW).contains(v))
+                    .map(|v| settings.heatmap_window = v),
+                "heatmap_decay" => value
+                    .into_rust::<f32>()
+                    .ok()
+                    .filter(|v| (HEATMAP_MIN_DECAY..=HEATMAP_MAX_DECAY).contains(v))
+                    .map(|v| settings.heatmap_decay = v),
+                "key_bindings" => KeyBindings::from_ron_value(value, &mut warnings)
+                    .map(|v| settings.key_bindings = v),
+                _ => {
+                    warnings.push(format!("ignoring unknown setting `{key}`"));
+                    continue;
+                }
+            };
+            if applied.is_none() {
+                warnings.push(format!("invalid value for `{key}`, using default"));
+            }
+        }
+        (settings, warnings)
+    }
+
+    /// 保存先から読み込む。保存されていなければデフォルト値を返す
+    pub fn load(store: &dyn SessionStore) -> Self {
+        let text = match store.load() {
+            Ok(text) => text,
+            Err(SessionError::NotFound) => return Self::default(),
+            Err(e) => {
+                warn!("failed to load settings: {e}");
+                return Self::default();
+            }
+        };
+        let (settings, warnings) = Self::from_ron(&text);
+        for warning in warnings {
+            warn!("settings: {warning}");
+        }
+        settings
+    }
+}
+
+/// 設定の保存先を保持するリソース
+///
+/// デフォルトはネイティブ版がプラットフォームの設定ディレクトリの `SETTINGS_FILE_NAME`、
+/// Web版が `SETTINGS_STORAGE_KEY` を使う。
+#[derive(Resource)]
+pub struct SettingsStorage(pub Box<dyn SessionStore>);
+
+impl Default for SettingsStorage {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let store = {
+            use crate::consts::{APP_NAME, SETTINGS_FILE_NAME};
+            // NOTE: 設定ディレクトリを特定できない環境では作業ディレクトリに保存する
+            let path = directories::ProjectDirs::from("", "", APP_NAME)
+                .map(|dirs| dirs.config_dir().join(SETTINGS_FILE_NAME))
+                .unwrap_or_else(|| SETTINGS_FILE_NAME.into());
+            crate::session::storage::FileSessionStore::at(path)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let store = crate::session::storage::LocalStorageSessionStore::new(
+            crate::consts::SETTINGS_STORAGE_KEY,
+        );
+        Self(Box::new(store))
+    }
+}
+
+/// ユーザー設定を読み込み、変更のたびに保存するプラグイン
+///
+/// 他のプラグインが初期値を参照できるよう、ビルド時に読み込んで `Settings` を登録する。
+/// `SettingsStorage` が登録済みであればその保存先を使う。
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsStorage>();
+        let settings = Settings::load(app.world().resource::<SettingsStorage>().0.as_ref());
+        app.insert_resource(settings);
+        app.insert_resource(AudioMuted(settings.muted));
+        app.add_systems(Update, sync_muted_setting);
+        // NOTE: 終了要求と同じフレームで保存できるよう、Update より後に実行する
+        app.add_systems(Last, save_settings);
+    }
+}
+
+/// ミュート状態の変更を設定に反映するシステム
+fn sync_muted_setting(audio_muted: Res<AudioMuted>, mut settings: ResMut<Settings>) {
+    if audio_muted.is_changed() && settings.muted != audio_muted.0 {
+        settings.muted = audio_muted.0;
+    }
+}
+
+/// 設定の変更が `SETTINGS_SAVE_DELAY` の間止まったら保存先に書き込むシステム
+///
+/// 終了要求があれば待たずに書き込む。`last_change` は未保存の変更が最後にあった時刻。
+fn save_settings(
+    settings: Res<Settings>,
+    storage: Res<SettingsStorage>,
+    time: Res<Time<Real>>,
+    mut exit_reader: MessageReader<AppExit>,
+    mut last_change: Local<Option<Duration>>,
+) {
+    if settings.is_changed() && !settings.is_added() {
+        *last_change = Some(time.elapsed());
+    }
+    let exiting = exit_reader.read().count() > 0;
+    let Some(changed_at) = *last_change else {
+        return;
+    };
+    if !exiting && time.elapsed().saturating_sub(changed_at) < SETTINGS_SAVE_DELAY {
+        return;
+    }
+    *last_change = None;
+    if let Err(e) = storage.0.save(&settings.to_ron()) {
+        warn!("failed to save settings: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::time::TimePlugin;
+
+    use super::*;
+
+    /// 保存されたテキストを記録する保存先
+    #[derive(Clone, Default)]
+    struct RecordingStore(Arc<Mutex<Vec<String>>>);
+
+    impl SessionStore for RecordingStore {
+        fn save(&self, text: &str) -> Result<String, SessionError> {
+            self.0.lock().unwrap().push(text.to_string());
+            Ok("memory".to_string())
+        }
+
+        fn load(&self) -> Result<String, SessionError> {
+            Err(SessionError::NotFound)
+        }
+    }
+
+    fn settings_app(store: &RecordingStore) -> App {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .insert_resource(SettingsStorage(Box::new(store.clone())))
+            .add_plugins(SettingsPlugin);
+        app.update();
+        app
+    }
+
+    #[test]
+    fn changes_every_frame_are_not_saved_until_they_settle() {
+        let store = RecordingStore::default();
+        let mut app = settings_app(&store);
+        for frame in 0..10 {
+            app.world_mut().resource_mut::<Settings>().camera_scale = 0.1 + frame as f32 * 0.01;
+            app.update();
+        }
+        assert!(store.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn pending_change_is_saved_on_exit() {
+        let store = RecordingStore::default();
+        let mut app = settings_app(&store);
+        app.world_mut().resource_mut::<Settings>().camera_scale = 0.2;
+        app.update();
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+        let saved = store.0.lock().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(Settings::from_ron(&saved[0]).0.camera_scale, 0.2);
+    }
+
+    #[test]
+    fn exit_without_changes_does_not_save() {
+        let store = RecordingStore::default();
+        let mut app = settings_app(&store);
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+        assert!(store.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn roundtrip_preserves_settings() {
+        let settings = Settings {
+            tick_interval: 0.3,
+            camera_scale: 0.2,
+            muted: true,
+            grid_visible: false,
+            auto_pause: true,
+            age_coloring: true,
+            heatmap: true,
+            heatmap_window: 16,
+            heatmap_decay: 0.8,
+            key_bindings: {
+                let mut bindings = KeyBindings::default();
+                bindings.set(key_bindings::KeyAction::Step, KeyCode::ArrowRight);
+                bindings
+            },
+        };
+        let (loaded, warnings) = Settings::from_ron(&settings.to_ron());
+        assert_eq!(loaded, settings);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let (loaded, warnings) = Settings::from_ron("(muted: true)");
+        assert_eq!(
+            loaded,
+            Settings {
+                muted: true,
+                ..Settings::default()
+            }
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unknown_and_invalid_fields_fall_back_with_warnings() {
+        let (loaded, warnings) = Settings::from_ron(
+            "(tick_interval: 99.0, camera_scale: \"big\", grid_visible: false, volume: 3)",
+        );
+        assert_eq!(
+            loaded,
+            Settings {
+                grid_visible: false,
+                ..Settings::default()
+            }
+        );
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().any(|w| w.contains("volume")));
+        assert!(warnings.iter().any(|w| w.contains("tick_interval")));
+        assert!(warnings.iter().any(|w| w.contains("camera_scale")));
+    }
+
+    #[test]
+    fn heatmap_settings_out_of_range_fall_back() {
+        let (loaded, warnings) =
+            Settings::from_ron("(heatmap: true, heatmap_window: 0, heatmap_decay: 1.5)");
+        assert!(loaded.heatmap);
+        assert_eq!(loaded.heatmap_config(), HeatmapConfig::default());
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn integer_values_are_accepted_for_floats() {
+        let (loaded, warnings) = Settings::from_ron("(tick_interval: 2)");
+        assert_eq!(loaded.tick_interval, 2.0);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn key_binding_warnings_are_reported_per_entry() {
+        let (loaded, warnings) = Settings::from_ron(
+            "(muted: true, key_bindings: {\"step\": \"Nope\", \"pan_up\": \"ArrowUp\"})",
+        );
+        assert!(loaded.muted);
+        assert_eq!(
+            loaded.key_bindings.key(key_bindings::KeyAction::PanUp),
+            KeyCode::ArrowUp
+        );
+        assert_eq!(
+            loaded.key_bindings.key(key_bindings::KeyAction::Step),
+            KeyCode::Space
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("step"));
+    }
+
+    #[test]
+    fn malformed_text_uses_defaults() {
+        let (loaded, warnings) = Settings::from_ron("(tick_interval: ");
+        assert_eq!(loaded, Settings::default());
+        assert_eq!(warnings.len(), 1);
+    }
+}
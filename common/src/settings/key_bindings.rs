@@ -0,0 +1,346 @@
+//! 操作に割り当てるキー
+//!
+//! 設定ファイルでは操作名からキー名（`KeyCode` のバリアント名）へのマップとして保存する。
+//! Escape・修飾キーとの組み合わせ・貼り付け中の回転反転など固定の操作に使うキーは割り当てられない。
+
+use bevy::input::keyboard::KeyCode;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// キーを割り当てられる操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    /// 短押しで1世代進める、長押しで自動シミュレーション
+    Step,
+    /// 1世代巻き戻す
+    StepBack,
+    /// 統計パネルの表示を切り替える
+    ToggleStats,
+    /// セルの年齢による色分けを切り替える
+    ToggleAgeColoring,
+    /// 活動度ヒートマップの表示を切り替える
+    ToggleHeatmap,
+    /// セル編集で塗る状態を切り替える
+    CyclePaintState,
+    /// カメラを上に動かす
+    PanUp,
+    /// カメラを下に動かす
+    PanDown,
+    /// カメラを左に動かす
+    PanLeft,
+    /// カメラを右に動かす
+    PanRight,
+    /// ズームアウト
+    ZoomOut,
+    /// ズームイン
+    ZoomIn,
+}
+
+impl KeyAction {
+    /// 全ての操作（設定ファイルに書き出す順）
+    pub const ALL: [Self; 12] = [
+        Self::Step,
+        Self::StepBack,
+        Self::ToggleStats,
+        Self::ToggleAgeColoring,
+        Self::ToggleHeatmap,
+        Self::CyclePaintState,
+        Self::PanUp,
+        Self::PanDown,
+        Self::PanLeft,
+        Self::PanRight,
+        Self::ZoomOut,
+        Self::ZoomIn,
+    ];
+
+    /// 設定ファイルで使う操作名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Step => "step",
+            Self::StepBack => "step_back",
+            Self::ToggleStats => "toggle_stats",
+            Self::ToggleAgeColoring => "toggle_age_coloring",
+            Self::ToggleHeatmap => "toggle_heatmap",
+            Self::CyclePaintState => "cycle_paint_state",
+            Self::PanUp => "pan_up",
+            Self::PanDown => "pan_down",
+            Self::PanLeft => "pan_left",
+            Self::PanRight => "pan_right",
+            Self::ZoomOut => "zoom_out",
+            Self::ZoomIn => "zoom_in",
+        }
+    }
+
+    /// 操作名から操作を返す
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// デフォルトで割り当てるキー
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Self::Step => KeyCode::Space,
+            Self::StepBack => KeyCode::KeyB,
+            Self::ToggleStats => KeyCode::KeyI,
+            Self::ToggleAgeColoring => KeyCode::KeyG,
+            Self::ToggleHeatmap => KeyCode::KeyH,
+            Self::CyclePaintState => KeyCode::KeyN,
+            Self::PanUp => KeyCode::KeyW,
+            Self::PanDown => KeyCode::KeyS,
+            Self::PanLeft => KeyCode::KeyA,
+            Self::PanRight => KeyCode::KeyD,
+            Self::ZoomOut => KeyCode::KeyQ,
+            Self::ZoomIn => KeyCode::KeyE,
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|&action| action == self)
+            .expect("ALL contains every action")
+    }
+}
+
+/// 操作に割り当てられるキー
+///
+/// 固定の操作に使うキー（Escape・修飾キー・C/X/V/Z・R/F/T・Delete）は含めない。
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyS,
+    KeyCode::KeyU,
+    KeyCode::KeyW,
+    KeyCode::KeyY,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Backquote,
+];
+
+/// キー名を返す（`KeyCode` のバリアント名）
+fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+/// キー名から割り当てられるキーを返す
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|&key| key_name(key) == name)
+}
+
+/// 操作ごとに割り当てたキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBindings([KeyCode; KeyAction::ALL.len()]);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(KeyAction::ALL.map(KeyAction::default_key))
+    }
+}
+
+impl KeyBindings {
+    /// 操作に割り当てたキーを返す
+    pub fn key(&self, action: KeyAction) -> KeyCode {
+        self.0[action.index()]
+    }
+
+    /// 操作にキーを割り当てる
+    pub fn set(&mut self, action: KeyAction, key: KeyCode) {
+        self.0[action.index()] = key;
+    }
+
+    /// 他の操作と同じキーが割り当てられているかを返す
+    fn conflicts(&self, action: KeyAction) -> bool {
+        let key = self.key(action);
+        KeyAction::ALL
+            .into_iter()
+            .any(|other| other != action && self.key(other) == key)
+    }
+
+    /// 設定ファイルの値から読み込み、読み飛ばした項目についての警告を `warnings` に追加する
+    ///
+    /// 項目ごとに検証し、未知の操作・割り当てられないキー・他の操作と重なるキーは
+    /// デフォルトのキーのままにする。値がマップでなければ `None` を返す。
+    pub fn from_ron_value(value: ron::Value, warnings: &mut Vec<String>) -> Option<Self> {
+        let ron::Value::Map(map) = value else {
+            return None;
+        };
+        let mut bindings = Self::default();
+        let mut overridden = Vec::new();
+        for (action, key) in map {
+            let Ok(name) = action.into_rust::<String>() else {
+                warnings.push("ignoring non-string key binding".to_string());
+                continue;
+            };
+            let Some(action) = KeyAction::from_name(&name) else {
+                warnings.push(format!("ignoring unknown key binding `{name}`"));
+                continue;
+            };
+            match key
+                .into_rust::<String>()
+                .ok()
+                .as_deref()
+                .and_then(parse_key)
+            {
+                Some(key) => {
+                    bindings.set(action, key);
+                    overridden.push(action);
+                }
+                None => warnings.push(format!(
+                    "invalid key for `{}`, using default",
+                    action.name()
+                )),
+            }
+        }
+        // NOTE: 重なりがなくなるまで、変更した操作を前から順にデフォルトのキーに戻す。
+        // デフォルト同士は重ならないため、必ず終わる
+        while let Some(position) = overridden
+            .iter()
+            .position(|&action| bindings.conflicts(action))
+        {
+            let action = overridden.remove(position);
+            warnings.push(format!(
+                "key {} for `{}` is already bound, using default",
+                key_name(bindings.key(action)),
+                action.name()
+            ));
+            bindings.set(action, action.default_key());
+        }
+        Some(bindings)
+    }
+}
+
+impl Serialize for KeyBindings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(KeyAction::ALL.len()))?;
+        for action in KeyAction::ALL {
+            map.serialize_entry(action.name(), &key_name(self.key(action)))?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> (Option<KeyBindings>, Vec<String>) {
+        let mut warnings = Vec::new();
+        let bindings = KeyBindings::from_ron_value(ron::from_str(text).unwrap(), &mut warnings);
+        (bindings, warnings)
+    }
+
+    #[test]
+    fn default_keys_do_not_conflict() {
+        let bindings = KeyBindings::default();
+        for action in KeyAction::ALL {
+            assert!(!bindings.conflicts(action), "{action:?}");
+            assert_eq!(
+                parse_key(&key_name(action.default_key())),
+                Some(action.default_key())
+            );
+        }
+    }
+
+    #[test]
+    fn names_roundtrip() {
+        for action in KeyAction::ALL {
+            assert_eq!(KeyAction::from_name(action.name()), Some(action));
+        }
+        assert_eq!(parse_key("ArrowUp"), Some(KeyCode::ArrowUp));
+        assert_eq!(parse_key("Escape"), None);
+        assert_eq!(parse_key("KeyC"), None);
+    }
+
+    #[test]
+    fn swapped_keys_are_accepted() {
+        let (bindings, warnings) = parse(r#"{"step_back": "KeyG", "toggle_age_coloring": "KeyB"}"#);
+        let bindings = bindings.unwrap();
+        assert_eq!(bindings.key(KeyAction::StepBack), KeyCode::KeyG);
+        assert_eq!(bindings.key(KeyAction::ToggleAgeColoring), KeyCode::KeyB);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn invalid_entries_fall_back_with_warnings() {
+        let (bindings, warnings) =
+            parse(r#"{"jump": "KeyJ", "pan_up": "Escape", "zoom_in": 3, "pan_down": "ArrowDown"}"#);
+        let bindings = bindings.unwrap();
+        assert_eq!(bindings.key(KeyAction::PanUp), KeyCode::KeyW);
+        assert_eq!(bindings.key(KeyAction::ZoomIn), KeyCode::KeyE);
+        assert_eq!(bindings.key(KeyAction::PanDown), KeyCode::ArrowDown);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().any(|w| w.contains("jump")));
+        assert!(warnings.iter().any(|w| w.contains("pan_up")));
+        assert!(warnings.iter().any(|w| w.contains("zoom_in")));
+    }
+
+    #[test]
+    fn conflicting_key_falls_back_to_default() {
+        let (bindings, warnings) = parse(r#"{"step_back": "KeyG"}"#);
+        assert_eq!(bindings.unwrap(), KeyBindings::default());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("step_back"));
+    }
+
+    #[test]
+    fn non_map_value_is_rejected() {
+        assert_eq!(parse("\"Space\"").0, None);
+    }
+}
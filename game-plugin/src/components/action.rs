@@ -10,6 +10,8 @@ use bevy::prelude::Component;
 pub enum GameButtonAction {
     /// シミュレーション開始/停止トグル
     ToggleSimulation,
+    /// 1世代巻き戻す
+    StepBack,
     /// 1世代進める
     Next,
     /// 初期状態にリセット
@@ -18,4 +20,14 @@ pub enum GameButtonAction {
     Clear,
     /// グリッドライン表示/非表示切り替え
     GridToggle,
+    /// 世代計算エンジンの切り替え
+    EngineToggle,
+    /// 全滅・固定物体での自動一時停止の切り替え
+    AutoPauseToggle,
+    /// 活動度ヒートマップの表示の切り替え
+    HeatmapToggle,
+    /// セル編集で塗る状態の切り替え（Generations ルールの衰退中の状態を順に選ぶ）
+    PaintState,
+    /// 入力した世代まで早送り
+    FastForward,
 }
@@ -10,6 +10,56 @@ pub struct OnGameScreen;
 #[derive(Component)]
 pub struct GenerationText;
 
+/// 適用中ルール表示テキストのマーカー
+#[derive(Component)]
+pub struct RuleText;
+
+/// 使用中の世代計算エンジン表示テキストのマーカー
+#[derive(Component)]
+pub struct EngineText;
+
+/// 検出した周期的な振る舞いの表示テキストのマーカー
+#[derive(Component)]
+pub struct CycleText;
+
+/// 統計パネルのルートノードのマーカー
+#[derive(Component)]
+pub struct StatsPanel;
+
+/// 統計パネルの数値表示テキストのマーカー
+#[derive(Component)]
+pub struct StatsText;
+
+/// 物体のセンサス表示テキストのマーカー
+#[derive(Component)]
+pub struct CensusText;
+
+/// 人口推移グラフの線分のマーカー（値は左端からの番号）
+#[derive(Component)]
+pub struct PopulationGraphLine(pub usize);
+
+/// 早送り先世代の入力欄のマーカー
+#[derive(Component)]
+pub struct FastForwardInputBox;
+
+/// 早送り先世代の入力欄テキストのマーカー
+#[derive(Component)]
+pub struct FastForwardText;
+
 /// マウスホバー時のセルハイライト表示のマーカー
 #[derive(Component)]
 pub struct CellHighlight;
+
+/// 範囲選択のオーバーレイ表示のマーカー
+#[derive(Component)]
+pub struct SelectionOverlay;
+
+/// 有限トポロジーの境界線の1辺のマーカー（0: 上, 1: 下, 2: 左, 3: 右）
+#[derive(Component)]
+pub struct TopologyBorder(pub usize);
+
+/// 貼り付けプレビュー（ゴースト）の親エンティティのマーカー
+///
+/// ホバー中のセルに追従し、子にクリップボードの各セルのスプライトを持つ。
+#[derive(Component)]
+pub struct PasteGhost;
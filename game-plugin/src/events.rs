@@ -1,11 +1,20 @@
 //! ゲーム内のシステム間通信に使用するメッセージイベント
 
 use bevy::prelude::Message;
+use common::patterns::CellTransform;
 
 /// 世代を1つ進めるイベント
 #[derive(Message)]
 pub struct ProgressGenerationEvent;
 
+/// 直前に記録した世代へ巻き戻すイベント
+#[derive(Message)]
+pub struct StepBackEvent;
+
+/// 指定した世代まで早送りするイベント
+#[derive(Message)]
+pub struct FastForwardEvent(pub u64);
+
 /// 世代を初期状態にリセットするイベント
 #[derive(Message)]
 pub struct GenerationResetEvent;
@@ -14,6 +23,35 @@ pub struct GenerationResetEvent;
 #[derive(Message)]
 pub struct WorldClearEvent;
 
+/// 直前の編集を取り消すイベント
+#[derive(Message)]
+pub struct UndoEvent;
+
+/// 取り消した編集をやり直すイベント
+#[derive(Message)]
+pub struct RedoEvent;
+
+/// 範囲選択・クリップボードの操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardAction {
+    /// 選択範囲をコピーする
+    Copy,
+    /// 選択範囲をコピーして削除する
+    Cut,
+    /// 貼り付け位置の選択を開始する
+    Paste,
+    /// 選択範囲のセルを削除する
+    Delete,
+    /// 貼り付け・スタンプ中のセルを回転・反転する
+    Transform(CellTransform),
+    /// 貼り付け・スタンプ・範囲選択を取り消す
+    Cancel,
+}
+
+/// 範囲選択・クリップボードを操作するイベント
+#[derive(Message)]
+pub struct ClipboardEvent(pub ClipboardAction);
+
 /// 効果音を再生するイベント
 #[derive(Message)]
 pub struct PlayAudioEvent;
@@ -20,6 +20,8 @@ pub struct GridUniforms {
     pub chunk_cells: f32,
     /// グリッド線のスクリーンピクセル幅
     pub grid_line_width: f32,
+    /// 六角形近傍で奇数行を半セルずらすフラグ（0.0=ずらさない, 1.0=ずらす）
+    pub hex_rows: f32,
     /// グリッド線の色
     pub grid_color: LinearRgba,
 }
@@ -6,9 +6,13 @@
 use bevy::sprite_render::Material2dPlugin;
 use bevy::{camera::Viewport, prelude::*};
 use common::{
-    consts::{INITIAL_CAMERA_SCALE, WINDOW_HEIGHT, WINDOW_WIDTH, calc_viewport_sizes},
-    patterns::LifePattern,
-    resources::{AudioMuted, GameAssets, SelectedPattern},
+    consts::{WINDOW_HEIGHT, WINDOW_WIDTH, calc_viewport_sizes},
+    patterns::{LifePattern, WireworldCircuit},
+    resources::{
+        AudioMuted, GameAssets, SelectedCircuit, SelectedPattern, SelectedRule, SelectedTopology,
+    },
+    session::{SessionRequest, SessionStatus, SessionStorage},
+    settings::Settings,
     states::GameState,
     systems::despawn_entity,
 };
@@ -28,8 +32,18 @@ use components::{
 };
 use events::*;
 use layer::Layer;
-use resources::interaction::{AudioCooldown, DragState, GridVisible, HoveredCell};
+use resources::clipboard::{Clipboard, Selection};
+use resources::interaction::{
+    AudioCooldown, AutoPause, CameraState, DragState, FastForwardInput, GridVisible, HoveredCell,
+    PaintState,
+};
 use resources::{
+    coloring::AgeGradient,
+    cycle::CycleTracker,
+    fast_forward::FastForwardJob,
+    history::EditHistory,
+    rewind::RewindBuffer,
+    stats::{GenerationRate, PopulationHistory, StatsVisible},
     timer::{SimulationTimer, SpaceKeyTimer},
     world::World,
 };
@@ -39,9 +53,23 @@ use systems::{
     audio::play_audios,
     cell_operations::*,
     chunk::{manage_chunks, update_grid_uniforms},
-    grid::{handle_grid_click, update_cell_highlight},
+    fast_forward::{
+        fast_forward_cancel_keyboard_handling, fast_forward_input_handling,
+        update_fast_forward_input,
+    },
+    grid::{handle_grid_click, update_cell_highlight, update_topology_border},
     input::*,
     screen::spawn_screen,
+    selection::{
+        cell_toggle_enabled, handle_clipboard, paste_click, selection_drag, update_paste_ghost,
+        update_selection_overlay,
+    },
+    session::{handle_session_requests, store_view_state},
+    settings::sync_view_settings,
+    stats::{
+        record_stats, spawn_stats_panel, update_census_text, update_population_graph,
+        update_stats_panel,
+    },
     viewport::update_camera_viewports,
 };
 
@@ -62,34 +90,60 @@ impl Plugin for GamePlugin {
                 setup_world_camera,
                 setup_resource,
                 spawn_screen,
+                spawn_stats_panel,
             )
                 .chain(),
         );
         app.add_systems(
             OnExit(GameState::Game),
             (
+                store_view_state,
                 despawn_entity::<OnGameScreen>,
                 despawn_entity::<BottomPanelCamera>,
                 despawn_entity::<WorldCamera>,
             ),
         );
+        // NOTE: セッションの保存・読み込みはメニュー画面から要求されるため、ステートを問わず処理する
+        app.add_systems(Update, handle_session_requests);
         app.add_systems(
             Update,
             (
                 manage_chunks,
-                game_input_keyboard_handling,
+                game_input_keyboard_handling
+                    .after(clipboard_keyboard_handling)
+                    .after(fast_forward_cancel_keyboard_handling),
+                fast_forward_cancel_keyboard_handling,
                 game_input_zoom_handling,
                 progress_generation,
                 progress_generation_trigger.run_if(in_state(SimulationState::Simulating)),
                 update_generation,
+                update_cycle_text.after(progress_generation),
+                update_rule_text,
+                update_engine_text,
                 reset_generation,
                 mouse_wheel_zoom,
-                handle_grid_click,
-                mouse_drag_pan.after(handle_grid_click),
+                handle_grid_click.run_if(cell_toggle_enabled),
+                mouse_drag_pan.after(paste_click),
                 update_cell_highlight,
             )
                 .run_if(in_state(GameState::Game)),
         );
+        app.add_systems(
+            Update,
+            (
+                selection_drag.after(handle_grid_click),
+                paste_click.after(handle_grid_click),
+                clipboard_keyboard_handling,
+                handle_clipboard.after(clipboard_keyboard_handling),
+                update_selection_overlay,
+                update_paste_ghost,
+                update_topology_border,
+                age_coloring_keyboard_handling,
+                heatmap_keyboard_handling,
+                paint_state_keyboard_handling,
+            )
+                .run_if(in_state(GameState::Game)),
+        );
         app.add_systems(
             Update,
             (
@@ -99,6 +153,24 @@ impl Plugin for GamePlugin {
                 update_toggle_button_text,
                 systems::slider::sync_slider_thumbs,
                 update_grid_uniforms,
+                fast_forward_input_handling,
+                fast_forward.after(fast_forward_input_handling),
+                run_fast_forward.after(fast_forward),
+                update_fast_forward_input.after(run_fast_forward),
+                undo_redo_keyboard_handling,
+                undo_redo.after(undo_redo_keyboard_handling),
+                step_back_keyboard_handling,
+                step_back.after(step_back_keyboard_handling),
+                sync_view_settings,
+                stats_keyboard_handling,
+                record_stats.after(progress_generation),
+                update_stats_panel
+                    .after(record_stats)
+                    .after(stats_keyboard_handling),
+                update_population_graph
+                    .after(record_stats)
+                    .after(stats_keyboard_handling),
+                update_census_text.after(stats_keyboard_handling),
             )
                 .run_if(in_state(GameState::Game)),
         );
@@ -106,11 +178,32 @@ impl Plugin for GamePlugin {
         app.init_resource::<HoveredCell>();
         app.init_resource::<AudioCooldown>();
         app.init_resource::<DragState>();
+        app.init_resource::<PaintState>();
+        app.init_resource::<World>();
+        app.init_resource::<AgeGradient>();
+        app.init_resource::<EditHistory>();
+        app.init_resource::<RewindBuffer>();
+        app.init_resource::<CycleTracker>();
+        app.init_resource::<PopulationHistory>();
+        app.init_resource::<GenerationRate>();
+        app.init_resource::<StatsVisible>();
+        app.init_resource::<Selection>();
+        app.init_resource::<Clipboard>();
+        app.init_resource::<Settings>();
+        app.init_resource::<CameraState>();
+        app.init_resource::<SessionStorage>();
+        app.init_resource::<SessionStatus>();
         app.init_state::<SimulationState>();
         app.add_message::<ProgressGenerationEvent>();
+        app.add_message::<FastForwardEvent>();
+        app.add_message::<StepBackEvent>();
         app.add_message::<GenerationResetEvent>();
         app.add_message::<WorldClearEvent>();
+        app.add_message::<UndoEvent>();
+        app.add_message::<RedoEvent>();
+        app.add_message::<ClipboardEvent>();
         app.add_message::<PlayAudioEvent>();
+        app.add_message::<SessionRequest>();
     }
 }
 
@@ -138,7 +231,13 @@ pub fn setup_bottom_panel_camera(mut commands: Commands, windows: Query<&Window>
 }
 
 /// ワールド描画用カメラを生成する
-pub fn setup_world_camera(mut commands: Commands, windows: Query<&Window>) {
+///
+/// 前回ゲーム画面を離れたとき（またはセッション読み込み時）の位置・拡大率を復元する。
+pub fn setup_world_camera(
+    mut commands: Commands,
+    windows: Query<&Window>,
+    camera_state: Res<CameraState>,
+) {
     let (pw, ph) = windows
         .single()
         .map(|w| (w.physical_width(), w.physical_height()))
@@ -157,30 +256,51 @@ pub fn setup_world_camera(mut commands: Commands, windows: Query<&Window>) {
             ..default()
         },
         Projection::Orthographic(OrthographicProjection {
-            scale: INITIAL_CAMERA_SCALE,
+            scale: camera_state.scale,
             ..OrthographicProjection::default_2d()
         }),
+        Transform::from_translation(camera_state.translation.extend(0.0)),
         WorldCamera,
         Layer::World.as_render_layer(),
     ));
 }
 
-/// Worldリソースとシミュレーションタイマーを初期化する
+/// メニューでの選択をワールドに反映し、画面ごとのリソースを初期化する
+///
+/// ワールド・編集履歴・クリップボードはプラグイン登録時に生成し、
+/// メニューとの往復で盤面が失われないよう画面遷移では作り直さない。
+/// メニューでパターンや Wireworld の回路が選択されていればスタンプモードを開始する。
+#[allow(clippy::too_many_arguments)]
 fn setup_resource(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
+    mut world: ResMut<World>,
+    mut clipboard: ResMut<Clipboard>,
     mut selected_pattern: ResMut<SelectedPattern>,
+    mut selected_circuit: ResMut<SelectedCircuit>,
+    selected_rule: Res<SelectedRule>,
+    selected_topology: Res<SelectedTopology>,
+    settings: Res<Settings>,
 ) {
-    let mut world = World::new();
+    world.set_rule(selected_rule.0);
+    world.set_topology(selected_topology.topology());
+    world.set_age_tracking(settings.age_coloring);
+    world.set_heatmap(settings.heatmap.then(|| settings.heatmap_config()));
 
-    // 選択されたパターンがあれば配置してリセット
     let pattern = selected_pattern.0;
     if pattern != LifePattern::None {
-        world.place_pattern(pattern.cells());
+        clipboard.start_stamp(pattern.cells());
         selected_pattern.0 = LifePattern::None;
     }
+    let circuit = selected_circuit.0;
+    if circuit != WireworldCircuit::None {
+        clipboard.start_state_stamp(&circuit.cells());
+        selected_circuit.0 = WireworldCircuit::None;
+    }
 
-    commands.insert_resource(world);
     commands.insert_resource(SimulationTimer::new(game_assets.tick_interval));
-    commands.insert_resource(GridVisible::default());
+    commands.insert_resource(GridVisible(settings.grid_visible));
+    commands.insert_resource(AutoPause(settings.auto_pause));
+    commands.insert_resource(FastForwardInput::default());
+    commands.insert_resource(FastForwardJob::default());
 }
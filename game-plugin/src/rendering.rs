@@ -7,13 +7,17 @@ use bevy::{
 };
 use common::consts::{
     CELL_ALIVE_RGB, CELL_DEAD_RGB, CELL_PIXELS, CELL_WORLD_SIZE, CHUNK_SIZE, CHUNK_TEX_SIZE,
-    CHUNK_WORLD_SIZE, GRID_LINE_RGB, GRID_LINE_SCREEN_WIDTH,
+    CHUNK_WORLD_SIZE, GRID_LINE_RGB, GRID_LINE_SCREEN_WIDTH, SELECTION_OVERLAY_COLOR,
+    TOPOLOGY_BORDER_COLOR,
 };
 
 use crate::components::chunk::Chunk;
-use crate::components::screen::{CellHighlight, OnGameScreen};
+use crate::components::screen::{
+    CellHighlight, OnGameScreen, PasteGhost, SelectionOverlay, TopologyBorder,
+};
 use crate::grid_material::{GridMaterial, GridUniforms};
 use crate::layer::Layer;
+use crate::resources::coloring::{AgeGradient, decaying_color, heat_overlay};
 use crate::resources::world::{ChunkKey, World};
 
 /// チャンクのワールド空間位置を計算する（メッシュ中心座標）
@@ -36,6 +40,7 @@ pub fn spawn_chunk_mesh(
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<GridMaterial>,
     world: &World,
+    gradient: &AgeGradient,
     chunk_key: ChunkKey,
     camera_scale: f32,
     grid_visible: bool,
@@ -43,7 +48,7 @@ pub fn spawn_chunk_mesh(
     let tex_size = CHUNK_TEX_SIZE;
     let mut data = vec![255u8; (tex_size * tex_size * 4) as usize];
 
-    write_chunk_to_image_data(&mut data, world, chunk_key);
+    write_chunk_to_image_data(&mut data, world, gradient, chunk_key);
 
     let mut image = Image::new(
         bevy::render::render_resource::Extent3d {
@@ -67,6 +72,7 @@ pub fn spawn_chunk_mesh(
             grid_visible: if grid_visible { 1.0 } else { 0.0 },
             chunk_cells: CHUNK_SIZE as f32,
             grid_line_width: GRID_LINE_SCREEN_WIDTH,
+            hex_rows: hex_rows(world),
             grid_color: LinearRgba::new(
                 GRID_LINE_RGB.0 as f32 / 255.0,
                 GRID_LINE_RGB.1 as f32 / 255.0,
@@ -91,6 +97,15 @@ pub fn spawn_chunk_mesh(
         .id()
 }
 
+/// 六角形近傍のルールなら奇数行をずらすシェーダーのフラグを返す
+pub fn hex_rows(world: &World) -> f32 {
+    if world.rule().neighborhood().is_hexagonal() {
+        1.0
+    } else {
+        0.0
+    }
+}
+
 /// マウスホバー時のセルハイライトスプライトを生成する
 pub fn spawn_cell_highlight(commands: &mut Commands) {
     commands.spawn((
@@ -106,34 +121,135 @@ pub fn spawn_cell_highlight(commands: &mut Commands) {
     ));
 }
 
+/// 範囲選択のオーバーレイを生成する（初期状態は非表示）
+pub fn spawn_selection_overlay(commands: &mut Commands) {
+    commands.spawn((
+        Sprite {
+            color: SELECTION_OVERLAY_COLOR,
+            custom_size: Some(Vec2::splat(CELL_WORLD_SIZE)),
+            ..default()
+        },
+        Visibility::Hidden,
+        Layer::World.as_render_layer(),
+        OnGameScreen,
+        SelectionOverlay,
+    ));
+}
+
+/// 有限トポロジーの境界線の4辺を生成する（初期状態は非表示）
+///
+/// 位置と太さはワールドのトポロジーとカメラの拡大率に合わせて毎フレーム更新する。
+pub fn spawn_topology_border(commands: &mut Commands) {
+    for edge in 0..4 {
+        commands.spawn((
+            Sprite {
+                color: TOPOLOGY_BORDER_COLOR,
+                custom_size: Some(Vec2::splat(CELL_WORLD_SIZE)),
+                ..default()
+            },
+            Visibility::Hidden,
+            Layer::World.as_render_layer(),
+            OnGameScreen,
+            TopologyBorder(edge),
+        ));
+    }
+}
+
+/// 貼り付けプレビューの親エンティティを生成する（初期状態は非表示）
+///
+/// 子のセルスプライトはクリップボードの内容に合わせて再生成する。
+pub fn spawn_paste_ghost(commands: &mut Commands) {
+    commands.spawn((
+        Transform::default(),
+        Visibility::Hidden,
+        Layer::World.as_render_layer(),
+        OnGameScreen,
+        PasteGhost,
+    ));
+}
+
 /// チャンクのセル状態をRGBAピクセルデータに書き込む
 ///
 /// チャンク内のセルデータのみをテクスチャに書き込む。
+/// セル状態はワールドのビットボードの行データから直接読み取る。
+/// ワールドがセルの年齢を追跡していれば、生存セルと死亡直後のセルを `gradient` で色分けする。
+/// Generations ルールの衰退中のセルは、状態ごとに異なる色で塗る。
+/// Wireworld などの多状態オートマトンでは、全ての状態をルールが持つ状態ごとの色で塗る。
+/// 活動度ヒートマップが有効なら、活動のあったセルにカラーランプの色を重ねる。
+/// 六角形近傍のルールでは奇数行を半セル右にずらし、行頭の半セルには西隣のチャンクの端のセルを塗る。
 /// グリッド線はシェーダーで描画するため、テクスチャには含まない。
-pub fn write_chunk_to_image_data(data: &mut [u8], world: &World, chunk_key: ChunkKey) {
+pub fn write_chunk_to_image_data(
+    data: &mut [u8],
+    world: &World,
+    gradient: &AgeGradient,
+    chunk_key: ChunkKey,
+) {
+    let rows = world.chunk(chunk_key);
+    let ages = world.ages();
+    let heatmap = world.heatmap().filter(|heatmap| !heatmap.is_empty());
+    let decaying: Vec<_> = world.decaying_cells().chunk_layers(chunk_key).collect();
+    let states = world.rule().states();
+    let multi_state = world.rule().multi_state();
+    let hexagonal = world.rule().neighborhood().is_hexagonal();
     let (cx, cy) = chunk_key;
-    let base_x = cx * CHUNK_SIZE;
-    let base_y = cy * CHUNK_SIZE;
     let tex_w = CHUNK_TEX_SIZE as usize;
     let cp = CELL_PIXELS as usize;
 
-    for tex_y in 0..tex_w {
-        for tex_x in 0..tex_w {
-            let offset = (tex_y * tex_w + tex_x) * 4;
-
-            let cell_x = base_x + (tex_x / cp).min(CHUNK_SIZE as usize - 1) as i32;
-            let cell_y = base_y + (tex_y / cp).min(CHUNK_SIZE as usize - 1) as i32;
-
-            let (r, g, b) = if world.is_alive(cell_x, cell_y) {
-                CELL_ALIVE_RGB
-            } else {
-                CELL_DEAD_RGB
-            };
+    let cell_color = |x: i32, y: i32, alive: bool, decaying_state: Option<u8>| {
+        let color = match (multi_state, decaying_state, ages) {
+            (Some(rule), state, _) => rule.color(state.unwrap_or(u8::from(alive))),
+            (None, Some(state), _) => decaying_color(state, states),
+            (None, None, Some(ages)) if alive || ages.has_trail() => gradient.color(ages.get(x, y)),
+            _ if alive => CELL_ALIVE_RGB,
+            _ => CELL_DEAD_RGB,
+        };
+        match heatmap {
+            Some(heatmap) => heat_overlay(color, heatmap.intensity(x, y)),
+            None => color,
+        }
+    };
+    // セル1個分の高さで、指定範囲の列のピクセルを塗る
+    let mut fill = |local_y: usize, tex_xs: std::ops::Range<usize>, (r, g, b): (u8, u8, u8)| {
+        for tex_y in local_y * cp..(local_y + 1) * cp {
+            for tex_x in tex_xs.clone() {
+                let offset = (tex_y * tex_w + tex_x) * 4;
+                data[offset] = r;
+                data[offset + 1] = g;
+                data[offset + 2] = b;
+                data[offset + 3] = 255;
+            }
+        }
+    };
 
-            data[offset] = r;
-            data[offset + 1] = g;
-            data[offset + 2] = b;
-            data[offset + 3] = 255;
+    for local_y in 0..CHUNK_SIZE as usize {
+        let row = rows.map_or(0, |rows| rows[local_y]);
+        let y = cy * CHUNK_SIZE + local_y as i32;
+        // NOTE: チャンクの辺は偶数セルなので、ローカル行の偶奇はワールド行の偶奇と一致する
+        let shift = if hexagonal && local_y % 2 == 1 {
+            cp / 2
+        } else {
+            0
+        };
+        if shift > 0 {
+            let x = cx * CHUNK_SIZE - 1;
+            let state = world.cell_state(x, y);
+            let decaying_state = (state >= 2).then_some(state);
+            fill(
+                local_y,
+                0..shift,
+                cell_color(x, y, state == 1, decaying_state),
+            );
+        }
+        for local_x in 0..CHUNK_SIZE as usize {
+            let alive = row >> local_x & 1 == 1;
+            let x = cx * CHUNK_SIZE + local_x as i32;
+            let decaying_state = decaying
+                .iter()
+                .find(|(_, rows)| rows[local_y] >> local_x & 1 == 1)
+                .map(|&(state, _)| state);
+            let start = local_x * cp + shift;
+            let end = ((local_x + 1) * cp + shift).min(tex_w);
+            fill(local_y, start..end, cell_color(x, y, alive, decaying_state));
         }
     }
 }
@@ -143,8 +259,13 @@ mod tests {
     use super::*;
     use crate::resources::world::World;
     use common::consts::{
-        CELL_ALIVE_RGB, CELL_DEAD_RGB, CELL_PIXELS, CHUNK_TEX_SIZE, CHUNK_WORLD_SIZE,
+        CELL_ALIVE_RGB, CELL_DEAD_RGB, CELL_PIXELS, CELL_TRAIL_RGB, CHUNK_TEX_SIZE,
+        CHUNK_WORLD_SIZE,
     };
+    use life_core::age::CellAge;
+    use life_core::consts::AGE_TRAIL_LENGTH;
+    use life_core::heatmap::HeatmapConfig;
+    use life_core::rules::Rule;
 
     fn pixel_rgb(data: &[u8], tex_width: usize, tx: usize, ty: usize) -> (u8, u8, u8) {
         let offset = (ty * tex_width + tx) * 4;
@@ -153,43 +274,178 @@ mod tests {
 
     #[test]
     fn first_pixel_is_dead_cell() {
-        let world = World::new();
+        let world = World::default();
         let tex_w = CHUNK_TEX_SIZE as usize;
         let mut data = vec![0u8; tex_w * tex_w * 4];
 
-        write_chunk_to_image_data(&mut data, &world, (0, 0));
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
 
         assert_eq!(pixel_rgb(&data, tex_w, 0, 0), CELL_DEAD_RGB);
     }
 
     #[test]
     fn alive_cell_pixel() {
-        let mut world = World::new();
+        let mut world = World::default();
         world.toggle_cell(0, 0);
 
         let tex_w = CHUNK_TEX_SIZE as usize;
         let mut data = vec![0u8; tex_w * tex_w * 4];
 
-        write_chunk_to_image_data(&mut data, &world, (0, 0));
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
 
         assert_eq!(pixel_rgb(&data, tex_w, 0, 0), CELL_ALIVE_RGB);
     }
 
     #[test]
     fn second_cell_starts_at_cell_pixels_offset() {
-        let mut world = World::new();
+        let mut world = World::default();
         world.toggle_cell(1, 0);
 
         let tex_w = CHUNK_TEX_SIZE as usize;
         let mut data = vec![0u8; tex_w * tex_w * 4];
 
-        write_chunk_to_image_data(&mut data, &world, (0, 0));
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
 
         let cp = CELL_PIXELS as usize;
         assert_eq!(pixel_rgb(&data, tex_w, cp, 0), CELL_ALIVE_RGB);
         assert_eq!(pixel_rgb(&data, tex_w, cp - 1, 0), CELL_DEAD_RGB);
     }
 
+    #[test]
+    fn age_tracking_colors_cells_by_age() {
+        let mut world = World::default();
+        world.place_pattern(&[(0, 0), (1, 0), (0, 1), (1, 1), (10, 0)]);
+        world.set_age_tracking(true);
+        world.advance_generations(40);
+        world.toggle_cell(5, 5);
+
+        let gradient = AgeGradient::default();
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &gradient, (0, 0));
+
+        let cp = CELL_PIXELS as usize;
+        let old = gradient.color(CellAge::Alive(40));
+        let newborn = gradient.color(CellAge::Alive(0));
+        assert_ne!(old, newborn);
+        assert_ne!(old, CELL_ALIVE_RGB);
+        // 長く生き残ったブロックと、置いたばかりのセルは別の色
+        assert_eq!(pixel_rgb(&data, tex_w, 0, 0), old);
+        assert_eq!(pixel_rgb(&data, tex_w, cp + cp - 1, cp + cp - 1), old);
+        assert_eq!(pixel_rgb(&data, tex_w, 5 * cp, 5 * cp), newborn);
+        assert_eq!(pixel_rgb(&data, tex_w, 3 * cp, 3 * cp), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn dead_cell_leaves_fading_trail() {
+        let mut world = World::default();
+        world.toggle_cell(3, 0);
+        world.set_age_tracking(true);
+        world.progress_generation();
+
+        let gradient = AgeGradient::default();
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &gradient, (0, 0));
+        let cp = CELL_PIXELS as usize;
+        let trail = pixel_rgb(&data, tex_w, 3 * cp, 0);
+        assert_eq!(trail, CELL_TRAIL_RGB);
+
+        world.advance_generations(AGE_TRAIL_LENGTH as u64);
+        write_chunk_to_image_data(&mut data, &world, &gradient, (0, 0));
+        assert_eq!(pixel_rgb(&data, tex_w, 3 * cp, 0), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn heatmap_overlays_active_cells() {
+        let mut world = World::default();
+        world.place_pattern(&[(0, 1), (1, 1), (2, 1)]);
+        world.set_heatmap(Some(HeatmapConfig::default()));
+        for _ in 0..4 {
+            world.progress_generation();
+        }
+
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
+
+        let cp = CELL_PIXELS as usize;
+        let heatmap = world.heatmap().unwrap();
+        // 毎世代反転するブリンカーの端は色が重なり、中心と離れたセルは元の色のまま
+        assert_eq!(
+            pixel_rgb(&data, tex_w, 0, cp),
+            heat_overlay(CELL_ALIVE_RGB, heatmap.intensity(0, 1))
+        );
+        assert_ne!(pixel_rgb(&data, tex_w, 0, cp), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, cp, cp), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, 9 * cp, 9 * cp), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn decaying_cells_are_colored_by_state() {
+        let mut world = World::default();
+        world.set_rule(Rule::parse("B2/S/C4").unwrap());
+        world.set_cell_state(0, 0, 1);
+        world.set_cell_state(1, 0, 2);
+        world.set_cell_state(2, 0, 3);
+
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
+
+        let cp = CELL_PIXELS as usize;
+        assert_eq!(pixel_rgb(&data, tex_w, 0, 0), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, cp, 0), decaying_color(2, 4));
+        assert_eq!(pixel_rgb(&data, tex_w, 2 * cp, 0), decaying_color(3, 4));
+        assert_eq!(pixel_rgb(&data, tex_w, 3 * cp, 0), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn multi_state_cells_use_rule_palette() {
+        let mut world = World::default();
+        world.set_rule(Rule::parse("Wireworld").unwrap());
+        world.place_states(&[((0, 0), 1), ((1, 0), 2), ((2, 0), 3)]);
+
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
+
+        let rule = world.rule().multi_state().unwrap();
+        let cp = CELL_PIXELS as usize;
+        for state in 0..4 {
+            assert_eq!(
+                pixel_rgb(&data, tex_w, (state as usize + 3) % 4 * cp, 0),
+                rule.color(state)
+            );
+        }
+    }
+
+    #[test]
+    fn hexagonal_rule_shifts_odd_rows_by_half_cell() {
+        let mut world = World::default();
+        world.set_rule(Rule::parse("B2/S34H").unwrap());
+        world.place_pattern(&[(0, 0), (0, 1), (-1, 1)]);
+
+        let tex_w = CHUNK_TEX_SIZE as usize;
+        let mut data = vec![0u8; tex_w * tex_w * 4];
+
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (0, 0));
+
+        let cp = CELL_PIXELS as usize;
+        // 偶数行はずらさない
+        assert_eq!(pixel_rgb(&data, tex_w, 0, 0), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, cp, 0), CELL_DEAD_RGB);
+        // 奇数行は半セル右にずれ、行頭には西隣のチャンクのセル (-1, 1) が見える
+        assert_eq!(pixel_rgb(&data, tex_w, 0, cp), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, cp + cp / 2 - 1, cp), CELL_ALIVE_RGB);
+        assert_eq!(pixel_rgb(&data, tex_w, cp + cp / 2, cp), CELL_DEAD_RGB);
+    }
+
     // === chunk_world_pos テスト ===
 
     #[test]
@@ -224,13 +480,13 @@ mod tests {
 
     #[test]
     fn negative_chunk_alive_cell() {
-        let mut world = World::new();
+        let mut world = World::default();
         world.toggle_cell(-1, -1);
 
         let tex_w = CHUNK_TEX_SIZE as usize;
         let mut data = vec![0u8; tex_w * tex_w * 4];
 
-        write_chunk_to_image_data(&mut data, &world, (-1, -1));
+        write_chunk_to_image_data(&mut data, &world, &AgeGradient::default(), (-1, -1));
 
         // セル(-1,-1)はチャンク(-1,-1)のローカル座標(63,63)
         let cp = CELL_PIXELS as usize;
@@ -1,6 +1,12 @@
 //! ゲーム画面で使用するリソース群
 
+pub mod clipboard;
+pub mod coloring;
+pub mod cycle;
+pub mod fast_forward;
+pub mod history;
 pub mod interaction;
-pub mod simulation;
+pub mod rewind;
+pub mod stats;
 pub mod timer;
 pub mod world;
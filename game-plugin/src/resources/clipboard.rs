@@ -0,0 +1,453 @@
+//! 範囲選択とクリップボード
+//!
+//! Shift+ドラッグで選択したグリッド上の矩形と、
+//! コピー・切り取りしたセルを保持する内部クリップボードを管理する。
+//! 生存セルのほか、Generations ルールの衰退中のセルや多状態オートマトンの状態2以上のセルも状態ごと保持する。
+
+use bevy::prelude::Resource;
+use common::formats::bounding_box;
+use common::patterns::CellTransform;
+
+use life_core::world::World;
+
+pub use life_core::rect::GridRect;
+
+/// Shift+ドラッグによる範囲選択の状態を管理するリソース
+#[derive(Resource, Debug, Default)]
+pub struct Selection {
+    /// ドラッグを開始したセル
+    anchor: Option<(i32, i32)>,
+    /// 選択中の矩形
+    rect: Option<GridRect>,
+    /// ドラッグ中か
+    dragging: bool,
+}
+
+impl Selection {
+    /// 指定セルから範囲選択のドラッグを開始する
+    pub fn begin(&mut self, cell: (i32, i32)) {
+        self.anchor = Some(cell);
+        self.rect = Some(GridRect::from_corners(cell, cell));
+        self.dragging = true;
+    }
+
+    /// ドラッグ中の矩形をカーソル位置のセルまで広げる
+    pub fn update(&mut self, cell: (i32, i32)) {
+        if let (true, Some(anchor)) = (self.dragging, self.anchor) {
+            self.rect = Some(GridRect::from_corners(anchor, cell));
+        }
+    }
+
+    /// ドラッグを終了し、選択範囲を確定する
+    pub fn finish(&mut self) {
+        self.dragging = false;
+    }
+
+    /// 選択範囲を指定した矩形にする
+    pub fn select(&mut self, rect: GridRect) {
+        self.anchor = Some(rect.min);
+        self.rect = Some(rect);
+        self.dragging = false;
+    }
+
+    /// 選択を解除する
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 選択中の矩形を返す
+    pub fn rect(&self) -> Option<GridRect> {
+        self.rect
+    }
+
+    /// 範囲選択のドラッグ中かを返す
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+}
+
+/// 貼り付け・スタンプで配置するセルのまとまり
+///
+/// セル座標は矩形の左上を原点とした相対座標で保持する。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct CellBlock {
+    /// 生存セルの相対座標（ソート済み）
+    cells: Vec<(i32, i32)>,
+    /// 状態2以上のセルの相対座標と状態（ソート済み）
+    states: Vec<((i32, i32), u8)>,
+    /// 矩形の幅
+    width: u32,
+    /// 矩形の高さ
+    height: u32,
+}
+
+impl CellBlock {
+    /// 状態付きのセル座標列から、バウンディングボックスを矩形とするまとまりを生成する
+    ///
+    /// 状態0のセルは含めない。
+    fn from_states(cells: &[((i32, i32), u8)]) -> Self {
+        let coords: Vec<_> = cells
+            .iter()
+            .filter(|&&(_, state)| state > 0)
+            .map(|&(cell, _)| cell)
+            .collect();
+        let Some((min_x, min_y, max_x, max_y)) = bounding_box(&coords) else {
+            return Self::default();
+        };
+        let mut block = Self {
+            width: max_x.abs_diff(min_x) + 1,
+            height: max_y.abs_diff(min_y) + 1,
+            ..Self::default()
+        };
+        for &((x, y), state) in cells {
+            let cell = (x - min_x, y - min_y);
+            match state {
+                0 => {}
+                1 => block.cells.push(cell),
+                _ => block.states.push((cell, state)),
+            }
+        }
+        block.sort();
+        block
+    }
+
+    /// 矩形ごと回転・反転し、変換後の矩形の左上を原点に保つ
+    fn transform(&mut self, transform: CellTransform) {
+        let (cells, width, height) = transform.apply_in_box(&self.cells, self.width, self.height);
+        let coords: Vec<_> = self.states.iter().map(|&(cell, _)| cell).collect();
+        let (coords, _, _) = transform.apply_in_box(&coords, self.width, self.height);
+        self.states = coords
+            .into_iter()
+            .zip(self.states.iter().map(|&(_, state)| state))
+            .collect();
+        self.cells = cells;
+        self.width = width;
+        self.height = height;
+        self.sort();
+    }
+
+    /// セル座標を並べ替え、重複を取り除く
+    fn sort(&mut self) {
+        self.cells.sort_unstable();
+        self.cells.dedup();
+        self.states.sort_unstable();
+        self.states.dedup_by_key(|&mut (cell, _)| cell);
+    }
+
+    /// セルを1つも含まないかを返す
+    fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.states.is_empty()
+    }
+}
+
+/// コピー・切り取りしたセルを保持する内部クリップボード
+///
+/// 貼り付け（1回配置して終了）と、メニューで選んだパターンのスタンプ
+/// （クリックごとに配置し続ける）の配置状態も管理する。
+/// 配置中はその内容をゴーストとしてプレビュー表示する。
+#[derive(Resource, Debug, Default)]
+pub struct Clipboard {
+    /// コピー・切り取りした内容
+    copied: CellBlock,
+    /// スタンプモードで配置中のパターン
+    stamp: Option<CellBlock>,
+    /// コピーした内容の貼り付け位置を選択中か
+    pasting: bool,
+}
+
+impl Clipboard {
+    /// ワールドの矩形内の生存セルと状態2以上のセルをコピーし、配置中の操作を終了する
+    ///
+    /// セルがない範囲でもコピーし、空のクリップボードになる。
+    pub fn copy_from(&mut self, world: &World, rect: GridRect) {
+        let (ox, oy) = rect.min;
+        let cells = world
+            .cells_in_rect(rect)
+            .into_iter()
+            .map(|(x, y)| (x - ox, y - oy))
+            .collect();
+        let states = world
+            .decaying_cells()
+            .iter()
+            .filter(|&((x, y), _)| rect.contains(x, y))
+            .map(|((x, y), state)| ((x - ox, y - oy), state))
+            .collect();
+        self.copied = CellBlock {
+            cells,
+            states,
+            width: rect.width(),
+            height: rect.height(),
+        };
+        self.copied.sort();
+        self.stop_placing();
+    }
+
+    /// 配置中（スタンプ中ならスタンプ、それ以外はコピーした内容）のまとまりを返す
+    fn active(&self) -> &CellBlock {
+        self.stamp.as_ref().unwrap_or(&self.copied)
+    }
+
+    /// 配置中の生存セルの相対座標を返す
+    pub fn cells(&self) -> &[(i32, i32)] {
+        &self.active().cells
+    }
+
+    /// 配置中の状態2以上のセルの相対座標と状態を返す
+    pub fn states(&self) -> &[((i32, i32), u8)] {
+        &self.active().states
+    }
+
+    /// 何もコピーされていないかを返す
+    pub fn is_empty(&self) -> bool {
+        self.copied.is_empty()
+    }
+
+    /// 左上を `origin` に置いたときの配置先の矩形を返す
+    pub fn rect_at(&self, origin: (i32, i32)) -> GridRect {
+        let block = self.active();
+        GridRect::from_origin_size(origin, block.width, block.height)
+    }
+
+    /// 左上を `origin` に置いたときの全てのセルの絶対座標と状態（生存セルは1）を返す
+    pub fn states_at(&self, origin: (i32, i32)) -> Vec<((i32, i32), u8)> {
+        let block = self.active();
+        block
+            .cells
+            .iter()
+            .map(|&cell| (cell, 1))
+            .chain(block.states.iter().copied())
+            .map(|((x, y), state)| ((x + origin.0, y + origin.1), state))
+            .collect()
+    }
+
+    /// 配置中のセルを回転・反転する
+    ///
+    /// 矩形ごと変換し、変換後の矩形の左上を原点に保つ。
+    pub fn transform(&mut self, transform: CellTransform) {
+        match &mut self.stamp {
+            Some(stamp) => stamp.transform(transform),
+            None => self.copied.transform(transform),
+        }
+    }
+
+    /// コピーした内容の貼り付け位置の選択を開始する。クリップボードが空なら何もしない
+    pub fn start_paste(&mut self) {
+        self.stamp = None;
+        self.pasting = !self.is_empty();
+    }
+
+    /// パターンのスタンプモードを開始する。セルが空なら何もしない
+    ///
+    /// パターンはバウンディングボックスの左上を原点として配置する。
+    pub fn start_stamp(&mut self, cells: &[(i32, i32)]) {
+        let cells: Vec<_> = cells.iter().map(|&cell| (cell, 1)).collect();
+        self.start_state_stamp(&cells);
+    }
+
+    /// 状態付きのセルのスタンプモードを開始する。セルが空なら何もしない
+    ///
+    /// Wireworld の回路のように状態2以上のセルを含むパターンに使う。
+    pub fn start_state_stamp(&mut self, cells: &[((i32, i32), u8)]) {
+        let block = CellBlock::from_states(cells);
+        if block.is_empty() {
+            return;
+        }
+        self.stamp = Some(block);
+        self.pasting = false;
+    }
+
+    /// 貼り付け・スタンプを終了する
+    pub fn stop_placing(&mut self) {
+        self.stamp = None;
+        self.pasting = false;
+    }
+
+    /// 貼り付け・スタンプの配置位置を選択中かを返す
+    pub fn is_placing(&self) -> bool {
+        self.pasting || self.stamp.is_some()
+    }
+
+    /// スタンプモード中かを返す
+    pub fn is_stamping(&self) -> bool {
+        self.stamp.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::history::EditHistory;
+    use common::patterns::{LifePattern, WireworldCircuit};
+    use life_core::rules::Rule;
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn selection_drag_follows_cursor() {
+        let mut selection = Selection::default();
+        selection.begin((5, 5));
+        selection.update((2, 8));
+        assert!(selection.is_dragging());
+        assert_eq!(
+            selection.rect(),
+            Some(GridRect::from_corners((2, 5), (5, 8)))
+        );
+        selection.finish();
+        selection.update((100, 100));
+        assert!(!selection.is_dragging());
+        assert_eq!(
+            selection.rect(),
+            Some(GridRect::from_corners((2, 5), (5, 8)))
+        );
+    }
+
+    #[test]
+    fn copy_stores_cells_relative_to_rect_origin() {
+        let mut world = World::new();
+        world.place_pattern(&[(-3, -3), (-2, -1), (10, 10)]);
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, GridRect::from_corners((-3, -3), (0, 0)));
+        assert_eq!(clipboard.cells(), &[(0, 0), (1, 2)]);
+        assert_eq!(
+            clipboard.rect_at((0, 0)),
+            GridRect::from_corners((0, 0), (3, 3))
+        );
+    }
+
+    #[test]
+    fn paste_places_copy_at_new_origin() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, GridRect::from_corners((0, 0), (2, 0)));
+        world.place_states(&clipboard.states_at((-70, 5)));
+        assert_eq!(
+            sorted(world.live_cells()),
+            vec![(-70, 5), (-69, 5), (-68, 5), (0, 0), (1, 0), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn cut_and_paste_undo_as_single_steps() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        world.place_pattern(&[(0, 0), (1, 1), (5, 5)]);
+        let rect = GridRect::from_corners((0, 0), (1, 1));
+
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, rect);
+        let diff = world.erase_rect(rect);
+        history.record(diff, &world);
+        assert_eq!(world.live_cells(), vec![(5, 5)]);
+
+        let diff = world.place_states(&clipboard.states_at((10, 10)));
+        history.record(diff, &world);
+        assert_eq!(sorted(world.live_cells()), vec![(5, 5), (10, 10), (11, 11)]);
+
+        assert!(history.undo(&mut world));
+        assert_eq!(world.live_cells(), vec![(5, 5)]);
+        assert!(history.undo(&mut world));
+        assert_eq!(sorted(world.live_cells()), vec![(0, 0), (1, 1), (5, 5)]);
+    }
+
+    #[test]
+    fn transform_rotates_cells_and_box() {
+        let mut world = World::new();
+        // 3×1 の横一列（右端が空き）
+        world.place_pattern(&[(0, 0), (1, 0)]);
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, GridRect::from_corners((0, 0), (2, 0)));
+
+        clipboard.transform(CellTransform::RotateClockwise);
+        assert_eq!(clipboard.cells(), &[(0, 0), (0, 1)]);
+        assert_eq!(
+            clipboard.rect_at((5, 5)),
+            GridRect::from_corners((5, 5), (5, 7))
+        );
+
+        clipboard.transform(CellTransform::FlipVertical);
+        assert_eq!(clipboard.cells(), &[(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn stamp_places_pattern_repeatedly_without_touching_copy() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0)]);
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, GridRect::from_corners((0, 0), (1, 1)));
+
+        clipboard.start_stamp(LifePattern::Glider.cells());
+        assert!(clipboard.is_placing());
+        assert!(clipboard.is_stamping());
+        // 原点中心のグライダーは左上を原点に正規化される
+        assert_eq!(clipboard.cells(), &[(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]);
+        assert_eq!(
+            clipboard.rect_at((10, 10)),
+            GridRect::from_corners((10, 10), (12, 12))
+        );
+        world.place_states(&clipboard.states_at((10, 10)));
+        world.place_states(&clipboard.states_at((20, 10)));
+        assert_eq!(world.live_cells().len(), 11);
+
+        // 変換はスタンプにのみ適用される
+        clipboard.transform(CellTransform::FlipHorizontal);
+        clipboard.stop_placing();
+        assert!(!clipboard.is_placing());
+        clipboard.start_paste();
+        assert_eq!(clipboard.cells(), &[(0, 0)]);
+        assert_eq!(
+            clipboard.rect_at((0, 0)),
+            GridRect::from_corners((0, 0), (1, 1))
+        );
+    }
+
+    #[test]
+    fn copy_and_stamp_keep_cell_states() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("Wireworld").unwrap());
+        world.place_states(&[((0, 0), 3), ((1, 0), 1), ((2, 0), 2), ((9, 9), 3)]);
+        let mut clipboard = Clipboard::default();
+        clipboard.copy_from(&world, GridRect::from_corners((0, 0), (2, 0)));
+        assert_eq!(clipboard.cells(), &[(1, 0)]);
+        assert_eq!(clipboard.states(), &[((0, 0), 3), ((2, 0), 2)]);
+
+        clipboard.start_paste();
+        clipboard.transform(CellTransform::RotateClockwise);
+        assert_eq!(clipboard.states(), &[((0, 0), 3), ((0, 2), 2)]);
+        let mut placed = clipboard.states_at((5, 5));
+        placed.sort_unstable();
+        assert_eq!(placed, vec![((5, 5), 3), ((5, 6), 1), ((5, 7), 2)]);
+
+        clipboard.start_state_stamp(&WireworldCircuit::Clock.cells());
+        assert!(clipboard.is_stamping());
+        assert_eq!(
+            clipboard.states().len() + clipboard.cells().len(),
+            WireworldCircuit::Clock.cells().len()
+        );
+    }
+
+    #[test]
+    fn start_stamp_with_empty_pattern_does_nothing() {
+        let mut clipboard = Clipboard::default();
+        clipboard.start_stamp(LifePattern::None.cells());
+        assert!(!clipboard.is_placing());
+    }
+
+    #[test]
+    fn start_paste_requires_content() {
+        let mut clipboard = Clipboard::default();
+        clipboard.start_paste();
+        assert!(!clipboard.is_placing());
+
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0)]);
+        clipboard.copy_from(&world, GridRect::from_corners((0, 0), (0, 0)));
+        clipboard.start_paste();
+        assert!(clipboard.is_placing());
+        clipboard.stop_placing();
+        assert!(!clipboard.is_placing());
+    }
+}
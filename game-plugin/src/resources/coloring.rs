@@ -0,0 +1,161 @@
+//! セルの年齢による色分け、Generations の衰退中のセルと活動度ヒートマップの色
+
+use bevy::prelude::Resource;
+use common::consts::{
+    CELL_AGE_GRADIENT, CELL_ALIVE_RGB, CELL_DEAD_RGB, CELL_DECAYING_RGB, CELL_TRAIL_RGB,
+    HEATMAP_OVERLAY_ALPHA, HEATMAP_RAMP,
+};
+use life_core::age::CellAge;
+use life_core::consts::AGE_TRAIL_LENGTH;
+
+/// セルの年齢を色に変換するグラデーションのリソース
+///
+/// 生存セルは年齢の区切り点の間を線形補間した色になり、最後の区切り点より古いセルはその色のままになる。
+/// 死亡直後のセルは `trail` から死亡セルの色へ、`AGE_TRAIL_LENGTH` 世代かけて薄れていく。
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct AgeGradient {
+    /// 年齢の昇順に並べた区切り点（年齢, RGB）
+    stops: Vec<(u32, (u8, u8, u8))>,
+    /// 死亡した直後のセルの色
+    trail: (u8, u8, u8),
+}
+
+impl Default for AgeGradient {
+    fn default() -> Self {
+        Self::new(CELL_AGE_GRADIENT.to_vec(), CELL_TRAIL_RGB)
+    }
+}
+
+impl AgeGradient {
+    /// 区切り点と軌跡の色からグラデーションを生成する
+    ///
+    /// 区切り点は年齢順に並べ替える。空なら全ての生存セルを `CELL_ALIVE_RGB` で塗る。
+    pub fn new(mut stops: Vec<(u32, (u8, u8, u8))>, trail: (u8, u8, u8)) -> Self {
+        stops.sort_by_key(|&(age, _)| age);
+        if stops.is_empty() {
+            stops.push((0, CELL_ALIVE_RGB));
+        }
+        Self { stops, trail }
+    }
+
+    /// セルの年齢による状態に対応する色を返す
+    pub fn color(&self, age: CellAge) -> (u8, u8, u8) {
+        match age {
+            CellAge::Dead => CELL_DEAD_RGB,
+            CellAge::Alive(age) => self.alive_color(age),
+            CellAge::Fading(since) => {
+                let t = since.saturating_sub(1) as f32 / AGE_TRAIL_LENGTH as f32;
+                lerp_rgb(self.trail, CELL_DEAD_RGB, t)
+            }
+        }
+    }
+
+    /// 生存セルの年齢に対応する色を区切り点から補間する
+    fn alive_color(&self, age: u32) -> (u8, u8, u8) {
+        let next = self.stops.partition_point(|&(stop, _)| stop <= age);
+        match (self.stops.get(next.wrapping_sub(1)), self.stops.get(next)) {
+            (Some(&(from_age, from)), Some(&(to_age, to))) => {
+                let t = (age - from_age) as f32 / (to_age - from_age) as f32;
+                lerp_rgb(from, to, t)
+            }
+            (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => CELL_ALIVE_RGB,
+        }
+    }
+}
+
+/// 状態数 `states` のルールで衰退中の状態 `state`（2以上）のセルの色を返す
+///
+/// 状態2が `CELL_DECAYING_RGB` で、死亡直前の状態ほど死亡セルの色に近づく。
+pub fn decaying_color(state: u8, states: u8) -> (u8, u8, u8) {
+    // NOTE: 最後の状態でも死亡セルと見分けられるよう、死亡セルの色の手前までで止める
+    let t = state.saturating_sub(2) as f32 / states.saturating_sub(1).max(2) as f32;
+    lerp_rgb(CELL_DECAYING_RGB, CELL_DEAD_RGB, t)
+}
+
+/// セルの色に活動度（0.0〜1.0）に対応するカラーランプの色を重ねる
+///
+/// 活動度が0のセルは元の色のまま返す。
+pub fn heat_overlay(base: (u8, u8, u8), intensity: f32) -> (u8, u8, u8) {
+    if intensity <= 0.0 {
+        return base;
+    }
+    let next = HEATMAP_RAMP.partition_point(|&(stop, _)| stop <= intensity);
+    let heat = match (
+        HEATMAP_RAMP.get(next.wrapping_sub(1)),
+        HEATMAP_RAMP.get(next),
+    ) {
+        (Some(&(from_stop, from)), Some(&(to_stop, to))) => {
+            lerp_rgb(from, to, (intensity - from_stop) / (to_stop - from_stop))
+        }
+        (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+        (None, None) => base,
+    };
+    lerp_rgb(base, heat, HEATMAP_OVERLAY_ALPHA)
+}
+
+/// 2色を `t`（0.0〜1.0）で線形補間する
+fn lerp_rgb(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient() -> AgeGradient {
+        AgeGradient::new(vec![(10, (100, 0, 0)), (0, (0, 0, 200))], (50, 50, 50))
+    }
+
+    #[test]
+    fn alive_color_interpolates_between_stops() {
+        let gradient = gradient();
+        assert_eq!(gradient.color(CellAge::Alive(0)), (0, 0, 200));
+        assert_eq!(gradient.color(CellAge::Alive(5)), (50, 0, 100));
+        assert_eq!(gradient.color(CellAge::Alive(10)), (100, 0, 0));
+        assert_eq!(gradient.color(CellAge::Alive(1000)), (100, 0, 0));
+    }
+
+    #[test]
+    fn trail_fades_to_dead_color() {
+        let gradient = gradient();
+        assert_eq!(gradient.color(CellAge::Fading(1)), (50, 50, 50));
+        assert_eq!(
+            gradient.color(CellAge::Fading(AGE_TRAIL_LENGTH + 1)),
+            CELL_DEAD_RGB
+        );
+        assert_eq!(gradient.color(CellAge::Dead), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn decaying_color_fades_towards_dead_color() {
+        assert_eq!(decaying_color(2, 3), CELL_DECAYING_RGB);
+        assert_eq!(decaying_color(2, 8), CELL_DECAYING_RGB);
+        let colors: Vec<_> = (2..8).map(|state| decaying_color(state, 8)).collect();
+        assert!(colors.windows(2).all(|pair| pair[0] != pair[1]));
+        assert_ne!(decaying_color(7, 8), CELL_DEAD_RGB);
+    }
+
+    #[test]
+    fn heat_overlay_follows_ramp() {
+        assert_eq!(heat_overlay(CELL_DEAD_RGB, 0.0), CELL_DEAD_RGB);
+        let (_, cold) = HEATMAP_RAMP[0];
+        let (_, hot) = HEATMAP_RAMP[HEATMAP_RAMP.len() - 1];
+        assert_eq!(
+            heat_overlay(CELL_DEAD_RGB, 1.0),
+            lerp_rgb(CELL_DEAD_RGB, hot, HEATMAP_OVERLAY_ALPHA)
+        );
+        assert_eq!(
+            heat_overlay(CELL_DEAD_RGB, 1e-6),
+            lerp_rgb(CELL_DEAD_RGB, cold, HEATMAP_OVERLAY_ALPHA)
+        );
+    }
+
+    #[test]
+    fn empty_gradient_uses_alive_color() {
+        let gradient = AgeGradient::new(Vec::new(), CELL_TRAIL_RGB);
+        assert_eq!(gradient.color(CellAge::Alive(7)), CELL_ALIVE_RGB);
+    }
+}
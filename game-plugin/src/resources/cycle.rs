@@ -0,0 +1,83 @@
+//! 周期的な振る舞いの検出状態
+
+use bevy::prelude::Resource;
+use life_core::analysis::{Behavior, CycleDetector};
+use life_core::world::World;
+
+/// ワールドの固定物体・振動子・移動物体への収束を検出するリソース
+///
+/// 世代を1つ進めるたびにセル集合を記録する。記録は `World` のリビジョンで現在の状態と
+/// 結び付けており、編集・巻き戻し・早送りなどで状態が変わった場合は記録をやり直す。
+#[derive(Resource, Debug, Default)]
+pub struct CycleTracker {
+    detector: CycleDetector,
+    /// 最後に記録した直後のワールドのリビジョン
+    revision: Option<u64>,
+}
+
+impl CycleTracker {
+    /// 現在のワールドについて検出済みの振る舞いを返す
+    ///
+    /// 記録後にワールドが変更されていれば `None` を返す。
+    pub fn behavior(&self, world: &World) -> Option<Behavior> {
+        if self.revision == Some(world.revision()) {
+            self.detector.behavior()
+        } else {
+            None
+        }
+    }
+
+    /// 記録後にワールドが変更されていれば記録をやり直し、現在の世代を起点として記録する
+    ///
+    /// 世代を進める直前に呼び出す。
+    pub fn sync(&mut self, world: &World) {
+        if self.revision != Some(world.revision()) {
+            self.detector.clear();
+            self.observe(world);
+        }
+    }
+
+    /// 現在の世代を記録し、この世代で新たに検出した振る舞いを返す
+    pub fn observe(&mut self, world: &World) -> Option<Behavior> {
+        self.revision = Some(world.revision());
+        self.detector
+            .observe(world.alive_cells(), world.generation_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(tracker: &mut CycleTracker, world: &mut World) -> Option<Behavior> {
+        tracker.sync(world);
+        world.advance_generations(1);
+        tracker.observe(world)
+    }
+
+    #[test]
+    fn oscillator_is_detected_while_stepping() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        let mut tracker = CycleTracker::default();
+        assert_eq!(step(&mut tracker, &mut world), None);
+        let blinker = Behavior::Oscillator { period: 2 };
+        assert_eq!(step(&mut tracker, &mut world), Some(blinker));
+        assert_eq!(step(&mut tracker, &mut world), None);
+        assert_eq!(tracker.behavior(&world), Some(blinker));
+    }
+
+    #[test]
+    fn editing_discards_detection() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let mut tracker = CycleTracker::default();
+        assert_eq!(step(&mut tracker, &mut world), Some(Behavior::StillLife));
+
+        world.toggle_cell(5, 5);
+        assert_eq!(tracker.behavior(&world), None);
+        // 孤立したセルは次の世代で消えるため、ブロックだけの固定物体として検出し直す
+        assert_eq!(step(&mut tracker, &mut world), None);
+        assert_eq!(step(&mut tracker, &mut world), Some(Behavior::StillLife));
+    }
+}
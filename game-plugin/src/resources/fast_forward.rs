@@ -0,0 +1,170 @@
+//! 数フレームに分けて進める早送り
+//!
+//! 1フレームで進める世代数を計算にかかった時間に合わせて倍増・半減させ、
+//! 画面を止めずに目標の世代まで進める。HashLife のように長い跳躍が速いエンジンでは
+//! 数フレームで、1世代ずつ計算する場合はフレームの予算に収まる分ずつ進む。
+//! 早送り全体は巻き戻しの1回の記録にまとめる。
+
+use std::time::Duration;
+
+use bevy::platform::time::Instant;
+use bevy::prelude::Resource;
+
+use crate::resources::rewind::RewindBuffer;
+use life_core::world::World;
+
+/// 実行中の早送りの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FastForwardRun {
+    /// 開始時の世代
+    start: u64,
+    /// 目標の世代
+    target: u64,
+    /// 次のフレームで進める世代数
+    batch: u64,
+    /// 直前に進めた直後のワールドのリビジョン（まだ進めていなければ `None`）
+    revision: Option<u64>,
+}
+
+/// 実行中の早送りを管理するリソース
+#[derive(Resource, Debug, Default)]
+pub struct FastForwardJob {
+    run: Option<FastForwardRun>,
+}
+
+impl FastForwardJob {
+    /// 指定した世代までの早送りを始める（現在の世代以前なら何もしない）
+    ///
+    /// 実行中の早送りがあれば置き換える。
+    pub fn start(&mut self, world: &World, target: u64) {
+        self.run = (target > world.generation_count).then_some(FastForwardRun {
+            start: world.generation_count,
+            target,
+            batch: 1,
+            revision: None,
+        });
+    }
+
+    /// 早送りを中止する（進めた世代はそのまま残る）
+    pub fn cancel(&mut self) {
+        self.run = None;
+    }
+
+    /// 早送り中かを返す
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// 早送りの進み具合（0.0〜1.0）を返す（早送り中でなければ `None`）
+    pub fn progress(&self, world: &World) -> Option<f32> {
+        self.run.map(|run| {
+            let done = world.generation_count.saturating_sub(run.start);
+            (done as f64 / (run.target - run.start) as f64).min(1.0) as f32
+        })
+    }
+
+    /// 1フレーム分だけ早送りを進める
+    ///
+    /// 進めた世代数の計算時間が `budget` の半分未満なら次のフレームで倍に、
+    /// `budget` を超えたら半分にする。目標に達するか、早送り中にワールドが
+    /// 編集・巻き戻しされた場合は終了する。
+    pub fn advance(&mut self, world: &mut World, rewind: &mut RewindBuffer, budget: Duration) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        if run
+            .revision
+            .is_some_and(|revision| revision != world.revision())
+            || world.generation_count >= run.target
+        {
+            self.run = None;
+            return;
+        }
+        let generations = run.batch.min(run.target - world.generation_count);
+        let started = Instant::now();
+        if run.revision.is_none() {
+            rewind.advance(world, generations);
+        } else {
+            rewind.advance_merged(world, generations);
+        }
+        let elapsed = started.elapsed();
+        if elapsed < budget / 2 {
+            run.batch = run.batch.saturating_mul(2);
+        } else if elapsed > budget {
+            run.batch = (run.batch / 2).max(1);
+        }
+        run.revision = Some(world.revision());
+        if world.generation_count >= run.target {
+            self.run = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::patterns::LifePattern;
+
+    fn glider_world() -> World {
+        let mut world = World::new();
+        world.place_pattern(LifePattern::Glider.cells());
+        world
+    }
+
+    #[test]
+    fn reaches_target_over_frames_as_one_rewind_frame() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        let mut job = FastForwardJob::default();
+        job.start(&world, 1000);
+        let mut frames = 0;
+        while job.is_running() {
+            job.advance(&mut world, &mut rewind, Duration::from_secs(1));
+            frames += 1;
+        }
+        assert_eq!(world.generation_count, 1000);
+        // NOTE: 1世代から倍増していくため、1フレームでは終わらない
+        assert!(frames > 1);
+        assert_eq!(rewind.len(), 1);
+        assert!(rewind.step_back(&mut world));
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn slow_frames_shrink_the_batch() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        let mut job = FastForwardJob::default();
+        job.start(&world, 100);
+        // 予算0では毎フレーム予算を超えるため、1世代ずつしか進まない
+        for generation in 1..=3 {
+            job.advance(&mut world, &mut rewind, Duration::ZERO);
+            assert_eq!(world.generation_count, generation);
+        }
+        assert_eq!(job.progress(&world), Some(0.03));
+    }
+
+    #[test]
+    fn edit_during_fast_forward_stops_it() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        let mut job = FastForwardJob::default();
+        job.start(&world, 1_000_000);
+        job.advance(&mut world, &mut rewind, Duration::from_secs(1));
+        world.toggle_cell(50, 50);
+        job.advance(&mut world, &mut rewind, Duration::from_secs(1));
+        assert!(!job.is_running());
+        // 編集で世代数は0に戻り、その後は進めない
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn target_in_the_past_does_nothing() {
+        let mut world = glider_world();
+        world.advance_generations(10);
+        let mut job = FastForwardJob::default();
+        job.start(&world, 5);
+        assert!(!job.is_running());
+        assert_eq!(job.progress(&world), None);
+    }
+}
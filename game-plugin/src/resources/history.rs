@@ -0,0 +1,233 @@
+//! セル編集の取り消し・やり直し履歴
+
+use std::collections::VecDeque;
+
+use bevy::prelude::Resource;
+use common::consts::EDIT_HISTORY_LIMIT;
+use life_core::world::World;
+
+pub use life_core::world::EditDiff;
+
+/// 履歴の1項目
+#[derive(Debug)]
+struct HistoryEntry {
+    diff: EditDiff,
+    /// この項目を記録・適用した直後のワールドのリビジョン
+    revision: u64,
+}
+
+/// セル編集の取り消し・やり直し履歴を管理するリソース
+///
+/// トグル・パターン配置・クリアなどの編集を1操作1項目として記録する。
+/// 保持する項目数は `EDIT_HISTORY_LIMIT` までで、超えた分は古い順に捨てる。
+#[derive(Resource, Debug, Default)]
+pub struct EditHistory {
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+}
+
+impl EditHistory {
+    /// 編集を記録し、やり直し履歴を破棄する
+    ///
+    /// 編集を行った直後の `world` を渡す。変化のない編集は記録しない。
+    pub fn record(&mut self, diff: EditDiff, world: &World) {
+        if diff.is_empty() {
+            return;
+        }
+        self.undo_stack.push_back(HistoryEntry {
+            diff,
+            revision: world.revision(),
+        });
+        if self.undo_stack.len() > EDIT_HISTORY_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    /// 直前の編集を取り消す。取り消す編集がなければ `false` を返す
+    pub fn undo(&mut self, world: &mut World) -> bool {
+        let Some(entry) = self.undo_stack.pop_back() else {
+            return false;
+        };
+        let exact = world.revision() == entry.revision;
+        world.apply_edit(&entry.diff, entry.diff.generation_before, exact);
+        self.redo_stack.push(HistoryEntry {
+            diff: entry.diff,
+            revision: world.revision(),
+        });
+        true
+    }
+
+    /// 取り消した編集をやり直す。やり直す編集がなければ `false` を返す
+    pub fn redo(&mut self, world: &mut World) -> bool {
+        let Some(entry) = self.redo_stack.pop() else {
+            return false;
+        };
+        let exact = world.revision() == entry.revision;
+        world.apply_edit(&entry.diff, 0, exact);
+        self.undo_stack.push_back(HistoryEntry {
+            diff: entry.diff,
+            revision: world.revision(),
+        });
+        true
+    }
+
+    /// 全ての履歴を破棄する
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// 取り消し可能な編集があるかを返す
+    #[allow(dead_code)]
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// やり直し可能な編集があるかを返す
+    #[allow(dead_code)]
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::patterns::LifePattern;
+
+    fn toggle(world: &mut World, history: &mut EditHistory, x: i32, y: i32) {
+        let diff = world.toggle_cell(x, y);
+        history.record(diff, world);
+    }
+
+    #[test]
+    fn undo_and_redo_toggle() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        toggle(&mut world, &mut history, 3, 4);
+
+        assert!(history.undo(&mut world));
+        assert!(!world.is_alive(3, 4));
+        assert!(!world.is_initial_alive(3, 4));
+
+        assert!(history.redo(&mut world));
+        assert!(world.is_alive(3, 4));
+        assert!(world.is_initial_alive(3, 4));
+    }
+
+    #[test]
+    fn undo_with_empty_history_does_nothing() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        assert!(!history.undo(&mut world));
+        assert!(!history.redo(&mut world));
+    }
+
+    #[test]
+    fn undo_walks_back_in_order() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        toggle(&mut world, &mut history, 0, 0);
+        toggle(&mut world, &mut history, 1, 0);
+        toggle(&mut world, &mut history, 0, 0);
+
+        history.undo(&mut world);
+        assert_eq!(world.live_cells(), vec![(0, 0), (1, 0)]);
+        history.undo(&mut world);
+        assert_eq!(world.live_cells(), vec![(0, 0)]);
+        history.undo(&mut world);
+        assert!(world.live_cells().is_empty());
+    }
+
+    #[test]
+    fn new_edit_discards_redo() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        toggle(&mut world, &mut history, 0, 0);
+        history.undo(&mut world);
+        toggle(&mut world, &mut history, 5, 5);
+        assert!(!history.can_redo());
+        assert!(!history.redo(&mut world));
+    }
+
+    #[test]
+    fn pattern_placement_undoes_as_single_step() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        toggle(&mut world, &mut history, 100, 100);
+        let diff = world.place_pattern(LifePattern::GosperGliderGun.cells());
+        history.record(diff, &world);
+
+        history.undo(&mut world);
+        assert_eq!(world.live_cells(), vec![(100, 100)]);
+        history.redo(&mut world);
+        assert_eq!(
+            world.alive_cells().len(),
+            LifePattern::GosperGliderGun.cells().len() + 1
+        );
+    }
+
+    #[test]
+    fn clear_undoes_as_single_step_and_restores_generation() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        world.place_pattern(LifePattern::Acorn.cells());
+        world.advance_generations(10);
+        let before = world.live_cells();
+
+        let diff = world.clear();
+        history.record(diff, &world);
+        assert!(world.live_cells().is_empty());
+
+        history.undo(&mut world);
+        assert_eq!(world.live_cells(), before);
+        assert_eq!(world.generation_count, 10);
+        for &(x, y) in LifePattern::Acorn.cells() {
+            assert!(world.is_initial_alive(x, y));
+        }
+    }
+
+    #[test]
+    fn undo_marks_affected_chunks_dirty() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        let diff = world.place_pattern(&[(-1, -1), (200, 3)]);
+        history.record(diff, &world);
+        world.clear_dirty_chunks();
+
+        history.undo(&mut world);
+        assert!(world.dirty_chunks().contains(&World::chunk_key(-1, -1)));
+        assert!(world.dirty_chunks().contains(&World::chunk_key(200, 3)));
+    }
+
+    #[test]
+    fn undo_after_simulation_restores_pattern_at_generation_zero() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        toggle(&mut world, &mut history, 50, 50);
+        let diff = world.place_pattern(LifePattern::Glider.cells());
+        history.record(diff, &world);
+        world.advance_generations(8);
+
+        // 世代が進んだ後の取り消しは、編集前の初期パターンを世代0で表示する
+        history.undo(&mut world);
+        assert_eq!(world.generation_count, 0);
+        assert_eq!(world.live_cells(), vec![(50, 50)]);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut world = World::new();
+        let mut history = EditHistory::default();
+        for i in 0..EDIT_HISTORY_LIMIT as i32 + 10 {
+            toggle(&mut world, &mut history, i, 0);
+        }
+        let mut undone = 0;
+        while history.undo(&mut world) {
+            undone += 1;
+        }
+        assert_eq!(undone, EDIT_HISTORY_LIMIT);
+        assert_eq!(world.alive_cells().len(), 10);
+    }
+}
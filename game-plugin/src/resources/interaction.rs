@@ -1,6 +1,9 @@
 //! インタラクション関連のリソース
 
 use bevy::prelude::*;
+use common::consts::INITIAL_CAMERA_SCALE;
+use common::rules::Rule;
+use common::settings::Settings;
 
 /// 現在マウスがホバーしているセルの座標を保持するリソース
 #[derive(Resource, Default, PartialEq)]
@@ -12,12 +15,58 @@ pub struct HoveredCell(pub Option<(i32, i32)>);
 pub struct AudioCooldown(pub Timer);
 
 /// グリッドラインの表示/非表示状態を管理するリソース
+///
+/// ゲーム画面に入るたびに `Settings` の保存値で初期化する。
 #[derive(Resource)]
 pub struct GridVisible(pub bool);
 
-impl Default for GridVisible {
+/// 全滅・固定物体を検出したときにシミュレーションを自動で一時停止するかを管理するリソース
+///
+/// ゲーム画面に入るたびに `Settings` の保存値で初期化する。
+#[derive(Resource)]
+pub struct AutoPause(pub bool);
+
+/// セル編集で塗る状態（1が生存、2以上が Generations ルールの衰退中）を管理するリソース
+///
+/// 多状態オートマトンではルールの状態1以上をそのまま表す。
+/// ルールの状態数を超える値は、使用時に最後の状態に丸める。
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaintState(pub u8);
+
+impl Default for PaintState {
     fn default() -> Self {
-        Self(true)
+        Self(1)
+    }
+}
+
+impl PaintState {
+    /// ルール `rule` のもとで塗る状態を返す
+    pub fn state(&self, rule: Rule) -> u8 {
+        self.0.clamp(1, rule.states() - 1)
+    }
+
+    /// ルール `rule` の次の状態に切り替える（最後の衰退中の状態の次は生存に戻る）
+    pub fn cycle(&mut self, rule: Rule) {
+        let next = self.state(rule) + 1;
+        self.0 = if next < rule.states() { next } else { 1 };
+    }
+}
+
+/// 早送り先世代の入力状態を管理するリソース
+///
+/// 入力欄をクリックするとフォーカスされ、数字キーで世代数を入力できる。
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct FastForwardInput {
+    /// 入力中の数字列
+    pub digits: String,
+    /// キーボード入力を受け付けているか
+    pub focused: bool,
+}
+
+impl FastForwardInput {
+    /// 入力中の世代数を返す（未入力なら `None`）
+    pub fn target(&self) -> Option<u64> {
+        self.digits.parse().ok()
     }
 }
 
@@ -35,8 +84,58 @@ pub struct DragState {
     pub is_dragging: bool,
 }
 
+/// ワールドカメラの位置と拡大率を画面遷移をまたいで保持するリソース
+///
+/// ゲーム画面を離れるときに記録し、戻ったときにカメラへ適用する。
+/// セッションの保存・読み込みでもこの値を読み書きする。
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct CameraState {
+    /// ワールド座標でのカメラ位置
+    pub translation: Vec2,
+    /// 正射影のスケール
+    pub scale: f32,
+}
+
+impl FromWorld for CameraState {
+    /// 原点から、`Settings` に保存された拡大率で始める
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            scale: world
+                .get_resource::<Settings>()
+                .map_or(INITIAL_CAMERA_SCALE, |settings| settings.camera_scale),
+        }
+    }
+}
+
 impl Default for AudioCooldown {
     fn default() -> Self {
         Self(Timer::from_seconds(0.05, TimerMode::Once))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_state_cycles_through_rule_states() {
+        let rule = Rule::parse("B2/S345/C4").unwrap();
+        let mut paint = PaintState::default();
+        let states: Vec<u8> = (0..4)
+            .map(|_| {
+                paint.cycle(rule);
+                paint.state(rule)
+            })
+            .collect();
+        assert_eq!(states, vec![2, 3, 1, 2]);
+    }
+
+    #[test]
+    fn paint_state_is_clamped_to_rule() {
+        let mut paint = PaintState(3);
+        assert_eq!(paint.state(Rule::conway()), 1);
+        paint.cycle(Rule::conway());
+        assert_eq!(paint, PaintState(1));
+    }
+}
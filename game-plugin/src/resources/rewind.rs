@@ -0,0 +1,364 @@
+//! シミュレーションの巻き戻し履歴
+//!
+//! 世代を進めるたびに、進める前後のセル（Generations ルールの衰退中のセルを含む）の
+//! XOR差分をリングバッファに積む。
+//! 差分を現在のセルに当てると直前の世代に戻るため、キーフレームは持たない。
+//! 保持する差分の合計がメモリ上限を超えると古い順に捨てる。
+
+use std::collections::VecDeque;
+
+use bevy::prelude::Resource;
+use common::consts::REWIND_MEMORY_LIMIT;
+
+use life_core::bitboard::Bitboard;
+use life_core::generations::DecayingCells;
+use life_core::world::World;
+
+/// 巻き戻し1回分の記録
+#[derive(Debug)]
+struct RewindFrame {
+    /// 進める前後のセルのXOR差分
+    diff: Bitboard,
+    /// 進める前後の衰退中のセルのXOR差分
+    decaying_diff: DecayingCells,
+    /// 進める前の世代数
+    generation: u64,
+}
+
+impl RewindFrame {
+    /// おおよそのメモリ使用量（バイト）
+    fn memory_bytes(&self) -> usize {
+        size_of::<Self>() + self.diff.memory_bytes() + self.decaying_diff.memory_bytes()
+    }
+}
+
+/// 過去の世代を巻き戻すためのリングバッファを管理するリソース
+///
+/// 記録は `World` のリビジョンで現在の状態と結び付けており、
+/// セル編集・リセットなどで状態が変わった場合は次の操作時に履歴を破棄する。
+#[derive(Resource, Debug)]
+pub struct RewindBuffer {
+    frames: VecDeque<RewindFrame>,
+    /// 保持中の記録のメモリ使用量（バイト）
+    memory_bytes: usize,
+    /// 保持する記録のメモリ上限（バイト）
+    memory_limit: usize,
+    /// 最後に記録・巻き戻しした直後のワールドのリビジョン
+    revision: Option<u64>,
+}
+
+impl Default for RewindBuffer {
+    fn default() -> Self {
+        Self::new(REWIND_MEMORY_LIMIT)
+    }
+}
+
+impl RewindBuffer {
+    /// メモリ上限（バイト）を指定して空の履歴を生成する
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            memory_bytes: 0,
+            memory_limit,
+            revision: None,
+        }
+    }
+
+    /// メモリ上限（バイト）を返す
+    #[allow(dead_code)]
+    pub fn memory_limit(&self) -> usize {
+        self.memory_limit
+    }
+
+    /// メモリ上限（バイト）を変更し、超過分の古い記録を捨てる
+    #[allow(dead_code)]
+    pub fn set_memory_limit(&mut self, memory_limit: usize) {
+        self.memory_limit = memory_limit;
+        self.evict();
+    }
+
+    /// 巻き戻し可能な回数を返す
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 巻き戻し可能な記録がないかを返す
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 全ての記録を破棄する
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.memory_bytes = 0;
+        self.revision = None;
+    }
+
+    /// ワールドの世代を `generations` だけ進め、進める前の状態を記録する
+    pub fn advance(&mut self, world: &mut World, generations: u64) {
+        if generations == 0 {
+            return;
+        }
+        self.sync(world);
+        let generation = world.generation_count;
+        let (diff, decaying_diff) = world.advance_generations_with_diff(generations);
+        self.push(RewindFrame {
+            diff,
+            decaying_diff,
+            generation,
+        });
+        self.revision = Some(world.revision());
+    }
+
+    /// 直前の記録に続けて世代を進め、進めた分の差分を直前の記録にまとめる
+    ///
+    /// 数フレームに分けた早送りを1回の記録にするために使う。XOR差分は重ねて当てられるため、
+    /// まとめた記録を巻き戻すと最初に進める前の世代に戻る。
+    /// 直前の記録がない、または記録後にワールドが編集されていれば `advance` と同じく新しく記録する。
+    pub fn advance_merged(&mut self, world: &mut World, generations: u64) {
+        if generations == 0 {
+            return;
+        }
+        self.sync(world);
+        let Some(mut frame) = self.frames.pop_back() else {
+            self.advance(world, generations);
+            return;
+        };
+        self.memory_bytes -= frame.memory_bytes();
+        let (diff, decaying_diff) = world.advance_generations_with_diff(generations);
+        frame.diff.xor_assign(&diff);
+        frame.decaying_diff.xor_assign(&decaying_diff);
+        self.push(frame);
+        self.revision = Some(world.revision());
+    }
+
+    /// 直前に記録した世代まで巻き戻す。巻き戻せなければ `false` を返す
+    pub fn step_back(&mut self, world: &mut World) -> bool {
+        self.sync(world);
+        let Some(frame) = self.frames.pop_back() else {
+            return false;
+        };
+        self.memory_bytes -= frame.memory_bytes();
+        world.apply_generation_diff(&frame.diff, &frame.decaying_diff, frame.generation);
+        self.revision = Some(world.revision());
+        true
+    }
+
+    /// 記録後にワールドが編集されていれば履歴を破棄する
+    fn sync(&mut self, world: &World) {
+        if self.revision != Some(world.revision()) {
+            self.clear();
+        }
+    }
+
+    /// 記録を積み、メモリ上限を超えた分の古い記録を捨てる
+    ///
+    /// 1回分だけで上限を超える記録は積まない。差分は新しい順に当てるため、
+    /// その場合はそれより前の記録にも戻れなくなり、履歴を全て破棄する。
+    fn push(&mut self, frame: RewindFrame) {
+        let bytes = frame.memory_bytes();
+        if bytes > self.memory_limit {
+            self.clear();
+            return;
+        }
+        self.memory_bytes += bytes;
+        self.frames.push_back(frame);
+        self.evict();
+    }
+
+    /// メモリ上限を超えている間、古い記録から捨てる
+    fn evict(&mut self) {
+        while self.memory_bytes > self.memory_limit {
+            let Some(frame) = self.frames.pop_front() else {
+                break;
+            };
+            self.memory_bytes -= frame.memory_bytes();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::patterns::LifePattern;
+    use life_core::bitboard::{ChunkKey, ChunkRows};
+
+    fn sorted_cells(world: &World) -> Vec<(i32, i32)> {
+        let mut cells = world.live_cells();
+        cells.sort();
+        cells
+    }
+
+    fn glider_world() -> World {
+        let mut world = World::new();
+        world.place_pattern(LifePattern::Glider.cells());
+        world
+    }
+
+    #[test]
+    fn step_back_restores_previous_generation() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        let mut history = vec![sorted_cells(&world)];
+        for _ in 0..10 {
+            rewind.advance(&mut world, 1);
+            history.push(sorted_cells(&world));
+        }
+        assert_eq!(rewind.len(), 10);
+
+        for generation in (0..10).rev() {
+            assert!(rewind.step_back(&mut world));
+            assert_eq!(world.generation_count, generation);
+            assert_eq!(sorted_cells(&world), history[generation as usize]);
+        }
+        assert!(!rewind.step_back(&mut world));
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn step_back_restores_decaying_cells() {
+        let mut world = World::new();
+        world.set_rule(life_core::rules::Rule::parse("B2/S/C3").unwrap());
+        world.place_pattern(&[(0, 0), (1, 0)]);
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 1);
+        let decaying = world.decaying_cells().clone();
+        assert!(!decaying.is_empty());
+        rewind.advance(&mut world, 1);
+
+        assert!(rewind.step_back(&mut world));
+        assert_eq!(world.decaying_cells(), &decaying);
+        assert!(rewind.step_back(&mut world));
+        assert!(world.decaying_cells().is_empty());
+    }
+
+    #[test]
+    fn step_back_on_empty_buffer_does_nothing() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        assert!(!rewind.step_back(&mut world));
+        assert_eq!(world.generation_count, 0);
+        assert_eq!(world.live_cells().len(), 5);
+    }
+
+    #[test]
+    fn step_back_over_fast_forward_returns_to_start_of_jump() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 3);
+        let before_jump = sorted_cells(&world);
+        rewind.advance(&mut world, 1);
+        for _ in 0..10 {
+            rewind.advance_merged(&mut world, 99);
+        }
+        rewind.advance_merged(&mut world, 6);
+        assert_eq!(world.generation_count, 1000);
+        assert_eq!(rewind.len(), 2);
+
+        assert!(rewind.step_back(&mut world));
+        assert_eq!(world.generation_count, 3);
+        assert_eq!(sorted_cells(&world), before_jump);
+    }
+
+    #[test]
+    fn advancing_after_step_back_continues_from_restored_state() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 8);
+        let expected = sorted_cells(&world);
+        rewind.step_back(&mut world);
+        rewind.advance(&mut world, 8);
+        assert_eq!(world.generation_count, 8);
+        assert_eq!(sorted_cells(&world), expected);
+        assert_eq!(rewind.len(), 1);
+    }
+
+    #[test]
+    fn edit_discards_rewind_history() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 1);
+        rewind.advance(&mut world, 1);
+        world.toggle_cell(50, 50);
+
+        assert!(!rewind.step_back(&mut world));
+        assert!(rewind.is_empty());
+        assert!(world.is_alive(50, 50));
+    }
+
+    #[test]
+    fn reset_discards_rewind_history() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 4);
+        world.reset();
+        assert!(!rewind.step_back(&mut world));
+    }
+
+    #[test]
+    fn step_back_marks_changed_chunks_dirty() {
+        let mut world = World::new();
+        // チャンク境界をまたぐブリンカー
+        world.place_pattern(&[(63, 10), (64, 10), (65, 10)]);
+        let mut rewind = RewindBuffer::default();
+        rewind.advance(&mut world, 1);
+        world.clear_dirty_chunks();
+
+        rewind.step_back(&mut world);
+        assert!(world.dirty_chunks().contains(&(0, 0)));
+        assert!(world.dirty_chunks().contains(&(1, 0)));
+    }
+
+    #[test]
+    fn memory_limit_bounds_rewind_depth() {
+        let mut world = glider_world();
+        // グライダーは1チャンク内なので、1回分の記録は1チャンク分
+        let frame_bytes = size_of::<RewindFrame>() + size_of::<ChunkKey>() + size_of::<ChunkRows>();
+        let mut rewind = RewindBuffer::new(frame_bytes * 3);
+        for _ in 0..10 {
+            rewind.advance(&mut world, 1);
+        }
+        assert_eq!(rewind.len(), 3);
+        for _ in 0..3 {
+            assert!(rewind.step_back(&mut world));
+        }
+        assert!(!rewind.step_back(&mut world));
+        assert_eq!(world.generation_count, 7);
+    }
+
+    #[test]
+    fn lowering_memory_limit_evicts_oldest_frames() {
+        let mut world = glider_world();
+        let mut rewind = RewindBuffer::default();
+        for _ in 0..5 {
+            rewind.advance(&mut world, 1);
+        }
+        rewind.set_memory_limit(0);
+        assert!(rewind.is_empty());
+        assert_eq!(rewind.memory_limit(), 0);
+    }
+
+    #[test]
+    fn frame_larger_than_limit_discards_history() {
+        // チャンク境界へ向かうグライダー。境界をまたぐ間の記録は2チャンク分になる
+        let mut world = World::new();
+        let cells: Vec<_> = LifePattern::Glider
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x + 58, y + 10))
+            .collect();
+        world.place_pattern(&cells);
+        let frame_bytes = size_of::<RewindFrame>() + size_of::<ChunkKey>() + size_of::<ChunkRows>();
+        let mut rewind = RewindBuffer::new(frame_bytes);
+        rewind.advance(&mut world, 1);
+        assert_eq!(rewind.len(), 1);
+        let discarded = (0..40).any(|_| {
+            rewind.advance(&mut world, 1);
+            rewind.is_empty()
+        });
+        assert!(discarded);
+        assert!(!rewind.step_back(&mut world));
+    }
+}
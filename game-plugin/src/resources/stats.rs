@@ -0,0 +1,161 @@
+//! 統計パネルに表示する人口推移と世代進行速度
+
+use std::collections::VecDeque;
+
+use bevy::prelude::Resource;
+use common::consts::{STATS_HISTORY_LENGTH, STATS_RATE_WINDOW};
+
+/// 統計パネルの表示/非表示状態を管理するリソース
+#[derive(Resource, Default)]
+pub struct StatsVisible(pub bool);
+
+/// 世代ごとの生存セル数を直近 `capacity` 世代分保持するリソース
+///
+/// 巻き戻し・リセットで世代が戻った場合は、その世代以降の記録を捨てて記録し直す。
+#[derive(Resource, Debug)]
+pub struct PopulationHistory {
+    /// `(世代, 生存セル数)` の記録（古い順）
+    samples: VecDeque<(u64, usize)>,
+    /// 保持する記録数の上限
+    capacity: usize,
+}
+
+impl Default for PopulationHistory {
+    fn default() -> Self {
+        Self::new(STATS_HISTORY_LENGTH)
+    }
+}
+
+impl PopulationHistory {
+    /// 上限を指定して空の記録を生成する
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// 保持する記録数の上限を返す
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 世代 `generation` の生存セル数を記録する
+    ///
+    /// 同じ世代の記録があれば置き換える（世代0での編集など）。
+    pub fn record(&mut self, generation: u64, population: usize) {
+        while self
+            .samples
+            .back()
+            .is_some_and(|&(last, _)| last >= generation)
+        {
+            self.samples.pop_back();
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((generation, population));
+    }
+
+    /// 記録した生存セル数を古い順に返す
+    pub fn populations(&self) -> impl ExactSizeIterator<Item = usize> + '_ {
+        self.samples.iter().map(|&(_, population)| population)
+    }
+
+    /// 記録中の最大の生存セル数を返す
+    pub fn max_population(&self) -> usize {
+        self.populations().max().unwrap_or(0)
+    }
+}
+
+/// 直近 `STATS_RATE_WINDOW` 秒間の世代の進み具合から世代進行速度を求めるリソース
+#[derive(Resource, Debug, Default)]
+pub struct GenerationRate {
+    /// `(経過時間（秒）, 世代)` の記録（古い順）
+    samples: VecDeque<(f64, u64)>,
+}
+
+impl GenerationRate {
+    /// 経過時間 `seconds` 時点の世代を記録する
+    ///
+    /// 世代が戻った場合（巻き戻し・リセット）はそれまでの記録を捨てる。
+    pub fn record(&mut self, seconds: f64, generation: u64) {
+        if self
+            .samples
+            .back()
+            .is_some_and(|&(_, last)| last > generation)
+        {
+            self.samples.clear();
+        }
+        self.samples.push_back((seconds, generation));
+        // NOTE: 時間幅の始点を求めるため、幅の外側の記録を1つだけ残す
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|&(time, _)| seconds - time >= STATS_RATE_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 1秒あたりに進んだ世代数を返す
+    pub fn per_second(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(&(start, from)), Some(&(end, to))) if end > start => {
+                (to - from) as f64 / (end - start)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_latest_samples() {
+        let mut history = PopulationHistory::new(3);
+        for generation in 0..5 {
+            history.record(generation, generation as usize * 10);
+        }
+        assert_eq!(history.populations().collect::<Vec<_>>(), vec![20, 30, 40]);
+        assert_eq!(history.max_population(), 40);
+    }
+
+    #[test]
+    fn history_rewinds_with_generation() {
+        let mut history = PopulationHistory::new(10);
+        history.record(0, 3);
+        history.record(1, 4);
+        history.record(2, 5);
+        // 巻き戻しで世代1に戻った
+        history.record(1, 4);
+        assert_eq!(history.populations().collect::<Vec<_>>(), vec![3, 4]);
+        // リセット後に世代0で編集した
+        history.record(0, 7);
+        assert_eq!(history.populations().collect::<Vec<_>>(), vec![7]);
+    }
+
+    #[test]
+    fn rate_averages_over_window() {
+        let mut rate = GenerationRate::default();
+        assert_eq!(rate.per_second(), 0.0);
+        for frame in 0..=40 {
+            // 0.05秒ごとに1世代ずつ進む（20世代/秒）
+            rate.record(frame as f64 * 0.05, frame);
+        }
+        assert!((rate.per_second() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rate_resets_when_generation_goes_back() {
+        let mut rate = GenerationRate::default();
+        rate.record(0.0, 0);
+        rate.record(0.5, 10);
+        rate.record(0.6, 2);
+        assert_eq!(rate.per_second(), 0.0);
+        rate.record(1.1, 7);
+        assert!((rate.per_second() - 10.0).abs() < 1e-9);
+    }
+}
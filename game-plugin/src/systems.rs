@@ -5,9 +5,14 @@ pub mod button_handler;
 pub mod cell_operations;
 pub mod chunk;
 pub mod coordinate;
+pub mod fast_forward;
 pub mod grid;
 pub mod input;
 pub mod screen;
+pub mod selection;
+pub mod session;
+pub mod settings;
 pub mod slider;
+pub mod stats;
 pub mod ui;
 pub mod viewport;
@@ -1,13 +1,15 @@
 //! ボタンクリック・ホバーのアクションハンドラ
 
 use bevy::prelude::*;
-use common::consts::BG_BUTTON_HOVER;
+use common::consts::{ACCENT_GREEN, BG_BUTTON_HOVER, TEXT_MUTED};
+use common::settings::Settings;
 
 use crate::components::action::GameButtonAction;
 use crate::events::{
-    GenerationResetEvent, PlayAudioEvent, ProgressGenerationEvent, WorldClearEvent,
+    GenerationResetEvent, PlayAudioEvent, ProgressGenerationEvent, StepBackEvent, WorldClearEvent,
 };
-use crate::resources::interaction::GridVisible;
+use crate::resources::interaction::{AutoPause, GridVisible, PaintState};
+use crate::resources::world::World;
 use crate::states::SimulationState;
 
 /// シミュレーション開始/停止トグルボタンのクリックハンドラ
@@ -22,22 +24,47 @@ pub fn handle_toggle_simulation(
     }
 }
 
-/// シミュレーション状態に応じてトグルボタンのテキストを更新するシステム
+/// シミュレーション状態・自動一時停止・ヒートマップ・塗る状態の設定に応じてトグルボタンの表示を更新するシステム
+///
+/// 開始/停止ボタンはラベルを、Auto・Heatボタンは有効時に文字色をアクセントカラーに切り替える。
+/// Paintボタンは塗る状態（多状態オートマトンでは状態名）をラベルに表示し、
+/// 3状態以上のルールでのみ文字色をアクセントカラーにする。
 pub fn update_toggle_button_text(
     simulation_state: Res<State<SimulationState>>,
+    auto_pause: Res<AutoPause>,
+    world: Res<World>,
+    paint_state: Res<PaintState>,
     query_button: Query<(&GameButtonAction, &Children)>,
-    mut query_text: Query<&mut Text>,
+    mut query_text: Query<(&mut Text, &mut TextColor)>,
 ) {
     let label = match simulation_state.get() {
         SimulationState::Paused => "Start",
         SimulationState::Simulating => "Stop",
     };
+    let toggle_color = |enabled: bool| if enabled { ACCENT_GREEN } else { TEXT_MUTED };
+    let auto_pause_color = toggle_color(auto_pause.0);
+    let heatmap_color = toggle_color(world.heatmap().is_some());
+    let rule = world.rule();
+    let paint = paint_state.state(rule);
+    let paint_label = match rule.multi_state() {
+        Some(multi_state) => format!("Paint {}", multi_state.state_name(paint)),
+        None => format!("Paint {paint}"),
+    };
+    let paint_color = toggle_color(rule.states() > 2);
     for (action, children) in query_button.iter() {
-        if matches!(action, GameButtonAction::ToggleSimulation) {
-            for child in children.iter() {
-                if let Ok(mut text) = query_text.get_mut(child) {
-                    **text = label.to_string();
+        for child in children.iter() {
+            let Ok((mut text, mut color)) = query_text.get_mut(child) else {
+                continue;
+            };
+            match action {
+                GameButtonAction::ToggleSimulation => **text = label.to_string(),
+                GameButtonAction::AutoPauseToggle => color.0 = auto_pause_color,
+                GameButtonAction::HeatmapToggle => color.0 = heatmap_color,
+                GameButtonAction::PaintState => {
+                    **text = paint_label.clone();
+                    color.0 = paint_color;
                 }
+                _ => {}
             }
         }
     }
@@ -51,6 +78,14 @@ pub fn handle_next(
     progress_generation_event_writer.write(ProgressGenerationEvent);
 }
 
+/// Backボタンのクリックハンドラ: 1世代巻き戻す
+pub fn handle_step_back(
+    _click: On<Pointer<Click>>,
+    mut step_back_event_writer: MessageWriter<StepBackEvent>,
+) {
+    step_back_event_writer.write(StepBackEvent);
+}
+
 /// Resetボタンのクリックハンドラ: 初期パターンに戻す
 pub fn handle_reset(
     _click: On<Pointer<Click>>,
@@ -83,3 +118,38 @@ pub fn handle_over(
 pub fn handle_grid_toggle(_click: On<Pointer<Click>>, mut grid_visible: ResMut<GridVisible>) {
     grid_visible.0 = !grid_visible.0;
 }
+
+/// Autoボタンのクリックハンドラ: 全滅・固定物体での自動一時停止を切り替える
+pub fn handle_auto_pause_toggle(_click: On<Pointer<Click>>, mut auto_pause: ResMut<AutoPause>) {
+    auto_pause.0 = !auto_pause.0;
+}
+
+/// Heatボタンのクリックハンドラ: 活動度ヒートマップの表示を切り替える
+pub fn handle_heatmap_toggle(
+    _click: On<Pointer<Click>>,
+    mut world: ResMut<World>,
+    settings: Res<Settings>,
+) {
+    toggle_heatmap(&mut world, &settings);
+}
+
+/// 活動度ヒートマップの表示を切り替える。有効にするときは `Settings` の集計方法を使う
+pub fn toggle_heatmap(world: &mut World, settings: &Settings) {
+    let config = world.heatmap().is_none().then(|| settings.heatmap_config());
+    world.set_heatmap(config);
+}
+
+/// Paintボタンのクリックハンドラ: セル編集で塗る状態を切り替える
+pub fn handle_paint_state(
+    _click: On<Pointer<Click>>,
+    world: Res<World>,
+    mut paint_state: ResMut<PaintState>,
+) {
+    paint_state.cycle(world.rule());
+}
+
+/// Engineボタンのクリックハンドラ: 世代計算エンジンを切り替える
+pub fn handle_engine_toggle(_click: On<Pointer<Click>>, mut world: ResMut<World>) {
+    let next = world.engine_kind().next();
+    world.set_engine(next);
+}
@@ -1,12 +1,22 @@
 //! セル状態の更新・イベントハンドリング
 //!
-//! 世代進行・リセット・クリアのイベントを処理する。
+//! 世代進行・早送り・巻き戻し・リセット・クリア・取り消し/やり直しのイベントを処理する。
+//! 世代進行のたびに周期的な振る舞いを検出し、全滅・固定物体なら設定に応じて一時停止する。
 
 use bevy::prelude::*;
+use common::consts::FAST_FORWARD_FRAME_BUDGET;
+use common::topology::Topology;
 
-use crate::components::screen::GenerationText;
-use crate::events::{GenerationResetEvent, ProgressGenerationEvent, WorldClearEvent};
-use crate::resources::{timer::SimulationTimer, world::World};
+use crate::components::screen::{CycleText, EngineText, GenerationText, RuleText};
+use crate::events::{
+    FastForwardEvent, GenerationResetEvent, ProgressGenerationEvent, RedoEvent, StepBackEvent,
+    UndoEvent, WorldClearEvent,
+};
+use crate::resources::{
+    cycle::CycleTracker, fast_forward::FastForwardJob, history::EditHistory,
+    interaction::AutoPause, rewind::RewindBuffer, timer::SimulationTimer, world::World,
+};
+use crate::states::SimulationState;
 
 /// 世代カウンターのUI表示を更新するシステム
 pub fn update_generation(world: Res<World>, mut query: Query<&mut TextSpan, With<GenerationText>>) {
@@ -15,6 +25,53 @@ pub fn update_generation(world: Res<World>, mut query: Query<&mut TextSpan, With
     }
 }
 
+/// 周期的な振る舞いを検出していないときの表示
+pub const CYCLE_UNKNOWN_LABEL: &str = "-";
+
+/// 検出した周期的な振る舞いのUI表示を更新するシステム
+///
+/// 検出後にワールドが編集・巻き戻しされた場合は未検出の表示に戻す。
+pub fn update_cycle_text(
+    world: Res<World>,
+    cycle: Res<CycleTracker>,
+    mut query: Query<&mut Text, With<CycleText>>,
+) {
+    if !world.is_changed() && !cycle.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = query.single_mut() {
+        let label = cycle
+            .behavior(&world)
+            .map_or_else(|| CYCLE_UNKNOWN_LABEL.to_string(), |b| b.to_string());
+        text.set_if_neq(Text(label));
+    }
+}
+
+/// 適用中ルールのUI表示を更新するシステム
+///
+/// 有限トポロジーでは Golly と同じく `B3/S23:T128,128` の形式で面の種類と大きさを添える。
+pub fn update_rule_text(world: Res<World>, mut query: Query<&mut TextSpan, With<RuleText>>) {
+    if !world.is_changed() {
+        return;
+    }
+    if let Ok(mut span) = query.single_mut() {
+        span.0 = match world.topology() {
+            Topology::Infinite => world.rule().to_string(),
+            topology => format!("{}:{topology}", world.rule()),
+        };
+    }
+}
+
+/// 使用中の世代計算エンジンのUI表示を更新するシステム
+pub fn update_engine_text(world: Res<World>, mut query: Query<&mut TextSpan, With<EngineText>>) {
+    if !world.is_changed() {
+        return;
+    }
+    if let Ok(mut span) = query.single_mut() {
+        span.0 = world.engine_kind().label().to_string();
+    }
+}
+
 /// シミュレーションタイマーに基づき世代進行イベントを発火するシステム
 ///
 /// `SimulationState::Simulating` の時のみ動作する。
@@ -29,12 +86,67 @@ pub fn progress_generation_trigger(
 }
 
 /// `ProgressGenerationEvent` を受け取りワールドの世代を進めるシステム
+///
+/// 進める前の状態は巻き戻し用に記録する。進めた後の状態で周期的な振る舞いを検出し、
+/// `AutoPause` が有効なら全滅・固定物体になった時点でシミュレーションを一時停止する。
 pub fn progress_generation(
     mut world: ResMut<World>,
+    mut rewind: ResMut<RewindBuffer>,
+    mut cycle: ResMut<CycleTracker>,
+    auto_pause: Res<AutoPause>,
+    mut simulation_next_state: ResMut<NextState<SimulationState>>,
     mut progress_generation_event_reader: MessageReader<ProgressGenerationEvent>,
 ) {
     for _ in progress_generation_event_reader.read() {
-        world.progress_generation()
+        cycle.sync(&world);
+        rewind.advance(&mut world, 1);
+        let detected = cycle.observe(&world);
+        if auto_pause.0 && detected.is_some_and(|behavior| behavior.is_static()) {
+            simulation_next_state.set(SimulationState::Paused);
+        }
+    }
+}
+
+/// `FastForwardEvent` を受け取り指定世代までの早送りを始めるシステム
+///
+/// 自動進行で早送りが中断されないよう、シミュレーションを一時停止する。
+pub fn fast_forward(
+    world: Res<World>,
+    mut job: ResMut<FastForwardJob>,
+    mut simulation_next_state: ResMut<NextState<SimulationState>>,
+    mut fast_forward_event_reader: MessageReader<FastForwardEvent>,
+) {
+    for event in fast_forward_event_reader.read() {
+        job.start(&world, event.0);
+        if job.is_running() {
+            simulation_next_state.set(SimulationState::Paused);
+        }
+    }
+}
+
+/// 実行中の早送りを1フレーム分進めるシステム
+pub fn run_fast_forward(
+    mut world: ResMut<World>,
+    mut rewind: ResMut<RewindBuffer>,
+    mut job: ResMut<FastForwardJob>,
+) {
+    if job.is_running() {
+        job.advance(&mut world, &mut rewind, FAST_FORWARD_FRAME_BUDGET);
+    }
+}
+
+/// `StepBackEvent` を受け取りワールドを直前に記録した世代へ巻き戻すシステム
+///
+/// 巻き戻した直後に自動進行で上書きされないよう、シミュレーションを一時停止する。
+pub fn step_back(
+    mut world: ResMut<World>,
+    mut rewind: ResMut<RewindBuffer>,
+    mut simulation_next_state: ResMut<NextState<SimulationState>>,
+    mut step_back_event_reader: MessageReader<StepBackEvent>,
+) {
+    for _ in step_back_event_reader.read() {
+        rewind.step_back(&mut world);
+        simulation_next_state.set(SimulationState::Paused);
     }
 }
 
@@ -51,9 +163,26 @@ pub fn reset_generation(
 /// `WorldClearEvent` を受け取り全セルをクリアするシステム
 pub fn world_clear(
     mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
     mut world_clear_event_reader: MessageReader<WorldClearEvent>,
 ) {
     for _ in world_clear_event_reader.read() {
-        world.clear();
+        let diff = world.clear();
+        history.record(diff, &world);
+    }
+}
+
+/// `UndoEvent` / `RedoEvent` を受け取り編集履歴を取り消し・やり直しするシステム
+pub fn undo_redo(
+    mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
+    mut undo_event_reader: MessageReader<UndoEvent>,
+    mut redo_event_reader: MessageReader<RedoEvent>,
+) {
+    for _ in undo_event_reader.read() {
+        history.undo(&mut world);
+    }
+    for _ in redo_event_reader.read() {
+        history.redo(&mut world);
     }
 }
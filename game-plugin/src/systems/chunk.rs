@@ -1,13 +1,15 @@
 //! チャンクの動的スポーン/デスポーン/更新システム
 
 use bevy::prelude::*;
-use common::consts::CHUNK_WORLD_SIZE;
+use common::consts::{CHUNK_SIZE, CHUNK_WORLD_SIZE};
+use common::topology::Topology;
 use rustc_hash::FxHashSet;
 
 use crate::WorldCamera;
 use crate::components::chunk::Chunk;
 use crate::grid_material::GridMaterial;
-use crate::rendering::{spawn_chunk_mesh, write_chunk_to_image_data};
+use crate::rendering::{hex_rows, spawn_chunk_mesh, write_chunk_to_image_data};
+use crate::resources::coloring::AgeGradient;
 use crate::resources::interaction::GridVisible;
 use crate::resources::world::{ChunkKey, World};
 
@@ -44,12 +46,30 @@ pub fn calc_visible_chunks(
     chunks
 }
 
+/// 有限トポロジーの領域と重ならないチャンクを取り除く（無限平面では何もしない）
+pub fn retain_chunks_in_topology(chunks: &mut FxHashSet<ChunkKey>, topology: Topology) {
+    let Some(rect) = topology.bounds() else {
+        return;
+    };
+    let (min_cx, min_cy) = (
+        rect.min.0.div_euclid(CHUNK_SIZE),
+        rect.min.1.div_euclid(CHUNK_SIZE),
+    );
+    let (max_cx, max_cy) = (
+        rect.max.0.div_euclid(CHUNK_SIZE),
+        rect.max.1.div_euclid(CHUNK_SIZE),
+    );
+    chunks.retain(|&(cx, cy)| (min_cx..=max_cx).contains(&cx) && (min_cy..=max_cy).contains(&cy));
+}
+
 /// チャンクの動的管理を行うシステム
 ///
-/// カメラのビューポートに基づいて:
+/// カメラのビューポート（有限トポロジーでは領域と重なる範囲のみ）に基づいて:
 /// - 新規に見えるチャンクをスポーン
 /// - 範囲外のチャンクをデスポーン
-/// - 変更のあったチャンクのテクスチャを再描画
+/// - 変更のあったチャンクのテクスチャを再描画（色分けのグラデーションが変わったときは全て）
+///
+/// 六角形近傍では奇数行の行頭に西隣のチャンクの端のセルを描くため、西隣が変わったチャンクも再描画する。
 #[allow(clippy::too_many_arguments)]
 pub fn manage_chunks(
     mut commands: Commands,
@@ -57,6 +77,7 @@ pub fn manage_chunks(
     mut meshes: ResMut<Assets<Mesh>>,
     mut grid_materials: ResMut<Assets<GridMaterial>>,
     mut world: ResMut<World>,
+    gradient: Res<AgeGradient>,
     grid_visible: Res<GridVisible>,
     camera_query: Query<(&Transform, &Projection), With<WorldCamera>>,
     windows: Query<&Window>,
@@ -78,7 +99,8 @@ pub fn manage_chunks(
     let viewport_h = window.height();
     let camera_pos = camera_transform.translation.truncate();
 
-    let visible_chunks = calc_visible_chunks(camera_pos, camera_scale, viewport_w, viewport_h);
+    let mut visible_chunks = calc_visible_chunks(camera_pos, camera_scale, viewport_w, viewport_h);
+    retain_chunks_in_topology(&mut visible_chunks, world.topology());
 
     // 既存チャンクのマップを構築
     let mut existing_map: FxHashSet<ChunkKey> = FxHashSet::default();
@@ -107,6 +129,7 @@ pub fn manage_chunks(
                 &mut meshes,
                 &mut grid_materials,
                 &world,
+                &gradient,
                 chunk_key,
                 camera_scale,
                 grid_visible.0,
@@ -115,17 +138,21 @@ pub fn manage_chunks(
     }
 
     // dirtyチャンクのテクスチャ更新
-    if world.is_changed() {
+    if world.is_changed() || gradient.is_changed() {
+        let hexagonal = world.rule().neighborhood().is_hexagonal();
         for (_, chunk, material_handle) in &existing_chunks {
             if !visible_chunks.contains(&chunk.0) {
                 continue;
             }
-            if world.dirty_chunks().contains(&chunk.0)
+            let (cx, cy) = chunk.0;
+            let dirty = world.dirty_chunks().contains(&chunk.0)
+                || hexagonal && world.dirty_chunks().contains(&(cx - 1, cy));
+            if (gradient.is_changed() || dirty)
                 && let Some(material) = grid_materials.get(&material_handle.0)
                 && let Some(image) = images.get_mut(&material.cell_texture)
                 && let Some(ref mut data) = image.data
             {
-                write_chunk_to_image_data(data, &world, chunk.0);
+                write_chunk_to_image_data(data, &world, &gradient, chunk.0);
             }
         }
 
@@ -134,10 +161,11 @@ pub fn manage_chunks(
     }
 }
 
-/// カメラスケール・GridVisibleの変更をマテリアルuniformに反映する
+/// カメラスケール・GridVisible・六角形近傍の行のずらしをマテリアルuniformに反映する
 pub fn update_grid_uniforms(
     camera_query: Query<&Projection, With<WorldCamera>>,
     grid_visible: Res<GridVisible>,
+    world: Res<World>,
     chunk_query: Query<&MeshMaterial2d<GridMaterial>, With<Chunk>>,
     mut materials: ResMut<Assets<GridMaterial>>,
 ) {
@@ -153,6 +181,7 @@ pub fn update_grid_uniforms(
         if let Some(material) = materials.get_mut(&material_handle.0) {
             material.uniforms.camera_scale = camera_scale;
             material.uniforms.grid_visible = if grid_visible.0 { 1.0 } else { 0.0 };
+            material.uniforms.hex_rows = hex_rows(&world);
         }
     }
 }
@@ -185,4 +214,19 @@ mod tests {
         let chunks_normal = calc_visible_chunks(Vec2::ZERO, 1.0, 100.0, 100.0);
         assert!(chunks_zoomed.len() > chunks_normal.len());
     }
+
+    #[test]
+    fn finite_topology_limits_visible_chunks() {
+        use common::topology::Surface;
+
+        let mut chunks = calc_visible_chunks(Vec2::ZERO, 4.0, 200.0, 200.0);
+        let all = chunks.len();
+        retain_chunks_in_topology(&mut chunks, Topology::Infinite);
+        assert_eq!(chunks.len(), all);
+        // 領域は x, y: -64..=63 なので、チャンク -1..=0 の2x2だけが残る
+        retain_chunks_in_topology(&mut chunks, Topology::finite(Surface::Torus, 128, 128));
+        let mut keys: Vec<_> = chunks.into_iter().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec![(-1, -1), (-1, 0), (0, -1), (0, 0)]);
+    }
 }
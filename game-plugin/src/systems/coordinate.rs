@@ -1,14 +1,28 @@
 //! グリッド座標とワールド空間座標の変換
+//!
+//! 六角形近傍のルールでは奇数行のセルを半セル右にずらして描画するため、変換でもずらしを考慮する。
 
 use bevy::prelude::*;
 use common::consts::CELL_WORLD_SIZE;
 
+/// 行 `grid_y` のセルを右にずらすワールド空間での幅を返す
+///
+/// `hexagonal` が真（六角形近傍）のときは奇数行を半セルずらし、それ以外は0を返す。
+pub fn hex_row_shift(grid_y: i32, hexagonal: bool) -> f32 {
+    if hexagonal && grid_y.rem_euclid(2) == 1 {
+        CELL_WORLD_SIZE / 2.0
+    } else {
+        0.0
+    }
+}
+
 /// グリッド座標をワールド空間の座標に変換する
 ///
 /// セルの中心座標を返す。Y軸は反転（グリッドY+が画面下方向）。
-pub fn world_to_screen_pos(grid_x: i32, grid_y: i32) -> Vec2 {
+/// `hexagonal` が真なら奇数行を半セル右にずらす。
+pub fn world_to_screen_pos(grid_x: i32, grid_y: i32, hexagonal: bool) -> Vec2 {
     Vec2::new(
-        grid_x as f32 * CELL_WORLD_SIZE + CELL_WORLD_SIZE / 2.0,
+        grid_x as f32 * CELL_WORLD_SIZE + CELL_WORLD_SIZE / 2.0 + hex_row_shift(grid_y, hexagonal),
         -(grid_y as f32 * CELL_WORLD_SIZE + CELL_WORLD_SIZE / 2.0),
     )
 }
@@ -16,9 +30,11 @@ pub fn world_to_screen_pos(grid_x: i32, grid_y: i32) -> Vec2 {
 /// ワールド空間の座標をグリッド座標に変換する
 ///
 /// 無限フィールドなので常に有効な座標を返す。
-pub fn screen_to_grid_coords(world_pos: Vec2) -> (i32, i32) {
-    let grid_x = (world_pos.x / CELL_WORLD_SIZE).floor() as i32;
+/// `hexagonal` が真なら行を求めてから、その行のずらし分を戻して列を求める。
+pub fn screen_to_grid_coords(world_pos: Vec2, hexagonal: bool) -> (i32, i32) {
     let grid_y = (-world_pos.y / CELL_WORLD_SIZE).floor() as i32;
+    let grid_x =
+        ((world_pos.x - hex_row_shift(grid_y, hexagonal)) / CELL_WORLD_SIZE).floor() as i32;
     (grid_x, grid_y)
 }
 
@@ -44,27 +60,27 @@ mod tests {
     #[test]
     fn origin_maps_to_cell_0_0_center() {
         // セル(0,0)の中心は (0.5, -0.5)
-        let pos = world_to_screen_pos(0, 0);
+        let pos = world_to_screen_pos(0, 0, false);
         assert_eq!(pos, Vec2::new(0.5, -0.5));
     }
 
     #[test]
     fn negative_cell_position() {
-        let pos = world_to_screen_pos(-1, -1);
+        let pos = world_to_screen_pos(-1, -1, false);
         assert_eq!(pos, Vec2::new(-0.5, 0.5));
     }
 
     #[test]
     fn screen_to_grid_at_origin() {
         // ワールド座標(0.5, -0.5)はセル(0, 0)
-        let (gx, gy) = screen_to_grid_coords(Vec2::new(0.5, -0.5));
+        let (gx, gy) = screen_to_grid_coords(Vec2::new(0.5, -0.5), false);
         assert_eq!((gx, gy), (0, 0));
     }
 
     #[test]
     fn screen_to_grid_negative() {
         // ワールド座標(-0.5, 0.5)はセル(-1, -1)
-        let (gx, gy) = screen_to_grid_coords(Vec2::new(-0.5, 0.5));
+        let (gx, gy) = screen_to_grid_coords(Vec2::new(-0.5, 0.5), false);
         assert_eq!((gx, gy), (-1, -1));
     }
 
@@ -73,7 +89,7 @@ mod tests {
         // ワールド座標(0.0, 0.0)はセル(0, -1)
         // x=0.0 → floor(0.0/1.0) = 0
         // y=0.0 → floor(-0.0/1.0) = floor(-0.0) = 0... but -y = -0.0
-        let (gx, gy) = screen_to_grid_coords(Vec2::new(0.0, 0.0));
+        let (gx, gy) = screen_to_grid_coords(Vec2::new(0.0, 0.0), false);
         assert_eq!(gx, 0);
         assert_eq!(gy, 0);
     }
@@ -82,8 +98,8 @@ mod tests {
     fn roundtrip_positive_coordinates() {
         for gx in [0, 1, 10, 50, 100] {
             for gy in [0, 1, 10, 50, 100] {
-                let screen_pos = world_to_screen_pos(gx, gy);
-                let (rx, ry) = screen_to_grid_coords(screen_pos);
+                let screen_pos = world_to_screen_pos(gx, gy, false);
+                let (rx, ry) = screen_to_grid_coords(screen_pos, false);
                 assert_eq!((rx, ry), (gx, gy), "roundtrip failed for ({}, {})", gx, gy);
             }
         }
@@ -93,13 +109,29 @@ mod tests {
     fn roundtrip_negative_coordinates() {
         for gx in [-100, -50, -10, -1] {
             for gy in [-100, -50, -10, -1] {
-                let screen_pos = world_to_screen_pos(gx, gy);
-                let (rx, ry) = screen_to_grid_coords(screen_pos);
+                let screen_pos = world_to_screen_pos(gx, gy, false);
+                let (rx, ry) = screen_to_grid_coords(screen_pos, false);
                 assert_eq!((rx, ry), (gx, gy), "roundtrip failed for ({}, {})", gx, gy);
             }
         }
     }
 
+    #[test]
+    fn hexagonal_odd_rows_shift_half_cell() {
+        assert_eq!(world_to_screen_pos(0, 0, true), Vec2::new(0.5, -0.5));
+        assert_eq!(world_to_screen_pos(0, 1, true), Vec2::new(1.0, -1.5));
+        assert_eq!(world_to_screen_pos(-1, -1, true), Vec2::new(0.0, 0.5));
+        // 奇数行の x=0.25 は西隣のセルの右半分
+        assert_eq!(screen_to_grid_coords(Vec2::new(0.25, -1.5), true), (-1, 1));
+        assert_eq!(screen_to_grid_coords(Vec2::new(0.25, -1.5), false), (0, 1));
+        for gx in [-3, 0, 2] {
+            for gy in [-3, -2, 0, 1] {
+                let screen_pos = world_to_screen_pos(gx, gy, true);
+                assert_eq!(screen_to_grid_coords(screen_pos, true), (gx, gy));
+            }
+        }
+    }
+
     #[test]
     fn cursor_in_world_viewport_with_scale_1() {
         assert!(is_cursor_over_world_viewport(Vec2::new(500., 0.), 1.0, 720));
@@ -0,0 +1,206 @@
+//! 早送り先世代の入力欄の操作
+//!
+//! 入力欄をクリックしてフォーカスし、数字キーで世代数を入力する。
+//! Enterキーまたは Jump ボタンで `FastForwardEvent` を発火する。
+//! 早送り中は入力欄に進み具合を表示し、Escキーで中止できる。
+
+use bevy::prelude::*;
+use common::consts::{
+    ACCENT_GREEN, BORDER_SUBTLE, FAST_FORWARD_MAX_DIGITS, TEXT_MUTED, TEXT_PRIMARY,
+};
+
+use crate::components::screen::{FastForwardInputBox, FastForwardText};
+use crate::events::FastForwardEvent;
+use crate::resources::fast_forward::FastForwardJob;
+use crate::resources::interaction::FastForwardInput;
+use crate::resources::world::World;
+
+/// 入力欄が空のときに表示するプレースホルダー
+const PLACEHOLDER: &str = "Gen...";
+
+/// キー入力を入力状態に反映する純粋関数
+///
+/// - 数字キー: 末尾に追加（`FAST_FORWARD_MAX_DIGITS` 桁まで）
+/// - Backspace: 末尾を削除
+/// - Enter: 入力中の世代数を返してフォーカスを外す
+pub(crate) fn apply_fast_forward_key(input: &mut FastForwardInput, key: KeyCode) -> Option<u64> {
+    match key {
+        KeyCode::Enter | KeyCode::NumpadEnter => {
+            input.focused = false;
+            return input.target();
+        }
+        KeyCode::Backspace => {
+            input.digits.pop();
+        }
+        _ => {
+            if let Some(digit) = key_to_digit(key)
+                && input.digits.len() < FAST_FORWARD_MAX_DIGITS
+            {
+                input.digits.push(digit);
+            }
+        }
+    }
+    None
+}
+
+/// 数字キーを対応する文字に変換する
+fn key_to_digit(key: KeyCode) -> Option<char> {
+    let digit = match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => '0',
+        KeyCode::Digit1 | KeyCode::Numpad1 => '1',
+        KeyCode::Digit2 | KeyCode::Numpad2 => '2',
+        KeyCode::Digit3 | KeyCode::Numpad3 => '3',
+        KeyCode::Digit4 | KeyCode::Numpad4 => '4',
+        KeyCode::Digit5 | KeyCode::Numpad5 => '5',
+        KeyCode::Digit6 | KeyCode::Numpad6 => '6',
+        KeyCode::Digit7 | KeyCode::Numpad7 => '7',
+        KeyCode::Digit8 | KeyCode::Numpad8 => '8',
+        KeyCode::Digit9 | KeyCode::Numpad9 => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+
+/// 入力欄のクリックハンドラ: フォーカスを切り替える
+pub fn handle_fast_forward_input_click(
+    _click: On<Pointer<Click>>,
+    mut input: ResMut<FastForwardInput>,
+) {
+    input.focused = !input.focused;
+}
+
+/// Jumpボタンのクリックハンドラ: 入力した世代まで早送りする
+pub fn handle_fast_forward(
+    _click: On<Pointer<Click>>,
+    mut input: ResMut<FastForwardInput>,
+    mut fast_forward_event_writer: MessageWriter<FastForwardEvent>,
+) {
+    input.focused = false;
+    if let Some(target) = input.target() {
+        fast_forward_event_writer.write(FastForwardEvent(target));
+    }
+}
+
+/// フォーカス中の入力欄へのキーボード入力を処理するシステム
+pub fn fast_forward_input_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut input: ResMut<FastForwardInput>,
+    mut fast_forward_event_writer: MessageWriter<FastForwardEvent>,
+) {
+    if !input.focused {
+        return;
+    }
+    for &key in keys.get_just_pressed() {
+        if let Some(target) = apply_fast_forward_key(&mut input, key) {
+            fast_forward_event_writer.write(FastForwardEvent(target));
+        }
+    }
+}
+
+/// 早送り中にEscキーで早送りを中止するシステム
+///
+/// 中止に使ったEscキーでメニューに戻らないよう、押下を取り消す。
+pub fn fast_forward_cancel_keyboard_handling(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut job: ResMut<FastForwardJob>,
+) {
+    if job.is_running() && keys.just_pressed(KeyCode::Escape) {
+        job.cancel();
+        keys.clear_just_pressed(KeyCode::Escape);
+    }
+}
+
+/// 入力状態と早送りの進み具合に合わせて入力欄の表示を更新するシステム
+pub fn update_fast_forward_input(
+    input: Res<FastForwardInput>,
+    job: Res<FastForwardJob>,
+    world: Res<World>,
+    mut query_text: Query<(&mut Text, &mut TextColor), With<FastForwardText>>,
+    mut query_box: Query<&mut BorderColor, With<FastForwardInputBox>>,
+) {
+    if !input.is_changed() && !job.is_changed() {
+        return;
+    }
+    if let Ok((mut text, mut color)) = query_text.single_mut() {
+        if let Some(progress) = job.progress(&world) {
+            **text = format!("{:.0}% (Esc)", progress * 100.0);
+            color.0 = ACCENT_GREEN;
+        } else if input.digits.is_empty() && !input.focused {
+            **text = PLACEHOLDER.to_string();
+            color.0 = TEXT_MUTED;
+        } else {
+            **text = input.digits.clone();
+            color.0 = TEXT_PRIMARY;
+        }
+    }
+    if let Ok(mut border) = query_box.single_mut() {
+        *border = BorderColor::all(if input.focused {
+            ACCENT_GREEN
+        } else {
+            BORDER_SUBTLE
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focused(digits: &str) -> FastForwardInput {
+        FastForwardInput {
+            digits: digits.to_string(),
+            focused: true,
+        }
+    }
+
+    #[test]
+    fn digits_are_appended() {
+        let mut input = focused("");
+        apply_fast_forward_key(&mut input, KeyCode::Digit1);
+        apply_fast_forward_key(&mut input, KeyCode::Numpad0);
+        apply_fast_forward_key(&mut input, KeyCode::Digit5);
+        assert_eq!(input.digits, "105");
+        assert_eq!(input.target(), Some(105));
+    }
+
+    #[test]
+    fn backspace_removes_last_digit() {
+        let mut input = focused("42");
+        apply_fast_forward_key(&mut input, KeyCode::Backspace);
+        assert_eq!(input.digits, "4");
+    }
+
+    #[test]
+    fn enter_submits_and_unfocuses() {
+        let mut input = focused("1000");
+        assert_eq!(
+            apply_fast_forward_key(&mut input, KeyCode::Enter),
+            Some(1000)
+        );
+        assert!(!input.focused);
+    }
+
+    #[test]
+    fn enter_on_empty_input_submits_nothing() {
+        let mut input = focused("");
+        assert_eq!(
+            apply_fast_forward_key(&mut input, KeyCode::NumpadEnter),
+            None
+        );
+    }
+
+    #[test]
+    fn non_digit_keys_are_ignored() {
+        let mut input = focused("7");
+        apply_fast_forward_key(&mut input, KeyCode::KeyW);
+        apply_fast_forward_key(&mut input, KeyCode::Space);
+        assert_eq!(input, focused("7"));
+    }
+
+    #[test]
+    fn digits_are_limited() {
+        let mut input = focused(&"9".repeat(FAST_FORWARD_MAX_DIGITS));
+        apply_fast_forward_key(&mut input, KeyCode::Digit1);
+        assert_eq!(input.digits.len(), FAST_FORWARD_MAX_DIGITS);
+    }
+}
@@ -1,25 +1,32 @@
-//! グリッドクリックとセルハイライトの処理
+//! グリッドクリック・セルハイライト・トポロジー境界線の処理
 
 use bevy::prelude::*;
-use common::consts::calc_viewport_sizes;
+use common::consts::{CELL_WORLD_SIZE, TOPOLOGY_BORDER_SCREEN_WIDTH, calc_viewport_sizes};
 
 use crate::WorldCamera;
-use crate::components::screen::CellHighlight;
-use crate::resources::interaction::{DragState, HoveredCell};
+use crate::components::screen::{CellHighlight, TopologyBorder};
+use crate::resources::clipboard::GridRect;
+use crate::resources::history::EditHistory;
+use crate::resources::interaction::{DragState, HoveredCell, PaintState};
 use crate::resources::world::World;
 use crate::systems::coordinate::{
     is_cursor_over_world_viewport, screen_to_grid_coords, world_to_screen_pos,
 };
 
-/// グリッド上の左クリックを処理し、クリックされたセルをトグルする
+/// グリッド上の左クリックを処理し、クリックされたセルを塗る状態でトグルする
 ///
+/// 塗る状態は `PaintState`（Life-like ルールでは常に生存）で、既にその状態のセルは死亡させる。
+/// Wireworld などの多状態オートマトンでは、導線や電子などルールの各状態を塗り分けられる。
 /// ドラッグ操作後のリリースではセルをトグルしない。
+/// トグルは編集履歴に記録する。
 pub fn handle_grid_click(
     mouse: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform), With<WorldCamera>>,
     mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
     drag_state: Res<DragState>,
+    paint_state: Res<PaintState>,
 ) {
     if !mouse.just_released(MouseButton::Left) {
         return;
@@ -44,17 +51,22 @@ pub fn handle_grid_click(
     let Ok(world_pos) = camera.viewport_to_world_2d(transform, cursor_pos) else {
         return;
     };
-    let (gx, gy) = screen_to_grid_coords(world_pos);
-    world.toggle_cell(gx, gy);
+    let hexagonal = world.rule().neighborhood().is_hexagonal();
+    let (gx, gy) = screen_to_grid_coords(world_pos, hexagonal);
+    let state = paint_state.state(world.rule());
+    let diff = world.paint_cell(gx, gy, state);
+    history.record(diff, &world);
 }
 
 /// マウスカーソル位置に応じてセルハイライトを更新する
 ///
 /// カーソルがワールドビューポート上にある場合は該当セル位置にハイライトを表示する。
 /// ビューポート外では非表示にする。
+/// 六角形近傍のルールでは、奇数行を半セルずらした描画に合わせてセルを求める。
 pub fn update_cell_highlight(
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform), With<WorldCamera>>,
+    world: Res<World>,
     mut highlight_query: Query<(&mut Transform, &mut Visibility), With<CellHighlight>>,
     mut hovered: ResMut<HoveredCell>,
 ) {
@@ -67,12 +79,13 @@ pub fn update_cell_highlight(
 
     let scale_factor = window.resolution.scale_factor();
     let sizes = calc_viewport_sizes(window.physical_width(), window.physical_height());
+    let hexagonal = world.rule().neighborhood().is_hexagonal();
 
     let grid_coords = window
         .cursor_position()
         .filter(|&pos| is_cursor_over_world_viewport(pos, scale_factor, sizes.main_height))
         .and_then(|cursor_pos| camera.viewport_to_world_2d(cam_transform, cursor_pos).ok())
-        .map(screen_to_grid_coords);
+        .map(|world_pos| screen_to_grid_coords(world_pos, hexagonal));
 
     let Ok((mut transform, mut vis)) = highlight_query.single_mut() else {
         return;
@@ -81,7 +94,7 @@ pub fn update_cell_highlight(
     match grid_coords {
         Some((gx, gy)) => {
             *vis = Visibility::Inherited;
-            let pos = world_to_screen_pos(gx, gy);
+            let pos = world_to_screen_pos(gx, gy, hexagonal);
             transform.translation.x = pos.x;
             transform.translation.y = pos.y;
             transform.translation.z = 1.0;
@@ -94,3 +107,72 @@ pub fn update_cell_highlight(
         }
     }
 }
+
+/// 矩形を囲む境界線の4辺（上・下・左・右）の中心座標とサイズを計算する
+///
+/// 線はセルの外縁の上に `thickness` の太さで引き、四隅は辺どうしで重ねて埋める。
+pub fn border_segments(rect: GridRect, thickness: f32) -> [(Vec2, Vec2); 4] {
+    let left = rect.min.0 as f32 * CELL_WORLD_SIZE;
+    let right = (rect.max.0 + 1) as f32 * CELL_WORLD_SIZE;
+    // Y軸反転: グリッドY+が画面下方向
+    let top = -(rect.min.1 as f32 * CELL_WORLD_SIZE);
+    let bottom = -((rect.max.1 + 1) as f32 * CELL_WORLD_SIZE);
+    let center = Vec2::new((left + right) / 2.0, (top + bottom) / 2.0);
+    let horizontal = Vec2::new(right - left + thickness, thickness);
+    let vertical = Vec2::new(thickness, top - bottom + thickness);
+    [
+        (Vec2::new(center.x, top), horizontal),
+        (Vec2::new(center.x, bottom), horizontal),
+        (Vec2::new(left, center.y), vertical),
+        (Vec2::new(right, center.y), vertical),
+    ]
+}
+
+/// 有限トポロジーの境界線をワールドの領域とカメラの拡大率に合わせるシステム
+///
+/// 無限平面では非表示にする。
+pub fn update_topology_border(
+    world: Res<World>,
+    camera_query: Query<&Projection, With<WorldCamera>>,
+    mut border_query: Query<(
+        &TopologyBorder,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+) {
+    let camera_scale = match camera_query.single() {
+        Ok(Projection::Orthographic(ortho)) => ortho.scale,
+        _ => 1.0,
+    };
+    let segments = world
+        .topology()
+        .bounds()
+        .map(|rect| border_segments(rect, TOPOLOGY_BORDER_SCREEN_WIDTH * camera_scale));
+    for (border, mut transform, mut sprite, mut visibility) in &mut border_query {
+        match segments {
+            Some(segments) => {
+                let (center, size) = segments[border.0];
+                transform.translation = center.extend(3.0);
+                sprite.custom_size = Some(size);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn border_segments_surround_rect() {
+        let rect = GridRect::from_corners((-2, -1), (1, 0));
+        let [top, bottom, left, right] = border_segments(rect, 0.5);
+        assert_eq!(top, (Vec2::new(0.0, 1.0), Vec2::new(4.5, 0.5)));
+        assert_eq!(bottom, (Vec2::new(0.0, -1.0), Vec2::new(4.5, 0.5)));
+        assert_eq!(left, (Vec2::new(-2.0, 0.0), Vec2::new(0.5, 2.5)));
+        assert_eq!(right, (Vec2::new(2.0, 0.0), Vec2::new(0.5, 2.5)));
+    }
+}
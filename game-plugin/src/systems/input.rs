@@ -1,8 +1,15 @@
 //! キーボード・マウス入力のハンドリング
 //!
 //! スペースキーのステートマシン（短押し/長押し判定）、
+//! Ctrl+Z / Ctrl+Shift+Z による取り消し・やり直し、Bキーによる巻き戻し、
+//! Iキーによる統計パネルの表示切り替え、Gキーによるセルの年齢による色分けの切り替え、
+//! Hキーによる活動度ヒートマップの表示切り替え、Nキーによるセル編集で塗る状態の切り替え、
+//! Ctrl+C/X/V・Deleteによる範囲選択のクリップボード操作、
+//! 貼り付け・スタンプ中のR/F/V/Tキーによる回転・反転、
 //! WASD/QEキーによるカメラ操作、マウスホイールズーム、
 //! 左クリックドラッグパンを処理する。
+//! 修飾キーとの組み合わせ・Escape・貼り付け中のキー以外は `Settings::key_bindings` で割り当てを変えられ、
+//! 上記はデフォルトの割り当てである。
 
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
@@ -10,13 +17,22 @@ use common::consts::{
     CAMERA_PAN_SPEED, CAMERA_SCALE_STEP, DRAG_THRESHOLD, MAX_CAMERA_SCALE, MIN_CAMERA_SCALE,
     MOUSE_WHEEL_ZOOM_SENSITIVITY, calc_viewport_sizes,
 };
+use common::patterns::CellTransform;
+use common::settings::Settings;
+use common::settings::key_bindings::KeyAction;
 use common::states::GameState;
 
 use crate::WorldCamera;
-use crate::events::ProgressGenerationEvent;
-use crate::resources::interaction::DragState;
+use crate::events::{
+    ClipboardAction, ClipboardEvent, ProgressGenerationEvent, RedoEvent, StepBackEvent, UndoEvent,
+};
+use crate::resources::clipboard::{Clipboard, Selection};
+use crate::resources::interaction::{DragState, PaintState};
+use crate::resources::stats::StatsVisible;
 use crate::resources::timer::SpaceKeyTimer;
+use crate::resources::world::World;
 use crate::states::SimulationState;
+use crate::systems::button_handler::toggle_heatmap;
 use crate::systems::coordinate::is_cursor_over_world_viewport;
 
 /// スペースキーの入力状態をまとめた構造体
@@ -67,8 +83,10 @@ pub(crate) fn resolve_space_key_action(input: &SpaceKeyInput) -> SpaceKeyAction
 ///
 /// - スペースキー: 短押しで1世代進める、長押しで自動シミュレーション開始/停止
 /// - Escapeキー: メニュー画面に遷移
+#[allow(clippy::too_many_arguments)]
 pub fn game_input_keyboard_handling(
     keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
     simulation_state: Res<State<SimulationState>>,
     mut simulation_next_state: ResMut<NextState<SimulationState>>,
     mut game_next_state: ResMut<NextState<GameState>>,
@@ -76,11 +94,12 @@ pub fn game_input_keyboard_handling(
     time: Res<Time>,
     mut space_key_timer: ResMut<SpaceKeyTimer>,
 ) {
+    let step_key = settings.key_bindings.key(KeyAction::Step);
     let space_input = SpaceKeyInput {
-        just_pressed: keys.just_pressed(KeyCode::Space),
-        pressed: keys.pressed(KeyCode::Space),
-        just_released: keys.just_released(KeyCode::Space),
-        hold_timer_finished: keys.pressed(KeyCode::Space)
+        just_pressed: keys.just_pressed(step_key),
+        pressed: keys.pressed(step_key),
+        just_released: keys.just_released(step_key),
+        hold_timer_finished: keys.pressed(step_key)
             && space_key_timer.0.tick(time.delta()).is_finished(),
         is_paused: *simulation_state.get() == SimulationState::Paused,
     };
@@ -107,34 +126,237 @@ pub fn game_input_keyboard_handling(
     }
 }
 
+/// 編集履歴を操作するキー入力から決定されるアクション
+#[derive(Debug, PartialEq)]
+pub(crate) enum HistoryAction {
+    /// Ctrl+Z: 取り消し
+    Undo,
+    /// Ctrl+Shift+Z: やり直し
+    Redo,
+}
+
+/// 修飾キーとZキーの状態から編集履歴のアクションを決定する純粋関数
+pub(crate) fn resolve_history_key(
+    ctrl: bool,
+    shift: bool,
+    z_just_pressed: bool,
+) -> Option<HistoryAction> {
+    if !ctrl || !z_just_pressed {
+        return None;
+    }
+    if shift {
+        Some(HistoryAction::Redo)
+    } else {
+        Some(HistoryAction::Undo)
+    }
+}
+
+/// Ctrl+Z / Ctrl+Shift+Z で取り消し・やり直しイベントを送るシステム
+///
+/// macOS向けにCommandキー（Super）もCtrlと同様に扱う。
+pub fn undo_redo_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut undo_event_writer: MessageWriter<UndoEvent>,
+    mut redo_event_writer: MessageWriter<RedoEvent>,
+) {
+    let ctrl = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    match resolve_history_key(ctrl, shift, keys.just_pressed(KeyCode::KeyZ)) {
+        Some(HistoryAction::Undo) => {
+            undo_event_writer.write(UndoEvent);
+        }
+        Some(HistoryAction::Redo) => {
+            redo_event_writer.write(RedoEvent);
+        }
+        None => {}
+    }
+}
+
+/// クリップボード操作に関わるキーの入力状態をまとめた構造体
+#[derive(Debug, Default)]
+pub(crate) struct ClipboardKeyInput {
+    pub ctrl: bool,
+    pub c_just_pressed: bool,
+    pub x_just_pressed: bool,
+    pub v_just_pressed: bool,
+    pub r_just_pressed: bool,
+    pub f_just_pressed: bool,
+    pub t_just_pressed: bool,
+    pub delete_just_pressed: bool,
+    pub escape_just_pressed: bool,
+    /// 範囲を選択中か
+    pub has_selection: bool,
+    /// 貼り付け・スタンプの配置位置を選択中か
+    pub is_placing: bool,
+}
+
+/// キー入力からクリップボード操作を決定する純粋関数
+///
+/// Escapeは選択中・配置中のときのみ取り消しとして扱う（それ以外はメニュー遷移）。
+/// 配置中は R: 時計回り回転、F: 左右反転、V: 上下反転、T: 転置。
+pub(crate) fn resolve_clipboard_key(input: &ClipboardKeyInput) -> Option<ClipboardAction> {
+    if input.ctrl && input.c_just_pressed {
+        return Some(ClipboardAction::Copy);
+    }
+    if input.ctrl && input.x_just_pressed {
+        return Some(ClipboardAction::Cut);
+    }
+    if input.ctrl && input.v_just_pressed {
+        return Some(ClipboardAction::Paste);
+    }
+    if input.is_placing && !input.ctrl {
+        let transform = if input.r_just_pressed {
+            Some(CellTransform::RotateClockwise)
+        } else if input.f_just_pressed {
+            Some(CellTransform::FlipHorizontal)
+        } else if input.v_just_pressed {
+            Some(CellTransform::FlipVertical)
+        } else if input.t_just_pressed {
+            Some(CellTransform::Transpose)
+        } else {
+            None
+        };
+        if let Some(transform) = transform {
+            return Some(ClipboardAction::Transform(transform));
+        }
+    }
+    if input.delete_just_pressed {
+        return Some(ClipboardAction::Delete);
+    }
+    if input.escape_just_pressed && (input.has_selection || input.is_placing) {
+        return Some(ClipboardAction::Cancel);
+    }
+    None
+}
+
+/// Ctrl+C/X/V・Delete・Escapeでクリップボード操作イベントを送るシステム
+///
+/// 選択・配置の取り消しに使ったEscapeは消費し、メニューへ遷移しないようにする。
+pub fn clipboard_keyboard_handling(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    clipboard: Res<Clipboard>,
+    mut clipboard_event_writer: MessageWriter<ClipboardEvent>,
+) {
+    let input = ClipboardKeyInput {
+        ctrl: keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]),
+        c_just_pressed: keys.just_pressed(KeyCode::KeyC),
+        x_just_pressed: keys.just_pressed(KeyCode::KeyX),
+        v_just_pressed: keys.just_pressed(KeyCode::KeyV),
+        r_just_pressed: keys.just_pressed(KeyCode::KeyR),
+        f_just_pressed: keys.just_pressed(KeyCode::KeyF),
+        t_just_pressed: keys.just_pressed(KeyCode::KeyT),
+        delete_just_pressed: keys.just_pressed(KeyCode::Delete),
+        escape_just_pressed: keys.just_pressed(KeyCode::Escape),
+        has_selection: selection.rect().is_some(),
+        is_placing: clipboard.is_placing(),
+    };
+    let Some(action) = resolve_clipboard_key(&input) else {
+        return;
+    };
+    if action == ClipboardAction::Cancel {
+        keys.clear_just_pressed(KeyCode::Escape);
+    }
+    clipboard_event_writer.write(ClipboardEvent(action));
+}
+
+/// Bキーで1世代巻き戻すイベントを送るシステム
+pub fn step_back_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut step_back_event_writer: MessageWriter<StepBackEvent>,
+) {
+    if keys.just_pressed(settings.key_bindings.key(KeyAction::StepBack)) {
+        step_back_event_writer.write(StepBackEvent);
+    }
+}
+
+/// Iキーで統計パネルの表示を切り替えるシステム
+pub fn stats_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut stats_visible: ResMut<StatsVisible>,
+) {
+    if keys.just_pressed(settings.key_bindings.key(KeyAction::ToggleStats)) {
+        stats_visible.0 = !stats_visible.0;
+    }
+}
+
+/// Gキーでセルの年齢による色分けを切り替えるシステム
+pub fn age_coloring_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut world: ResMut<World>,
+) {
+    if keys.just_pressed(settings.key_bindings.key(KeyAction::ToggleAgeColoring)) {
+        let enabled = !world.is_age_tracking();
+        world.set_age_tracking(enabled);
+    }
+}
+
+/// Hキーで活動度ヒートマップの表示を切り替えるシステム
+pub fn heatmap_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut world: ResMut<World>,
+    settings: Res<Settings>,
+) {
+    if keys.just_pressed(settings.key_bindings.key(KeyAction::ToggleHeatmap)) {
+        toggle_heatmap(&mut world, &settings);
+    }
+}
+
+/// Nキーでセル編集で塗る状態を切り替えるシステム
+pub fn paint_state_keyboard_handling(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    world: Res<World>,
+    mut paint_state: ResMut<PaintState>,
+) {
+    if keys.just_pressed(settings.key_bindings.key(KeyAction::CyclePaintState)) {
+        paint_state.cycle(world.rule());
+    }
+}
+
 /// WASD/QEキーによるカメラ操作システム
 ///
 /// - W/A/S/D: カメラのパン（上下左右移動）
 /// - Q: ズームアウト、E: ズームイン
 pub fn game_input_zoom_handling(
     keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
     mut camera_query: Query<(&mut Transform, &mut Projection), With<WorldCamera>>,
 ) {
     let Ok((mut transform, mut projection)) = camera_query.single_mut() else {
         return;
     };
-    if keys.just_pressed(KeyCode::KeyW) {
+    let just_pressed = |action| keys.just_pressed(settings.key_bindings.key(action));
+    if just_pressed(KeyAction::PanUp) {
         transform.translation.y += CAMERA_PAN_SPEED;
     }
-    if keys.just_pressed(KeyCode::KeyS) {
+    if just_pressed(KeyAction::PanDown) {
         transform.translation.y -= CAMERA_PAN_SPEED;
     }
-    if keys.just_pressed(KeyCode::KeyA) {
+    if just_pressed(KeyAction::PanLeft) {
         transform.translation.x -= CAMERA_PAN_SPEED;
     }
-    if keys.just_pressed(KeyCode::KeyD) {
+    if just_pressed(KeyAction::PanRight) {
         transform.translation.x += CAMERA_PAN_SPEED;
     }
     if let Projection::Orthographic(ref mut ortho) = *projection {
-        if keys.just_pressed(KeyCode::KeyQ) {
+        if just_pressed(KeyAction::ZoomOut) {
             ortho.scale = (ortho.scale + CAMERA_SCALE_STEP).min(MAX_CAMERA_SCALE);
         }
-        if keys.just_pressed(KeyCode::KeyE) {
+        if just_pressed(KeyAction::ZoomIn) {
             ortho.scale = (ortho.scale - CAMERA_SCALE_STEP).max(MIN_CAMERA_SCALE);
         }
     }
@@ -189,8 +411,10 @@ pub fn mouse_wheel_zoom(
 ///
 /// クリック開始位置からの移動量が閾値を超えるとドラッグモードに入り、
 /// カーソル移動量に応じてカメラを移動する。
+/// Shiftを押しながらのドラッグは範囲選択に使うためパンしない。
 pub fn mouse_drag_pan(
     mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
     windows: Query<&Window>,
     mut camera_query: Query<(&mut Transform, &Projection), With<WorldCamera>>,
     mut drag_state: ResMut<DragState>,
@@ -201,6 +425,9 @@ pub fn mouse_drag_pan(
 
     // 左クリック開始
     if mouse.just_pressed(MouseButton::Left) {
+        if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            return;
+        }
         if let Some(cursor_pos) = window.cursor_position() {
             let scale_factor = window.resolution.scale_factor();
             let sizes = calc_viewport_sizes(window.physical_width(), window.physical_height());
@@ -314,6 +541,165 @@ mod tests {
         assert_eq!(result, SpaceKeyAction::None);
     }
 
+    // --- 取り消し・やり直しキー判定テスト ---
+
+    #[test]
+    fn ctrl_z_returns_undo() {
+        assert_eq!(
+            resolve_history_key(true, false, true),
+            Some(HistoryAction::Undo)
+        );
+    }
+
+    #[test]
+    fn ctrl_shift_z_returns_redo() {
+        assert_eq!(
+            resolve_history_key(true, true, true),
+            Some(HistoryAction::Redo)
+        );
+    }
+
+    #[test]
+    fn z_without_ctrl_returns_none() {
+        assert_eq!(resolve_history_key(false, true, true), None);
+        assert_eq!(resolve_history_key(false, false, true), None);
+    }
+
+    #[test]
+    fn ctrl_without_z_returns_none() {
+        assert_eq!(resolve_history_key(true, true, false), None);
+    }
+
+    // --- クリップボード操作キー判定テスト ---
+
+    #[test]
+    fn ctrl_c_x_v_resolve_to_clipboard_actions() {
+        let copy = ClipboardKeyInput {
+            ctrl: true,
+            c_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&copy), Some(ClipboardAction::Copy));
+        let cut = ClipboardKeyInput {
+            ctrl: true,
+            x_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&cut), Some(ClipboardAction::Cut));
+        let paste = ClipboardKeyInput {
+            ctrl: true,
+            v_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&paste), Some(ClipboardAction::Paste));
+    }
+
+    #[test]
+    fn clipboard_letters_without_ctrl_return_none() {
+        let input = ClipboardKeyInput {
+            c_just_pressed: true,
+            x_just_pressed: true,
+            v_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&input), None);
+    }
+
+    #[test]
+    fn transform_keys_apply_only_while_pasting() {
+        let idle = ClipboardKeyInput {
+            r_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&idle), None);
+        for (input, expected) in [
+            (
+                ClipboardKeyInput {
+                    r_just_pressed: true,
+                    ..default()
+                },
+                CellTransform::RotateClockwise,
+            ),
+            (
+                ClipboardKeyInput {
+                    f_just_pressed: true,
+                    ..default()
+                },
+                CellTransform::FlipHorizontal,
+            ),
+            (
+                ClipboardKeyInput {
+                    v_just_pressed: true,
+                    ..default()
+                },
+                CellTransform::FlipVertical,
+            ),
+            (
+                ClipboardKeyInput {
+                    t_just_pressed: true,
+                    ..default()
+                },
+                CellTransform::Transpose,
+            ),
+        ] {
+            let input = ClipboardKeyInput {
+                is_placing: true,
+                ..input
+            };
+            assert_eq!(
+                resolve_clipboard_key(&input),
+                Some(ClipboardAction::Transform(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn ctrl_v_while_pasting_still_pastes() {
+        let input = ClipboardKeyInput {
+            ctrl: true,
+            v_just_pressed: true,
+            is_placing: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&input), Some(ClipboardAction::Paste));
+    }
+
+    #[test]
+    fn delete_key_returns_delete() {
+        let input = ClipboardKeyInput {
+            delete_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&input), Some(ClipboardAction::Delete));
+    }
+
+    #[test]
+    fn escape_cancels_only_while_selecting_or_pasting() {
+        let idle = ClipboardKeyInput {
+            escape_just_pressed: true,
+            ..default()
+        };
+        assert_eq!(resolve_clipboard_key(&idle), None);
+        let selecting = ClipboardKeyInput {
+            escape_just_pressed: true,
+            has_selection: true,
+            ..default()
+        };
+        assert_eq!(
+            resolve_clipboard_key(&selecting),
+            Some(ClipboardAction::Cancel)
+        );
+        let pasting = ClipboardKeyInput {
+            escape_just_pressed: true,
+            is_placing: true,
+            ..default()
+        };
+        assert_eq!(
+            resolve_clipboard_key(&pasting),
+            Some(ClipboardAction::Cancel)
+        );
+    }
+
     // --- マウスホイールズーム計算テスト ---
 
     #[test]
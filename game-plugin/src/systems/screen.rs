@@ -2,16 +2,19 @@
 
 use bevy::prelude::*;
 use common::{
-    consts::{BG_SURFACE, SPACING_MD, SPACING_SM},
+    consts::{BG_SURFACE, SPACING_MD, SPACING_SM, SPACING_XS},
     resources::GameAssets,
     ui::handle_screen_button_out,
 };
 
 use crate::components::{action::GameButtonAction, screen::OnGameScreen, slider::SliderKind};
 use crate::layer::Layer;
-use crate::rendering::spawn_cell_highlight;
+use crate::rendering::{
+    spawn_cell_highlight, spawn_paste_ghost, spawn_selection_overlay, spawn_topology_border,
+};
 use crate::resources::world::World;
 use crate::systems::button_handler::*;
+use crate::systems::fast_forward::handle_fast_forward;
 use crate::systems::ui::*;
 
 /// ゲーム画面の全UIを構築するシステム
@@ -37,22 +40,43 @@ pub fn spawn_screen(mut commands: Commands, world: Res<World>, game_assets: Res<
             BackgroundColor(BG_SURFACE),
         ))
         .with_children(|p| {
-            // Generation counter
-            spawn_generation_text(p, &game_assets, world.generation_count);
+            // Generation counter + Cycle + Rule + Engine
+            p.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(SPACING_XS),
+                ..default()
+            })
+            .with_children(|p| {
+                p.spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(SPACING_SM),
+                    ..default()
+                })
+                .with_children(|p| {
+                    spawn_generation_text(p, &game_assets, world.generation_count);
+                    spawn_cycle_text(p, &game_assets);
+                });
+                spawn_rule_text(p, &game_assets, world.rule());
+                spawn_engine_text(p, &game_assets, world.engine_kind());
+            });
 
-            // Simulation controls: Start/Stop toggle + Next
+            // Simulation controls: Start/Stop toggle + Back + Next
             p.spawn(button_group_node()).with_children(|p| {
                 spawn_action_button(p, &game_assets, "Start", GameButtonAction::ToggleSimulation)
                     .observe(handle_toggle_simulation)
                     .observe(handle_over)
                     .observe(handle_screen_button_out);
+                spawn_action_button(p, &game_assets, "Back", GameButtonAction::StepBack)
+                    .observe(handle_step_back)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
                 spawn_action_button(p, &game_assets, "Next", GameButtonAction::Next)
                     .observe(handle_next)
                     .observe(handle_over)
                     .observe(handle_screen_button_out);
             });
 
-            // Reset / Clear
+            // Reset / Clear / Paint state
             p.spawn(button_group_node()).with_children(|p| {
                 spawn_action_button(p, &game_assets, "Reset", GameButtonAction::Reset)
                     .observe(handle_reset)
@@ -62,14 +86,39 @@ pub fn spawn_screen(mut commands: Commands, world: Res<World>, game_assets: Res<
                     .observe(handle_clear)
                     .observe(handle_over)
                     .observe(handle_screen_button_out);
+                spawn_action_button(p, &game_assets, "Paint 1", GameButtonAction::PaintState)
+                    .observe(handle_paint_state)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
             });
 
-            // Grid toggle
+            // Grid toggle / Engine toggle / Auto-pause toggle / Heatmap toggle
             p.spawn(button_group_node()).with_children(|p| {
                 spawn_action_button(p, &game_assets, "Grid", GameButtonAction::GridToggle)
                     .observe(handle_grid_toggle)
                     .observe(handle_over)
                     .observe(handle_screen_button_out);
+                spawn_action_button(p, &game_assets, "Engine", GameButtonAction::EngineToggle)
+                    .observe(handle_engine_toggle)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
+                spawn_action_button(p, &game_assets, "Auto", GameButtonAction::AutoPauseToggle)
+                    .observe(handle_auto_pause_toggle)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
+                spawn_action_button(p, &game_assets, "Heat", GameButtonAction::HeatmapToggle)
+                    .observe(handle_heatmap_toggle)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
+            });
+
+            // Fast-forward: 世代入力欄 + Jump
+            p.spawn(button_group_node()).with_children(|p| {
+                spawn_fast_forward_input(p, &game_assets);
+                spawn_action_button(p, &game_assets, "Jump", GameButtonAction::FastForward)
+                    .observe(handle_fast_forward)
+                    .observe(handle_over)
+                    .observe(handle_screen_button_out);
             });
 
             // Speed slider
@@ -81,4 +130,7 @@ pub fn spawn_screen(mut commands: Commands, world: Res<World>, game_assets: Res<
 
     // NOTE: Cell highlight (チャンクスプライトはmanage_chunksが管理)
     spawn_cell_highlight(&mut commands);
+    spawn_selection_overlay(&mut commands);
+    spawn_paste_ghost(&mut commands);
+    spawn_topology_border(&mut commands);
 }
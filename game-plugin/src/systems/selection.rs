@@ -0,0 +1,225 @@
+//! 範囲選択・クリップボード操作と、そのオーバーレイ表示
+//!
+//! Shift+左ドラッグで矩形を選択し、コピー・切り取り・貼り付け・削除を行う。
+//! 貼り付け・スタンプ中は配置するセルをゴーストとしてホバー中のセルに追従表示し、
+//! R/F/V/Tキーで回転・反転してから左クリックで確定する。
+//! スタンプ（メニューで選んだパターン）はEscapeで取り消すまでクリックごとに配置する。
+//! 編集はいずれも1操作として編集履歴に記録する。
+
+use bevy::prelude::*;
+use common::consts::{CELL_WORLD_SIZE, PASTE_GHOST_COLOR};
+
+use crate::components::screen::{PasteGhost, SelectionOverlay};
+use crate::events::{ClipboardAction, ClipboardEvent};
+use crate::layer::Layer;
+use crate::resources::clipboard::{Clipboard, GridRect, Selection};
+use crate::resources::history::EditHistory;
+use crate::resources::interaction::{DragState, HoveredCell};
+use crate::resources::world::World;
+use crate::systems::coordinate::{hex_row_shift, world_to_screen_pos};
+
+/// 範囲選択・貼り付け・スタンプの操作中でなく、クリックでセルをトグルしてよいかを返すラン条件
+pub fn cell_toggle_enabled(selection: Res<Selection>, clipboard: Res<Clipboard>) -> bool {
+    !selection.is_dragging() && !clipboard.is_placing()
+}
+
+/// Shift+左ドラッグで範囲選択を行うシステム
+///
+/// ドラッグ開始時のセルとホバー中のセルを対角とする矩形を選択する。
+pub fn selection_drag(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    hovered: Res<HoveredCell>,
+    mut selection: ResMut<Selection>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if mouse.just_pressed(MouseButton::Left) && shift {
+        if let Some(cell) = hovered.0 {
+            selection.begin(cell);
+        }
+        return;
+    }
+    if !selection.is_dragging() {
+        return;
+    }
+    if let Some(cell) = hovered.0 {
+        selection.update(cell);
+    }
+    if !mouse.pressed(MouseButton::Left) {
+        selection.finish();
+    }
+}
+
+/// 貼り付け・スタンプ中の左クリックでセルを配置するシステム
+///
+/// ホバー中のセルを左上として、衰退中や多状態オートマトンのセルも状態ごと配置する。貼り付けは1回で終了し配置した範囲を選択状態にするが、
+/// スタンプは取り消すまで続ける。ドラッグ（カメラパン）後のリリースでは配置しない。
+pub fn paste_click(
+    mouse: Res<ButtonInput<MouseButton>>,
+    drag_state: Res<DragState>,
+    hovered: Res<HoveredCell>,
+    mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
+    mut clipboard: ResMut<Clipboard>,
+    mut selection: ResMut<Selection>,
+) {
+    if !clipboard.is_placing() || !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    if drag_state.is_dragging {
+        return;
+    }
+    let Some(origin) = hovered.0 else {
+        return;
+    };
+    let diff = world.place_states(&clipboard.states_at(origin));
+    history.record(diff, &world);
+    if !clipboard.is_stamping() {
+        selection.select(clipboard.rect_at(origin));
+        clipboard.stop_placing();
+    }
+}
+
+/// `ClipboardEvent` を受け取り選択範囲・クリップボードを操作するシステム
+pub fn handle_clipboard(
+    mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
+    mut selection: ResMut<Selection>,
+    mut clipboard: ResMut<Clipboard>,
+    mut clipboard_event_reader: MessageReader<ClipboardEvent>,
+) {
+    for event in clipboard_event_reader.read() {
+        let rect = selection.rect();
+        match (event.0, rect) {
+            (ClipboardAction::Copy, Some(rect)) => clipboard.copy_from(&world, rect),
+            (ClipboardAction::Cut, Some(rect)) => {
+                clipboard.copy_from(&world, rect);
+                let diff = world.erase_rect(rect);
+                history.record(diff, &world);
+            }
+            (ClipboardAction::Delete, Some(rect)) => {
+                let diff = world.erase_rect(rect);
+                history.record(diff, &world);
+            }
+            (ClipboardAction::Paste, _) => clipboard.start_paste(),
+            (ClipboardAction::Transform(transform), _) => {
+                if clipboard.is_placing() {
+                    clipboard.transform(transform);
+                }
+            }
+            (ClipboardAction::Cancel, _) => {
+                if clipboard.is_placing() {
+                    clipboard.stop_placing();
+                } else {
+                    selection.clear();
+                }
+            }
+            (ClipboardAction::Copy | ClipboardAction::Cut | ClipboardAction::Delete, None) => {}
+        }
+    }
+}
+
+/// 矩形の中心のワールド座標とワールド空間でのサイズを返す純粋関数
+pub(crate) fn rect_world_bounds(rect: GridRect) -> (Vec2, Vec2) {
+    let center = (world_to_screen_pos(rect.min.0, rect.min.1, false)
+        + world_to_screen_pos(rect.max.0, rect.max.1, false))
+        / 2.0;
+    let size = Vec2::new(rect.width() as f32, rect.height() as f32) * CELL_WORLD_SIZE;
+    (center, size)
+}
+
+/// 選択範囲のオーバーレイの位置・サイズ・表示状態を更新するシステム
+///
+/// 貼り付け・スタンプ中は選択範囲を表示しない。
+pub fn update_selection_overlay(
+    selection: Res<Selection>,
+    clipboard: Res<Clipboard>,
+    mut query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<SelectionOverlay>>,
+) {
+    let Ok((mut transform, mut sprite, mut visibility)) = query.single_mut() else {
+        return;
+    };
+    match selection.rect().filter(|_| !clipboard.is_placing()) {
+        Some(rect) => {
+            let (center, size) = rect_world_bounds(rect);
+            transform.translation = center.extend(2.0);
+            sprite.custom_size = Some(size);
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+/// 貼り付け・スタンプのプレビューをホバー中のセルに追従させるシステム
+///
+/// 配置するセルが変わったときはセルのスプライトを作り直す。
+/// 六角形近傍のルールでは各セルの行の偶奇でずらし幅が変わるため、ホバー中の行の偶奇が変わったときも作り直す。
+pub fn update_paste_ghost(
+    mut commands: Commands,
+    clipboard: Res<Clipboard>,
+    hovered: Res<HoveredCell>,
+    world: Res<World>,
+    mut query: Query<(Entity, &mut Transform, &mut Visibility), With<PasteGhost>>,
+    mut ghost_row_parity: Local<Option<i32>>,
+) {
+    let Ok((entity, mut transform, mut visibility)) = query.single_mut() else {
+        return;
+    };
+    let hexagonal = world.rule().neighborhood().is_hexagonal();
+    let row_parity = hovered
+        .0
+        .filter(|_| hexagonal)
+        .map(|(_, gy)| gy.rem_euclid(2));
+    if clipboard.is_changed() || row_parity != *ghost_row_parity {
+        *ghost_row_parity = row_parity;
+        let origin_y = row_parity.unwrap_or_default();
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|parent| {
+                let states = clipboard.states().iter().map(|&(cell, _)| cell);
+                for (x, y) in clipboard.cells().iter().copied().chain(states) {
+                    parent.spawn((
+                        Sprite {
+                            color: PASTE_GHOST_COLOR,
+                            custom_size: Some(Vec2::splat(CELL_WORLD_SIZE)),
+                            ..default()
+                        },
+                        Transform::from_xyz(
+                            x as f32 * CELL_WORLD_SIZE + hex_row_shift(origin_y + y, hexagonal),
+                            -(y as f32) * CELL_WORLD_SIZE,
+                            0.0,
+                        ),
+                        Layer::World.as_render_layer(),
+                    ));
+                }
+            });
+    }
+    match hovered.0.filter(|_| clipboard.is_placing()) {
+        Some((gx, gy)) => {
+            transform.translation = world_to_screen_pos(gx, gy, false).extend(1.5);
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_cell_rect_matches_cell_center() {
+        let (center, size) = rect_world_bounds(GridRect::from_corners((3, -2), (3, -2)));
+        assert_eq!(center, world_to_screen_pos(3, -2, false));
+        assert_eq!(size, Vec2::splat(CELL_WORLD_SIZE));
+    }
+
+    #[test]
+    fn rect_bounds_cover_all_cells() {
+        // セル(0,0)〜(3,1): ワールド座標では x: 0〜4, y: 0〜-2
+        let (center, size) = rect_world_bounds(GridRect::from_corners((0, 0), (3, 1)));
+        assert_eq!(center, Vec2::new(2.0, -1.0));
+        assert_eq!(size, Vec2::new(4.0, 2.0));
+    }
+}
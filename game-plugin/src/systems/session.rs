@@ -0,0 +1,171 @@
+//! ワールドのセッションの保存・読み込み
+//!
+//! メニュー画面から送られる `SessionRequest` を受け取り、盤面・ルール・トポロジー・カメラ・速度を
+//! `SessionStorage` に保存する、または保存したセッションでワールドを置き換える。
+//! カメラと速度はゲーム画面を離れるときに `CameraState` / `GameAssets` へ退避しておく。
+
+use bevy::ecs::system::NonSendMarker;
+use bevy::prelude::*;
+use common::{
+    resources::{GameAssets, SelectedRule, SelectedTopology},
+    session::{
+        CameraSnapshot, SESSION_FORMAT_VERSION, Session, SessionError, SessionRequest,
+        SessionStatus, SessionStorage,
+    },
+};
+use life_core::generations::DecayingCells;
+
+use crate::components::camera::WorldCamera;
+use crate::resources::history::EditHistory;
+use crate::resources::interaction::CameraState;
+use crate::resources::timer::SimulationTimer;
+use crate::resources::world::World;
+
+/// 現在のワールドからセッションを組み立てる純粋関数
+pub(crate) fn build_session(world: &World, camera: &CameraState, tick_interval: f32) -> Session {
+    let mut initial_cells = world.initial_live_cells();
+    initial_cells.sort_unstable_by_key(|&(x, y)| (y, x));
+    let sorted_decaying = |cells: &DecayingCells| {
+        let mut cells: Vec<_> = cells.iter().collect();
+        cells.sort_unstable_by_key(|&((x, y), _)| (y, x));
+        cells
+    };
+    Session {
+        version: SESSION_FORMAT_VERSION,
+        rule: world.rule(),
+        topology: world.topology(),
+        generation: world.generation_count,
+        tick_interval,
+        camera: CameraSnapshot {
+            x: camera.translation.x,
+            y: camera.translation.y,
+            scale: camera.scale,
+        },
+        cells: world.live_cells(),
+        initial_cells,
+        decaying: sorted_decaying(world.decaying_cells()),
+        initial_decaying: sorted_decaying(world.initial_decaying_cells()),
+    }
+}
+
+/// セッションでワールドを置き換え、復元後のカメラ状態を返す
+pub(crate) fn restore_session(world: &mut World, session: &Session) -> CameraState {
+    world.set_rule(session.rule);
+    world.set_topology(session.topology);
+    world.restore(
+        &session.cells,
+        &session.initial_cells,
+        &session.decaying,
+        &session.initial_decaying,
+        session.generation,
+    );
+    CameraState {
+        translation: Vec2::new(session.camera.x, session.camera.y),
+        scale: session.camera.scale,
+    }
+}
+
+/// ゲーム画面を離れるときにカメラの位置・拡大率とシミュレーション速度を退避するシステム
+pub fn store_view_state(
+    camera_query: Query<(&Transform, &Projection), With<WorldCamera>>,
+    timer: Option<Res<SimulationTimer>>,
+    mut camera_state: ResMut<CameraState>,
+    mut game_assets: ResMut<GameAssets>,
+) {
+    if let Ok((transform, Projection::Orthographic(ortho))) = camera_query.single() {
+        *camera_state = CameraState {
+            translation: transform.translation.truncate(),
+            scale: ortho.scale,
+        };
+    }
+    if let Some(timer) = timer {
+        game_assets.tick_interval = timer.0.duration().as_secs_f32();
+    }
+}
+
+/// `SessionRequest` を受け取りセッションを保存・読み込みするシステム
+///
+/// 読み込んだルール・トポロジーは `SelectedRule` / `SelectedTopology` にも反映し、
+/// ゲーム画面に戻ったときに上書きされないようにする。
+/// 結果は `SessionStatus` に書き込み、メニュー画面に表示する。
+#[allow(clippy::too_many_arguments)]
+pub fn handle_session_requests(
+    mut requests: MessageReader<SessionRequest>,
+    mut world: ResMut<World>,
+    mut history: ResMut<EditHistory>,
+    mut camera_state: ResMut<CameraState>,
+    mut game_assets: ResMut<GameAssets>,
+    mut selected_rule: ResMut<SelectedRule>,
+    mut selected_topology: ResMut<SelectedTopology>,
+    storage: Res<SessionStorage>,
+    mut status: ResMut<SessionStatus>,
+    // NOTE: 保存先のファイルダイアログはメインスレッドで開く必要がある
+    _main_thread: NonSendMarker,
+) {
+    for request in requests.read() {
+        let result = match request {
+            SessionRequest::Save => build_session(&world, &camera_state, game_assets.tick_interval)
+                .to_ron()
+                .and_then(|text| storage.0.save(&text))
+                .map(|location| format!("Saved to {location}")),
+            SessionRequest::Load => storage
+                .0
+                .load()
+                .and_then(|text| Session::from_ron(&text))
+                .map(|session| {
+                    *camera_state = restore_session(&mut world, &session);
+                    game_assets.tick_interval = session.tick_interval;
+                    selected_rule.0 = session.rule;
+                    selected_topology.select(session.topology);
+                    history.clear();
+                    format!("Loaded generation {}", session.generation)
+                }),
+        };
+        status.0 = Some(match result {
+            Ok(message) => message,
+            Err(SessionError::Cancelled) => "Cancelled".to_string(),
+            Err(e) => format!("Failed: {e}"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::rules::Rule;
+    use common::topology::{Surface, Topology};
+
+    #[test]
+    fn session_roundtrip_restores_world() {
+        let mut world = World::default();
+        world.set_rule(Rule::parse("B36/S23").unwrap());
+        world.set_topology(Topology::finite(Surface::Torus, 64, 64));
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        world.advance_generations(5);
+        world.set_rule(Rule::parse("B2/S/C4").unwrap());
+        world.set_cell_state(8, 8, 3);
+        world.advance_generations(5);
+        let camera = CameraState {
+            translation: Vec2::new(10.0, -4.0),
+            scale: 0.3,
+        };
+
+        let text = build_session(&world, &camera, 0.5).to_ron().unwrap();
+        let session = Session::from_ron(&text).unwrap();
+        assert_eq!(session.tick_interval, 0.5);
+
+        let mut restored = World::default();
+        restored.place_pattern(&[(500, 500)]);
+        assert_eq!(restore_session(&mut restored, &session), camera);
+        assert_eq!(restored.live_cells(), world.live_cells());
+        assert_eq!(restored.decaying_cells(), world.decaying_cells());
+        assert_eq!(restored.generation_count, 5);
+        assert_eq!(restored.rule(), world.rule());
+        assert_eq!(restored.topology(), world.topology());
+
+        restored.reset();
+        world.reset();
+        assert_eq!(restored.live_cells(), world.live_cells());
+        assert_eq!(restored.cell_state(8, 8), 3);
+    }
+}
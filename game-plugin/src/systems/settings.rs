@@ -0,0 +1,34 @@
+//! ゲーム画面の表示設定をユーザー設定に反映する
+
+use bevy::prelude::*;
+use common::settings::Settings;
+
+use crate::components::camera::WorldCamera;
+use crate::resources::interaction::{AutoPause, GridVisible};
+use crate::resources::timer::SimulationTimer;
+use crate::resources::world::World;
+
+/// シミュレーション速度・ズーム・グリッド表示・自動一時停止・年齢による色分け・
+/// ヒートマップの表示の変更を `Settings` に反映するシステム
+///
+/// 値が変わったときだけ書き込む。ズーム中は毎フレーム変わるが、
+/// 設定ファイルへの保存は `SettingsPlugin` が変更の止まるまで遅らせる。
+pub fn sync_view_settings(
+    timer: Res<SimulationTimer>,
+    grid_visible: Res<GridVisible>,
+    auto_pause: Res<AutoPause>,
+    world: Res<World>,
+    camera_query: Query<&Projection, With<WorldCamera>>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut next = *settings;
+    next.tick_interval = timer.0.duration().as_secs_f32();
+    next.grid_visible = grid_visible.0;
+    next.auto_pause = auto_pause.0;
+    next.age_coloring = world.is_age_tracking();
+    next.heatmap = world.heatmap().is_some();
+    if let Ok(Projection::Orthographic(ortho)) = camera_query.single() {
+        next.camera_scale = ortho.scale;
+    }
+    settings.set_if_neq(next);
+}
@@ -0,0 +1,348 @@
+//! 統計パネルの構築・更新
+//!
+//! ワールドビューポートの右上に、生存セル数・直前の世代の誕生/死亡数・バウンディングボックス・
+//! 世代進行速度・人口推移グラフ・物体のセンサスを重ねて表示する。Iキーで表示を切り替える。
+//! グラフは記録ごとに前の記録との間を縦に結ぶ細いノードを並べた折れ線で描く。
+
+use bevy::prelude::*;
+use common::{
+    consts::{
+        ACCENT_GREEN, BG_SURFACE, BORDER_RADIUS, BORDER_SUBTLE, CENSUS_DISPLAY_LIMIT,
+        CENSUS_REFRESH_INTERVAL, FONT_SIZE_SMALL, SPACING_SM, SPACING_XS, STATS_GRAPH_HEIGHT,
+        STATS_PANEL_WIDTH, TEXT_MUTED, TEXT_PRIMARY,
+    },
+    resources::GameAssets,
+};
+use life_core::analysis::Summary;
+use life_core::analysis::census::Census;
+
+use crate::components::camera::WorldCamera;
+use crate::components::screen::{
+    CensusText, OnGameScreen, PopulationGraphLine, StatsPanel, StatsText,
+};
+use crate::resources::stats::{GenerationRate, PopulationHistory, StatsVisible};
+use crate::resources::world::World;
+
+/// グラフの線の太さ（ピクセル）
+const GRAPH_LINE_WIDTH: f32 = 2.0;
+
+/// 統計パネルを生成するシステム（表示状態は `update_stats_panel` が切り替える）
+///
+/// ボトムパネルではなくワールドカメラに描画するため、ワールドカメラの生成後に実行する。
+pub fn spawn_stats_panel(
+    mut commands: Commands,
+    camera_query: Query<Entity, With<WorldCamera>>,
+    game_assets: Res<GameAssets>,
+    history: Res<PopulationHistory>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let segment_width = STATS_PANEL_WIDTH / history.capacity() as f32;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(SPACING_SM),
+                right: Val::Px(SPACING_SM),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(SPACING_XS),
+                padding: UiRect::all(Val::Px(SPACING_SM)),
+                border: UiRect::all(Val::Px(1.0)),
+                border_radius: BorderRadius::all(Val::Px(BORDER_RADIUS)),
+                ..default()
+            },
+            BorderColor::all(BORDER_SUBTLE),
+            BackgroundColor(BG_SURFACE.with_alpha(0.85)),
+            Visibility::Hidden,
+            UiTargetCamera(camera),
+            Pickable::IGNORE,
+            StatsPanel,
+            OnGameScreen,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(String::new()),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(TEXT_PRIMARY),
+                Pickable::IGNORE,
+                StatsText,
+            ));
+
+            // 人口推移グラフ
+            p.spawn((
+                Node {
+                    width: Val::Px(STATS_PANEL_WIDTH),
+                    height: Val::Px(STATS_GRAPH_HEIGHT),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                BorderColor::all(BORDER_SUBTLE),
+                Pickable::IGNORE,
+            ))
+            .with_children(|graph| {
+                for index in 0..history.capacity() {
+                    graph.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(index as f32 * segment_width),
+                            width: Val::Px(segment_width.max(1.0)),
+                            display: Display::None,
+                            ..default()
+                        },
+                        BackgroundColor(ACCENT_GREEN),
+                        Pickable::IGNORE,
+                        PopulationGraphLine(index),
+                    ));
+                }
+            });
+
+            p.spawn((
+                Text::new(String::new()),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(TEXT_MUTED),
+                Pickable::IGNORE,
+                CensusText,
+            ));
+        });
+}
+
+/// 毎フレームの世代と、世代が変わったときの生存セル数を記録するシステム
+///
+/// パネルの表示状態にかかわらず記録し、表示したときに直近の推移が見えるようにする。
+pub fn record_stats(
+    time: Res<Time>,
+    world: Res<World>,
+    mut history: ResMut<PopulationHistory>,
+    mut rate: ResMut<GenerationRate>,
+) {
+    rate.record(time.elapsed_secs_f64(), world.generation_count);
+    if world.is_changed() {
+        history.record(world.generation_count, world.alive_cells().len());
+    }
+}
+
+/// 統計の表示テキストを組み立てる純粋関数
+pub(crate) fn format_stats(summary: &Summary, births: usize, deaths: usize, rate: f64) -> String {
+    let bounding_box = match summary.bounding_box {
+        Some((min_x, min_y, max_x, max_y)) => format!(
+            "{} x {}",
+            max_x.abs_diff(min_x) + 1,
+            max_y.abs_diff(min_y) + 1
+        ),
+        None => "-".to_string(),
+    };
+    format!(
+        "Population: {}\nBirths: +{births}  Deaths: -{deaths}\nBounding box: {bounding_box}\nSpeed: {rate:.1} gen/s",
+        summary.population
+    )
+}
+
+/// センサスの表示テキストを組み立てる純粋関数
+///
+/// 数の多い順に `CENSUS_DISPLAY_LIMIT` 種類まで表示し、残りは種類数だけ示す。
+pub(crate) fn format_census(census: &Census) -> String {
+    let mut text = format!("Census: {} objects", census.total());
+    for entry in census.entries.iter().take(CENSUS_DISPLAY_LIMIT) {
+        text.push_str(&format!("\n{:>5}  {entry}", entry.count));
+    }
+    if let Some(rest) = census.entries.len().checked_sub(CENSUS_DISPLAY_LIMIT)
+        && rest > 0
+    {
+        text.push_str(&format!("\n  +{rest} more types"));
+    }
+    text
+}
+
+/// グラフの `index` 番目の線分の `(top, height)` を求める純粋関数
+///
+/// 直前の記録の高さから現在の記録の高さまでを縦に結ぶ。`max` が0なら底辺に沿わせる。
+pub(crate) fn graph_segment(previous: usize, current: usize, max: usize) -> (f32, f32) {
+    let inner = STATS_GRAPH_HEIGHT - GRAPH_LINE_WIDTH;
+    let y = |population: usize| {
+        if max == 0 {
+            inner
+        } else {
+            inner * (1.0 - population as f32 / max as f32)
+        }
+    };
+    let (a, b) = (y(previous), y(current));
+    (a.min(b), (a - b).abs() + GRAPH_LINE_WIDTH)
+}
+
+/// 統計パネルの表示状態とテキストを更新するシステム
+///
+/// バウンディングボックスの集計は全セルを走査するため、ワールドが変わったときだけやり直す。
+pub fn update_stats_panel(
+    visible: Res<StatsVisible>,
+    world: Res<World>,
+    rate: Res<GenerationRate>,
+    mut summary: Local<Option<(u64, Summary)>>,
+    mut panel_query: Query<&mut Visibility, With<StatsPanel>>,
+    mut text_query: Query<&mut Text, With<StatsText>>,
+) {
+    if let Ok(mut visibility) = panel_query.single_mut() {
+        visibility.set_if_neq(if visible.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !visible.0 {
+        return;
+    }
+
+    let current = match *summary {
+        Some((revision, current)) if revision == world.revision() => current,
+        _ => {
+            let current = Summary::of(&world);
+            *summary = Some((world.revision(), current));
+            current
+        }
+    };
+    if let Ok(mut text) = text_query.single_mut() {
+        let step = world.last_step();
+        text.set_if_neq(Text(format_stats(
+            &current,
+            step.births,
+            step.deaths,
+            rate.per_second(),
+        )));
+    }
+}
+
+/// センサスの集計状態
+#[derive(Default)]
+pub struct CensusState {
+    /// 最後に集計したワールドのリビジョン
+    revision: Option<u64>,
+    /// 最後に集計してからの経過時間（秒）
+    elapsed: f32,
+}
+
+/// 物体のセンサスの表示を更新するシステム
+///
+/// 島ごとに世代を進めて分類するため、パネルを表示している間、ワールドが変わっていれば
+/// `CENSUS_REFRESH_INTERVAL` 秒に1回だけ集計し直す。
+pub fn update_census_text(
+    visible: Res<StatsVisible>,
+    time: Res<Time>,
+    world: Res<World>,
+    mut state: Local<CensusState>,
+    mut text_query: Query<&mut Text, With<CensusText>>,
+) {
+    state.elapsed += time.delta_secs();
+    if !visible.0 || state.revision == Some(world.revision()) {
+        return;
+    }
+    if state.revision.is_some() && state.elapsed < CENSUS_REFRESH_INTERVAL {
+        return;
+    }
+    state.revision = Some(world.revision());
+    state.elapsed = 0.0;
+    if let Ok(mut text) = text_query.single_mut() {
+        text.0 = format_census(&world.census());
+    }
+}
+
+/// 人口推移グラフを更新するシステム
+///
+/// パネルを表示している間、記録が変わったときだけ線分を並べ直す。
+pub fn update_population_graph(
+    visible: Res<StatsVisible>,
+    history: Res<PopulationHistory>,
+    mut line_query: Query<(&PopulationGraphLine, &mut Node)>,
+) {
+    if !visible.0 || (!history.is_changed() && !visible.is_changed()) {
+        return;
+    }
+    let populations: Vec<usize> = history.populations().collect();
+    let max = history.max_population();
+    // NOTE: 最新の記録が右端に来るよう、記録数が上限に満たない分だけ左を空ける
+    let offset = history.capacity() - populations.len();
+    for (line, mut node) in &mut line_query {
+        let Some(sample) = line.0.checked_sub(offset) else {
+            node.display = Display::None;
+            continue;
+        };
+        let current = populations[sample];
+        let previous = sample.checked_sub(1).map_or(current, |i| populations[i]);
+        let (top, height) = graph_segment(previous, current, max);
+        node.display = Display::Flex;
+        node.top = Val::Px(top);
+        node.height = Val::Px(height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_text_lists_all_values() {
+        let summary = Summary {
+            generation: 12,
+            population: 5,
+            bounding_box: Some((-1, 0, 1, 2)),
+        };
+        assert_eq!(
+            format_stats(&summary, 2, 3, 9.96),
+            "Population: 5\nBirths: +2  Deaths: -3\nBounding box: 3 x 3\nSpeed: 10.0 gen/s"
+        );
+        let empty = Summary {
+            generation: 0,
+            population: 0,
+            bounding_box: None,
+        };
+        assert!(format_stats(&empty, 0, 0, 0.0).contains("Bounding box: -"));
+    }
+
+    #[test]
+    fn census_text_lists_top_entries() {
+        use life_core::analysis::census::CensusEntry;
+
+        let entry = |apgcode: &str, name, count| CensusEntry {
+            apgcode: apgcode.to_string(),
+            name,
+            count,
+        };
+        let mut census = Census {
+            entries: vec![
+                entry("xs4_33", Some("block"), 12),
+                entry("xp15_4r4z4r4", None, 1),
+            ],
+        };
+        assert_eq!(
+            format_census(&census),
+            "Census: 13 objects\n   12  block\n    1  xp15_4r4z4r4"
+        );
+
+        census.entries = (0..CENSUS_DISPLAY_LIMIT + 2)
+            .map(|i| entry(&format!("xs{i}"), None, 1))
+            .collect();
+        let text = format_census(&census);
+        assert_eq!(text.lines().count(), CENSUS_DISPLAY_LIMIT + 2);
+        assert!(text.ends_with("+2 more types"));
+    }
+
+    #[test]
+    fn graph_segment_connects_previous_and_current() {
+        let inner = STATS_GRAPH_HEIGHT - GRAPH_LINE_WIDTH;
+        // 最大値は上端、0は下端
+        assert_eq!(graph_segment(10, 10, 10), (0.0, GRAPH_LINE_WIDTH));
+        assert_eq!(graph_segment(0, 0, 10), (inner, GRAPH_LINE_WIDTH));
+        // 上昇・下降のどちらも同じ線分になる
+        assert_eq!(graph_segment(0, 10, 10), (0.0, inner + GRAPH_LINE_WIDTH));
+        assert_eq!(graph_segment(10, 0, 10), graph_segment(0, 10, 10));
+        assert_eq!(graph_segment(0, 0, 0), (inner, GRAPH_LINE_WIDTH));
+    }
+}
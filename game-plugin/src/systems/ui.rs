@@ -4,17 +4,24 @@ use bevy::prelude::*;
 use common::{
     consts::{
         ACCENT_GREEN, ACTION_BUTTON_HEIGHT, ACTION_BUTTON_MIN_WIDTH, BG_BUTTON, BORDER_RADIUS,
-        BORDER_SUBTLE, FONT_SIZE_LARGE, FONT_SIZE_MEDIUM, FONT_SIZE_SMALL, GEN_COUNTER_MIN_WIDTH,
-        SLIDER_THUMB_SIZE, SLIDER_TRACK_HEIGHT, SLIDER_TRACK_WIDTH, TEXT_MUTED, TEXT_PRIMARY,
+        BORDER_SUBTLE, BUTTON_BORDER_WIDTH, FAST_FORWARD_INPUT_WIDTH, FONT_SIZE_LARGE,
+        FONT_SIZE_MEDIUM, FONT_SIZE_SMALL, GEN_COUNTER_MIN_WIDTH, SLIDER_THUMB_SIZE,
+        SLIDER_TRACK_HEIGHT, SLIDER_TRACK_WIDTH, TEXT_MUTED, TEXT_PRIMARY,
     },
     resources::GameAssets,
+    rules::Rule,
 };
+use life_core::engine::EngineKind;
 
 use crate::components::{
     action::GameButtonAction,
-    screen::GenerationText,
+    screen::{
+        CycleText, EngineText, FastForwardInputBox, FastForwardText, GenerationText, RuleText,
+    },
     slider::{SliderKind, SliderThumb, SliderTrack},
 };
+use crate::systems::cell_operations::CYCLE_UNKNOWN_LABEL;
+use crate::systems::fast_forward::handle_fast_forward_input_click;
 use crate::systems::slider::{handle_slider_click, handle_slider_drag};
 
 /// 世代カウンター表示テキストを生成する
@@ -51,6 +58,109 @@ pub fn spawn_generation_text(
         });
 }
 
+/// 検出した周期的な振る舞いの表示テキストを生成する
+///
+/// 表示内容は `update_cycle_text` が検出状態に合わせて更新する。
+pub fn spawn_cycle_text(parent: &mut ChildSpawnerCommands, game_assets: &GameAssets) {
+    parent.spawn((
+        Text::new(CYCLE_UNKNOWN_LABEL),
+        TextFont {
+            font: game_assets.font.clone(),
+            font_size: FONT_SIZE_SMALL,
+            ..default()
+        },
+        TextColor(TEXT_PRIMARY),
+        CycleText,
+    ));
+}
+
+/// 適用中ルール表示テキストを生成する
+pub fn spawn_rule_text(parent: &mut ChildSpawnerCommands, game_assets: &GameAssets, rule: Rule) {
+    parent
+        .spawn((
+            Text::new("Rule: ".to_string()),
+            TextFont {
+                font: game_assets.font.clone(),
+                font_size: FONT_SIZE_SMALL,
+                ..default()
+            },
+            TextColor(TEXT_MUTED),
+        ))
+        .with_child((
+            TextSpan::new(rule.to_string()),
+            TextFont {
+                font: game_assets.font.clone(),
+                font_size: FONT_SIZE_SMALL,
+                ..default()
+            },
+            RuleText,
+            TextColor(TEXT_PRIMARY),
+        ));
+}
+
+/// 使用中の世代計算エンジン表示テキストを生成する
+pub fn spawn_engine_text(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    engine: EngineKind,
+) {
+    parent
+        .spawn((
+            Text::new("Engine: ".to_string()),
+            TextFont {
+                font: game_assets.font.clone(),
+                font_size: FONT_SIZE_SMALL,
+                ..default()
+            },
+            TextColor(TEXT_MUTED),
+        ))
+        .with_child((
+            TextSpan::new(engine.label()),
+            TextFont {
+                font: game_assets.font.clone(),
+                font_size: FONT_SIZE_SMALL,
+                ..default()
+            },
+            EngineText,
+            TextColor(TEXT_PRIMARY),
+        ));
+}
+
+/// 早送り先世代の入力欄を生成する
+///
+/// 表示内容は `update_fast_forward_input` が入力状態に合わせて更新する。
+pub fn spawn_fast_forward_input(parent: &mut ChildSpawnerCommands, game_assets: &GameAssets) {
+    parent
+        .spawn((
+            Node {
+                align_items: AlignItems::Center,
+                width: Val::Px(FAST_FORWARD_INPUT_WIDTH),
+                height: Val::Px(ACTION_BUTTON_HEIGHT),
+                padding: UiRect::horizontal(Val::Px(12.)),
+                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
+                border_radius: BorderRadius::all(Val::Px(BORDER_RADIUS)),
+                ..default()
+            },
+            BorderColor::all(BORDER_SUBTLE),
+            BackgroundColor(BG_BUTTON),
+            FastForwardInputBox,
+        ))
+        .observe(handle_fast_forward_input_click)
+        .with_children(|p| {
+            p.spawn((
+                Text::new(String::new()),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: FONT_SIZE_MEDIUM,
+                    ..default()
+                },
+                TextColor(TEXT_PRIMARY),
+                FastForwardText,
+                Pickable::IGNORE,
+            ));
+        });
+}
+
 /// アクションボタン（フルサイズ）を生成する
 pub fn spawn_action_button<'a>(
     parent: &'a mut ChildSpawnerCommands<'_>,
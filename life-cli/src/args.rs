@@ -0,0 +1,242 @@
+//! コマンドライン引数の解析
+
+use std::fmt;
+use std::path::PathBuf;
+
+use life_core::engine::EngineKind;
+use life_core::rules::{Rule, RuleParseError};
+
+/// 安定判定で世代を進める上限のデフォルト値
+pub const DEFAULT_MAX_GENERATIONS: u64 = 100_000;
+
+/// 使い方の説明
+pub const USAGE: &str = "\
+usage: life-cli <PATTERN> [options]
+
+Load a pattern file (RLE, Plaintext, Life 1.05/1.06), run it and print
+generation, population and bounding box.
+
+options:
+  -g, --generations <N>      run N generations (default: 0)
+  -s, --until-stable         run until the pattern dies out, repeats or moves
+                             as a spaceship
+      --max-generations <N>  upper limit for --until-stable (default: 100000)
+  -r, --rule <RULE>          override the rule (e.g. B3/S23)
+  -e, --engine <ENGINE>      bitboard | sparse | hashlife (default: bitboard)
+  -o, --output <FILE>        write the final state as RLE (2-state rules only)
+  -h, --help                 print this help";
+
+/// 世代の進め方
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunMode {
+    /// 指定した世代数だけ進める
+    Generations(u64),
+    /// 全滅するか周期的な振る舞いを検出するまで、上限世代数まで進める
+    UntilStable { max_generations: u64 },
+}
+
+/// 解析済みのコマンドライン引数
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    /// 読み込むパターンファイル
+    pub input: PathBuf,
+    /// 世代の進め方
+    pub mode: RunMode,
+    /// ファイルの指定より優先するルール
+    pub rule: Option<Rule>,
+    /// 世代計算エンジン
+    pub engine: EngineKind,
+    /// 最終状態を書き出すRLEファイル
+    pub output: Option<PathBuf>,
+}
+
+/// 引数の解析結果
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// シミュレーションを実行する
+    Run(Box<Options>),
+    /// 使い方を表示する
+    Help,
+}
+
+/// 引数の解析エラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    /// パターンファイルの指定がない
+    MissingInput,
+    /// 値を取るオプションに値がない
+    MissingValue(String),
+    /// 未知のオプション・余分な引数
+    Unexpected(String),
+    /// 数値の書式誤り
+    InvalidNumber(String),
+    /// ルール文字列の解析失敗
+    InvalidRule(RuleParseError),
+    /// 未知のエンジン名
+    UnknownEngine(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInput => write!(f, "no pattern file given"),
+            Self::MissingValue(option) => write!(f, "option '{option}' requires a value"),
+            Self::Unexpected(arg) => write!(f, "unexpected argument '{arg}'"),
+            Self::InvalidNumber(value) => write!(f, "invalid number '{value}'"),
+            Self::InvalidRule(e) => write!(f, "invalid rule: {e}"),
+            Self::UnknownEngine(name) => {
+                write!(
+                    f,
+                    "unknown engine '{name}' (expected bitboard, sparse or hashlife)"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+/// コマンドライン引数（プログラム名を除く）を解析する
+pub fn parse_args<I, S>(args: I) -> Result<Command, ArgsError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let mut input = None;
+    let mut generations = 0;
+    let mut until_stable = false;
+    let mut max_generations = DEFAULT_MAX_GENERATIONS;
+    let mut rule = None;
+    let mut engine = EngineKind::default();
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| ArgsError::MissingValue(arg.clone()))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-g" | "--generations" => generations = parse_number(&value()?)?,
+            "-s" | "--until-stable" => until_stable = true,
+            "--max-generations" => max_generations = parse_number(&value()?)?,
+            "-r" | "--rule" => {
+                rule = Some(Rule::parse(&value()?).map_err(ArgsError::InvalidRule)?);
+            }
+            "-e" | "--engine" => engine = parse_engine(&value()?)?,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            _ if arg.starts_with('-') => return Err(ArgsError::Unexpected(arg)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(ArgsError::Unexpected(arg)),
+        }
+    }
+
+    let mode = if until_stable {
+        RunMode::UntilStable { max_generations }
+    } else {
+        RunMode::Generations(generations)
+    };
+    Ok(Command::Run(Box::new(Options {
+        input: input.ok_or(ArgsError::MissingInput)?,
+        mode,
+        rule,
+        engine,
+        output,
+    })))
+}
+
+fn parse_number(value: &str) -> Result<u64, ArgsError> {
+    value
+        .parse()
+        .map_err(|_| ArgsError::InvalidNumber(value.to_string()))
+}
+
+fn parse_engine(name: &str) -> Result<EngineKind, ArgsError> {
+    [
+        EngineKind::Bitboard,
+        EngineKind::Sparse,
+        EngineKind::HashLife,
+    ]
+    .into_iter()
+    .find(|kind| kind.label().eq_ignore_ascii_case(name))
+    .ok_or_else(|| ArgsError::UnknownEngine(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_options(args: &[&str]) -> Options {
+        match parse_args(args.iter().copied()) {
+            Ok(Command::Run(options)) => *options,
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn defaults_run_zero_generations() {
+        let options = run_options(&["glider.rle"]);
+        assert_eq!(options.input, PathBuf::from("glider.rle"));
+        assert_eq!(options.mode, RunMode::Generations(0));
+        assert_eq!(options.rule, None);
+        assert_eq!(options.engine, EngineKind::Bitboard);
+        assert_eq!(options.output, None);
+    }
+
+    #[test]
+    fn all_options_are_parsed() {
+        let options = run_options(&[
+            "-g", "500", "soup.rle", "--rule", "B36/S23", "-e", "HashLife", "-o", "out.rle",
+        ]);
+        assert_eq!(options.mode, RunMode::Generations(500));
+        assert_eq!(options.rule, Some(Rule::parse("B36/S23").unwrap()));
+        assert_eq!(options.engine, EngineKind::HashLife);
+        assert_eq!(options.output, Some(PathBuf::from("out.rle")));
+    }
+
+    #[test]
+    fn until_stable_uses_max_generations() {
+        let options = run_options(&["soup.rle", "--until-stable", "--max-generations", "42"]);
+        assert_eq!(
+            options.mode,
+            RunMode::UntilStable {
+                max_generations: 42
+            }
+        );
+    }
+
+    #[test]
+    fn help_wins_over_other_arguments() {
+        assert_eq!(parse_args(["soup.rle", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(parse_args(["-g", "10"]), Err(ArgsError::MissingInput));
+        assert_eq!(
+            parse_args(["a.rle", "-g"]),
+            Err(ArgsError::MissingValue("-g".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.rle", "-g", "ten"]),
+            Err(ArgsError::InvalidNumber("ten".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.rle", "b.rle"]),
+            Err(ArgsError::Unexpected("b.rle".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.rle", "--fast"]),
+            Err(ArgsError::Unexpected("--fast".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.rle", "-e", "gpu"]),
+            Err(ArgsError::UnknownEngine("gpu".to_string()))
+        );
+        assert_eq!(
+            parse_args(["a.rle", "-r", "B0/S"]),
+            Err(ArgsError::InvalidRule(RuleParseError::UnsupportedB0))
+        );
+    }
+}
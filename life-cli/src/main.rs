@@ -0,0 +1,36 @@
+//! ライフゲームのコマンドライン実行ツール
+//!
+//! 画面を持たない環境（CIやサーバー）で、パターンファイルを読み込んで世代を進め、
+//! 世代数・生存セル数・バウンディングボックスを表示する。最終状態はRLEで書き出せる。
+
+mod args;
+mod run;
+
+use std::process::ExitCode;
+
+use args::{Command, USAGE, parse_args};
+
+/// アプリケーションのエントリーポイント
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run::run(&options) {
+        Ok(report) => {
+            print!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
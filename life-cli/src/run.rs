@@ -0,0 +1,269 @@
+//! パターンの読み込み・シミュレーション・結果の出力
+
+use std::fmt;
+use std::path::Path;
+
+use life_core::analysis::{Stability, Summary, run_until_stable};
+use life_core::formats::{FormatError, PatternFile, PatternFormat, rle};
+use life_core::rules::Rule;
+use life_core::world::World;
+
+use crate::args::{Options, RunMode};
+
+/// シミュレーション結果
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// 使用したルール
+    pub rule: Rule,
+    /// 最終世代の概要
+    pub summary: Summary,
+    /// 安定判定の結果（`--until-stable` 指定時のみ）
+    pub stability: Option<Stability>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rule: {}", self.rule)?;
+        writeln!(f, "generation: {}", self.summary.generation)?;
+        writeln!(f, "population: {}", self.summary.population)?;
+        match self.summary.bounding_box {
+            Some((min_x, min_y, max_x, max_y)) => writeln!(
+                f,
+                "bounding box: x={min_x} y={min_y} width={} height={}",
+                max_x.abs_diff(min_x) + 1,
+                max_y.abs_diff(min_y) + 1
+            )?,
+            None => writeln!(f, "bounding box: empty")?,
+        }
+        match self.stability {
+            Some(Stability::Settled(behavior)) => writeln!(f, "stability: {behavior}"),
+            Some(Stability::Unsettled) => writeln!(f, "stability: not settled"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 実行時エラー
+#[derive(Debug)]
+pub enum RunError {
+    /// ファイルの読み書きに失敗した
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// パターンファイルの解析に失敗した
+    Format { path: String, source: FormatError },
+    /// 2状態を超えるルールの結果はRLEに書き出せない（衰退中のセルが失われるため）
+    UnsupportedOutput {
+        path: String,
+        rule: String,
+        states: u8,
+    },
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{path}: {source}"),
+            Self::Format { path, source } => write!(f, "{path}: {source}"),
+            Self::UnsupportedOutput { path, rule, states } => write!(
+                f,
+                "{path}: RLE output supports only 2-state rules, but {rule} has {states} states"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+/// 引数に従ってパターンを読み込み、シミュレーションして結果を返す
+pub fn run(options: &Options) -> Result<Report, RunError> {
+    let pattern = load_pattern(&options.input)?;
+    let rule = options.rule.or(pattern.rule).unwrap_or_default();
+    // NOTE: 書き出せない結果のために計算しないよう、進める前に確かめる
+    if let Some(path) = &options.output
+        && rule.states() > 2
+    {
+        return Err(RunError::UnsupportedOutput {
+            path: path.display().to_string(),
+            rule: rule.to_string(),
+            states: rule.states(),
+        });
+    }
+    let (world, stability) = simulate(&pattern.cells, rule, options);
+
+    if let Some(path) = &options.output {
+        let text = rle::write(&PatternFile {
+            name: pattern.name.clone(),
+            rule: Some(rule),
+            cells: world.live_cells(),
+            ..PatternFile::default()
+        });
+        std::fs::write(path, text).map_err(|source| RunError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+    }
+
+    Ok(Report {
+        rule,
+        summary: Summary::of(&world),
+        stability,
+    })
+}
+
+/// パターンファイルを読み込む（形式は拡張子、なければ内容から判定する）
+fn load_pattern(path: &Path) -> Result<PatternFile, RunError> {
+    let display = || path.display().to_string();
+    let text = std::fs::read_to_string(path).map_err(|source| RunError::Io {
+        path: display(),
+        source,
+    })?;
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(PatternFormat::from_extension)
+        .unwrap_or_else(|| PatternFormat::detect(&text));
+    format.parse(&text).map_err(|source| RunError::Format {
+        path: display(),
+        source,
+    })
+}
+
+/// セルを配置したワールドを引数の指定どおりに進める
+fn simulate(cells: &[(i32, i32)], rule: Rule, options: &Options) -> (World, Option<Stability>) {
+    let mut world = World::new();
+    world.set_engine(options.engine);
+    world.set_rule(rule);
+    world.place_pattern(cells);
+
+    match options.mode {
+        RunMode::Generations(generations) => {
+            world.advance_generations(generations);
+            (world, None)
+        }
+        RunMode::UntilStable { max_generations } => {
+            let stability = run_until_stable(&mut world, max_generations);
+            (world, Some(stability))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use life_core::analysis::Behavior;
+    use life_core::engine::EngineKind;
+    use life_core::patterns::LifePattern;
+
+    fn options(mode: RunMode) -> Options {
+        Options {
+            input: "unused".into(),
+            mode,
+            rule: None,
+            engine: EngineKind::default(),
+            output: None,
+        }
+    }
+
+    #[test]
+    fn fixed_generations_are_run() {
+        let (world, stability) = simulate(
+            LifePattern::Glider.cells(),
+            Rule::conway(),
+            &options(RunMode::Generations(8)),
+        );
+        assert_eq!(world.generation_count, 8);
+        assert_eq!(world.live_cells().len(), 5);
+        assert_eq!(stability, None);
+    }
+
+    #[test]
+    fn until_stable_reports_stability() {
+        let blinker = [(0, 0), (1, 0), (2, 0)];
+        let (world, stability) = simulate(
+            &blinker,
+            Rule::conway(),
+            &options(RunMode::UntilStable {
+                max_generations: 100,
+            }),
+        );
+        assert_eq!(
+            stability,
+            Some(Stability::Settled(Behavior::Oscillator { period: 2 }))
+        );
+        assert_eq!(world.generation_count, 2);
+    }
+
+    #[test]
+    fn run_reads_pattern_and_writes_rle() {
+        let dir = std::env::temp_dir().join(format!("life-cli-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("blinker.cells");
+        let output = dir.join("out.rle");
+        std::fs::write(&input, "!Name: Blinker\nOOO\n").unwrap();
+
+        let report = run(&Options {
+            input: input.clone(),
+            output: Some(output.clone()),
+            ..options(RunMode::Generations(1))
+        })
+        .unwrap();
+        assert_eq!(report.summary.generation, 1);
+        assert_eq!(report.summary.population, 3);
+        assert_eq!(report.summary.bounding_box, Some((1, -1, 1, 1)));
+        assert!(
+            report
+                .to_string()
+                .contains("bounding box: x=1 y=-1 width=1 height=3")
+        );
+
+        let written = rle::parse(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(written.name.as_deref(), Some("Blinker"));
+        let mut cells = written.cells;
+        cells.sort_unstable();
+        assert_eq!(cells, vec![(1, -1), (1, 0), (1, 1)]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn multi_state_rule_output_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("life-cli-states-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("line.cells");
+        let output = dir.join("out.rle");
+        std::fs::write(&input, "OOO\n").unwrap();
+
+        let result = run(&Options {
+            input: input.clone(),
+            output: Some(output.clone()),
+            rule: Some(Rule::parse("B2/S/C3").unwrap()),
+            ..options(RunMode::Generations(1))
+        });
+        let Err(error) = result else {
+            panic!("expected an error, got {result:?}");
+        };
+        assert!(matches!(error, RunError::UnsupportedOutput { .. }));
+        assert!(error.to_string().contains("3 states"));
+        assert!(!output.exists());
+
+        // 出力しなければ多状態のルールでも実行できる
+        let report = run(&Options {
+            input,
+            rule: Some(Rule::parse("B2/S/C3").unwrap()),
+            ..options(RunMode::Generations(1))
+        })
+        .unwrap();
+        assert_eq!(report.summary.generation, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_io_error() {
+        let result = run(&Options {
+            input: "/nonexistent/pattern.rle".into(),
+            ..options(RunMode::Generations(0))
+        });
+        assert!(matches!(result, Err(RunError::Io { .. })));
+    }
+}
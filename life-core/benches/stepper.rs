@@ -0,0 +1,52 @@
+//! 世代計算のベンチマーク
+//!
+//! 疎集合による逐次計算（`SparseEngine::step`）と
+//! チャンク単位のビットボード（`Bitboard::step`）を同じパターンで比較する。
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use life_core::bitboard::Bitboard;
+use life_core::engine::SparseEngine;
+use life_core::patterns::LifePattern;
+use life_core::rules::Rule;
+use rustc_hash::FxHashSet;
+
+/// 1回の計測で進める世代数
+const GENERATIONS: usize = 100;
+
+fn bench_stepper(c: &mut Criterion) {
+    let rule = Rule::conway();
+    let mut group = c.benchmark_group("stepper");
+
+    for pattern in [LifePattern::GosperGliderGun, LifePattern::RPentomino] {
+        let name = pattern.label();
+
+        let cells: FxHashSet<(i32, i32)> = pattern.cells().iter().copied().collect();
+        group.bench_with_input(BenchmarkId::new("sparse", name), &cells, |b, cells| {
+            b.iter(|| {
+                let mut current = cells.clone();
+                for _ in 0..GENERATIONS {
+                    current = SparseEngine::step(&current, &rule);
+                }
+                black_box(current)
+            })
+        });
+
+        let board: Bitboard = pattern.cells().iter().copied().collect();
+        group.bench_with_input(BenchmarkId::new("bitboard", name), &board, |b, board| {
+            b.iter(|| {
+                let mut current = board.clone();
+                for _ in 0..GENERATIONS {
+                    current = current.step(&rule);
+                }
+                black_box(current)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_stepper);
+criterion_main!(benches);
@@ -0,0 +1,167 @@
+//! セルの年齢（生存し続けている世代数）の追跡
+//!
+//! 生存セルごとに誕生してからの世代数を、死亡直後のセルには死亡してからの世代数を保持する。
+//! 死亡してから `AGE_TRAIL_LENGTH` 世代を超えたセルは追跡をやめる。
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::bitboard::{Bitboard, ChunkKey};
+use crate::consts::AGE_TRAIL_LENGTH;
+
+/// 1セルの年齢による状態
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellAge {
+    /// 死亡セル（死亡直後の軌跡も消えたもの）
+    Dead,
+    /// 生存セル（誕生した世代なら0）
+    Alive(u32),
+    /// 死亡直後のセル（直前の世代進行で死亡したなら1）
+    Fading(u32),
+}
+
+/// セルの年齢の表
+#[derive(Clone, Debug, Default)]
+pub struct CellAges {
+    /// 生存セルごとの誕生からの世代数
+    alive: FxHashMap<(i32, i32), u32>,
+    /// 死亡直後のセルごとの死亡からの世代数
+    fading: FxHashMap<(i32, i32), u32>,
+}
+
+impl CellAges {
+    /// 現在の生存セルを全て年齢0として追跡を始める
+    pub fn new(cells: &Bitboard) -> Self {
+        Self {
+            alive: cells.iter().map(|cell| (cell, 0)).collect(),
+            fading: FxHashMap::default(),
+        }
+    }
+
+    /// 指定座標のセルの年齢による状態を返す
+    pub fn get(&self, x: i32, y: i32) -> CellAge {
+        if let Some(&age) = self.alive.get(&(x, y)) {
+            CellAge::Alive(age)
+        } else if let Some(&since) = self.fading.get(&(x, y)) {
+            CellAge::Fading(since)
+        } else {
+            CellAge::Dead
+        }
+    }
+
+    /// 死亡直後の軌跡を持つセルがあるかを返す
+    pub fn has_trail(&self) -> bool {
+        !self.fading.is_empty()
+    }
+
+    /// 死亡直後の軌跡を持つセルを含むチャンクのキーを返す
+    pub fn trail_chunk_keys(&self) -> FxHashSet<ChunkKey> {
+        self.fading
+            .keys()
+            .map(|&(x, y)| Bitboard::chunk_key(x, y))
+            .collect()
+    }
+
+    /// 世代進行の前後の状態から年齢を更新する
+    ///
+    /// 前後とも生存しているセルは `generations` だけ年を取り、新たに生存したセルは0になる。
+    /// 複数世代をまとめて進めた場合、途中で死亡して再び誕生したセルも生存し続けたものとみなす。
+    pub fn advance(&mut self, after: &Bitboard, generations: u64) {
+        let step = u32::try_from(generations).unwrap_or(u32::MAX);
+        let mut fading: FxHashMap<(i32, i32), u32> = self
+            .fading
+            .drain()
+            .map(|(cell, since)| (cell, since.saturating_add(step)))
+            .filter(|&(_, since)| since <= AGE_TRAIL_LENGTH)
+            .collect();
+        let mut alive = FxHashMap::default();
+        for (x, y) in after.iter() {
+            let age = self
+                .alive
+                .remove(&(x, y))
+                .map_or(0, |age| age.saturating_add(step));
+            alive.insert((x, y), age);
+            fading.remove(&(x, y));
+        }
+        // NOTE: 残ったのは今回の世代進行で死亡したセル
+        fading.extend(self.alive.drain().map(|(cell, _)| (cell, 1)));
+        self.alive = alive;
+        self.fading = fading;
+    }
+
+    /// 編集後のセルに合わせる
+    ///
+    /// 生存し続けているセルは年齢を保ち、新たに生存したセルは0にする。
+    /// 編集で消えたセルは軌跡を残さない。
+    pub fn sync(&mut self, cells: &Bitboard) {
+        self.alive.retain(|&(x, y), _| cells.get(x, y));
+        for cell in cells.iter() {
+            self.alive.entry(cell).or_insert(0);
+            self.fading.remove(&cell);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rule;
+
+    #[test]
+    fn still_life_ages_every_generation() {
+        let mut cells: Bitboard = [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().collect();
+        let mut ages = CellAges::new(&cells);
+        for _ in 0..3 {
+            cells = cells.step(&Rule::conway());
+            ages.advance(&cells, 1);
+        }
+        assert_eq!(ages.get(1, 1), CellAge::Alive(3));
+        ages.advance(&cells, 10);
+        assert_eq!(ages.get(0, 0), CellAge::Alive(13));
+        assert!(!ages.has_trail());
+    }
+
+    #[test]
+    fn blinker_leaves_fading_trail() {
+        let mut cells: Bitboard = [(-1, 0), (0, 0), (1, 0)].into_iter().collect();
+        let mut ages = CellAges::new(&cells);
+        cells = cells.step(&Rule::conway());
+        ages.advance(&cells, 1);
+        assert_eq!(ages.get(0, 0), CellAge::Alive(1));
+        assert_eq!(ages.get(0, -1), CellAge::Alive(0));
+        assert_eq!(ages.get(-1, 0), CellAge::Fading(1));
+        assert_eq!(ages.get(5, 5), CellAge::Dead);
+
+        // 再び誕生したセルは年齢0からやり直し、軌跡から外れる
+        cells = cells.step(&Rule::conway());
+        ages.advance(&cells, 1);
+        assert_eq!(ages.get(-1, 0), CellAge::Alive(0));
+        assert_eq!(ages.get(0, -1), CellAge::Fading(1));
+        assert_eq!(ages.trail_chunk_keys().len(), 2);
+    }
+
+    #[test]
+    fn trail_disappears_after_trail_length() {
+        let mut ages = CellAges::new(&[(3, 3)].into_iter().collect());
+        let empty = Bitboard::new();
+        ages.advance(&empty, 1);
+        for _ in 1..AGE_TRAIL_LENGTH {
+            ages.advance(&empty, 1);
+        }
+        assert_eq!(ages.get(3, 3), CellAge::Fading(AGE_TRAIL_LENGTH));
+        ages.advance(&empty, 1);
+        assert_eq!(ages.get(3, 3), CellAge::Dead);
+    }
+
+    #[test]
+    fn sync_keeps_ages_of_surviving_cells() {
+        let mut cells: Bitboard = [(0, 0), (1, 0)].into_iter().collect();
+        let mut ages = CellAges::new(&cells);
+        ages.advance(&cells, 5);
+        cells.set(1, 0, false);
+        cells.set(2, 0, true);
+        ages.sync(&cells);
+        assert_eq!(ages.get(0, 0), CellAge::Alive(5));
+        assert_eq!(ages.get(2, 0), CellAge::Alive(0));
+        assert_eq!(ages.get(1, 0), CellAge::Dead);
+    }
+}
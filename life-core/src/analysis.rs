@@ -0,0 +1,355 @@
+//! ワールドの状態の解析
+//!
+//! 生存セル数・バウンディングボックスの集計と、全滅・固定物体・振動子・移動物体の検出を提供する。
+//! 物体の種類ごとの集計は [`census`] を参照。
+
+pub mod census;
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use crate::bitboard::Bitboard;
+use crate::consts::STABILITY_WINDOW;
+use crate::formats::bounding_box;
+use crate::world::World;
+
+/// ある世代のワールドの概要
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    /// 世代数
+    pub generation: u64,
+    /// 生存セル数
+    pub population: usize,
+    /// バウンディングボックス `(min_x, min_y, max_x, max_y)`（全滅時は `None`）
+    pub bounding_box: Option<(i32, i32, i32, i32)>,
+}
+
+impl Summary {
+    /// 現在のワールドの概要を集計する
+    pub fn of(world: &World) -> Self {
+        let cells = world.live_cells();
+        Self {
+            generation: world.generation_count,
+            population: cells.len(),
+            bounding_box: bounding_box(&cells),
+        }
+    }
+}
+
+/// 周期的な振る舞いの分類
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Behavior {
+    /// 全てのセルが死滅した
+    Extinct,
+    /// 世代を進めても変化しない固定物体
+    StillLife,
+    /// その場で `period` 世代ごとに同じ状態に戻る振動子
+    Oscillator { period: u64 },
+    /// `period` 世代ごとに `displacement` だけ移動して同じ形に戻る移動物体
+    Spaceship {
+        period: u64,
+        displacement: (i32, i32),
+    },
+}
+
+impl Behavior {
+    /// 周期と1周期あたりの移動量から分類する
+    fn from_cycle(period: u64, displacement: (i32, i32)) -> Self {
+        match (period, displacement) {
+            (_, (0, 0)) if period == 1 => Self::StillLife,
+            (_, (0, 0)) => Self::Oscillator { period },
+            _ => Self::Spaceship {
+                period,
+                displacement,
+            },
+        }
+    }
+
+    /// 全滅または固定物体になり、以降変化しないかを返す
+    pub fn is_static(&self) -> bool {
+        matches!(self, Self::Extinct | Self::StillLife)
+    }
+
+    /// 移動物体の速さを `(分子, 分母)` の既約分数で返す（単位は光速 c）
+    ///
+    /// 1周期の移動量は縦横の大きい方で数える（グライダーは `(1, 4)` で c/4）。
+    pub fn speed(&self) -> Option<(u64, u64)> {
+        let Self::Spaceship {
+            period,
+            displacement: (dx, dy),
+        } = *self
+        else {
+            return None;
+        };
+        let distance = u64::from(dx.unsigned_abs().max(dy.unsigned_abs()));
+        let divisor = gcd(distance, period);
+        Some((distance / divisor, period / divisor))
+    }
+}
+
+impl fmt::Display for Behavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Extinct => write!(f, "Extinct"),
+            Self::StillLife => write!(f, "Still life"),
+            Self::Oscillator { period } => write!(f, "P{period} oscillator"),
+            Self::Spaceship { .. } => match self.speed() {
+                Some((1, period)) => write!(f, "c/{period} spaceship"),
+                Some((distance, period)) => write!(f, "{distance}c/{period} spaceship"),
+                None => unreachable!("spaceships always have a speed"),
+            },
+        }
+    }
+}
+
+/// 世代ごとのセル集合を記録し、周期的な振る舞いを検出する
+///
+/// 各世代のセル集合を平行移動を除いて正規化したハッシュで比較するため、
+/// 振動子に加えて移動物体も検出できる。直近 `STABILITY_WINDOW` 世代より長い周期は検出しない。
+/// ハッシュのみを比較するので、異なる状態を同一とみなす可能性はごくわずかに残る。
+#[derive(Debug, Default)]
+pub struct CycleDetector {
+    /// 正規化したハッシュ → 記録した世代と正規化の原点
+    seen: FxHashMap<u64, (u64, (i32, i32))>,
+    /// 記録した順のハッシュ（古いものから捨てる）
+    order: VecDeque<u64>,
+    /// 最後に記録した世代
+    last_generation: Option<u64>,
+    /// 検出済みの振る舞い
+    behavior: Option<Behavior>,
+}
+
+impl CycleDetector {
+    /// 空の検出器を生成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 検出済みの振る舞いを返す
+    pub fn behavior(&self) -> Option<Behavior> {
+        self.behavior
+    }
+
+    /// 全ての記録を破棄する
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+        self.last_generation = None;
+        self.behavior = None;
+    }
+
+    /// 世代 `generation` のセル集合を記録し、この世代で新たに検出した振る舞いを返す
+    ///
+    /// 直前に記録した世代の次の世代でなければ（巻き戻し・早送り・編集の後など）記録をやり直す。
+    /// 周期に入った後の状態は決定的に繰り返すため、検出後はハッシュを計算しない。
+    pub fn observe(&mut self, cells: &Bitboard, generation: u64) -> Option<Behavior> {
+        if self
+            .last_generation
+            .is_some_and(|last| last.checked_add(1) != Some(generation))
+        {
+            self.clear();
+        }
+        self.last_generation = Some(generation);
+        if self.behavior.is_some() {
+            return None;
+        }
+
+        let Some((hash, origin)) = normalized_hash(cells) else {
+            self.behavior = Some(Behavior::Extinct);
+            return self.behavior;
+        };
+        if let Some(&(past_generation, past_origin)) = self.seen.get(&hash) {
+            self.behavior = Some(Behavior::from_cycle(
+                generation - past_generation,
+                (origin.0 - past_origin.0, origin.1 - past_origin.1),
+            ));
+            return self.behavior;
+        }
+
+        if self.order.len() == STABILITY_WINDOW
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(hash, (generation, origin));
+        self.order.push_back(hash);
+        None
+    }
+}
+
+/// 安定判定の結果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stability {
+    /// 全滅または周期的な振る舞いに入った
+    Settled(Behavior),
+    /// 上限世代数までに安定しなかった
+    Unsettled,
+}
+
+/// 全滅するか周期的な振る舞いを検出するまで、1世代ずつ進める
+///
+/// `max_generations` はワールドの世代数の上限で、到達すると `Stability::Unsettled` を返す。
+pub fn run_until_stable(world: &mut World, max_generations: u64) -> Stability {
+    let mut detector = CycleDetector::new();
+    loop {
+        if let Some(behavior) = detector.observe(world.alive_cells(), world.generation_count) {
+            return Stability::Settled(behavior);
+        }
+        if world.generation_count >= max_generations {
+            return Stability::Unsettled;
+        }
+        world.advance_generations(1);
+    }
+}
+
+/// 平行移動を除いたセル集合のハッシュと、正規化に使った原点（左上端）を返す
+///
+/// セルごとのハッシュの和をとるため、列挙順に依存しない。全滅していれば `None`。
+fn normalized_hash(cells: &Bitboard) -> Option<(u64, (i32, i32))> {
+    let origin = cells
+        .iter()
+        .reduce(|(min_x, min_y), (x, y)| (min_x.min(x), min_y.min(y)))?;
+    let hash = cells.iter().fold(0u64, |hash, (x, y)| {
+        let dx = u64::from(x.abs_diff(origin.0));
+        let dy = u64::from(y.abs_diff(origin.1));
+        hash.wrapping_add(mix(dx << 32 | dy))
+    });
+    Some((hash, origin))
+}
+
+/// 64ビット値を攪拌する（SplitMix64 の最終化関数）
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::LifePattern;
+
+    fn until_stable(cells: &[(i32, i32)], max_generations: u64) -> (World, Stability) {
+        let mut world = World::new();
+        world.place_pattern(cells);
+        let stability = run_until_stable(&mut world, max_generations);
+        (world, stability)
+    }
+
+    #[test]
+    fn summary_counts_cells_and_bounds() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        world.advance_generations(1);
+        assert_eq!(
+            Summary::of(&world),
+            Summary {
+                generation: 1,
+                population: 3,
+                bounding_box: Some((1, -1, 1, 1)),
+            }
+        );
+        assert_eq!(Summary::of(&World::new()).bounding_box, None);
+    }
+
+    #[test]
+    fn still_life_is_detected() {
+        let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        let (world, stability) = until_stable(&block, 100);
+        assert_eq!(stability, Stability::Settled(Behavior::StillLife));
+        assert_eq!(world.generation_count, 1);
+    }
+
+    #[test]
+    fn oscillator_period_is_detected() {
+        let blinker = [(0, 0), (1, 0), (2, 0)];
+        let (world, stability) = until_stable(&blinker, 100);
+        assert_eq!(
+            stability,
+            Stability::Settled(Behavior::Oscillator { period: 2 })
+        );
+        assert_eq!(world.generation_count, 2);
+
+        let (_, stability) = until_stable(LifePattern::Pulsar.cells(), 100);
+        assert_eq!(
+            stability,
+            Stability::Settled(Behavior::Oscillator { period: 3 })
+        );
+    }
+
+    #[test]
+    fn extinction_is_detected() {
+        let (world, stability) = until_stable(&[(0, 0), (1, 0)], 100);
+        assert_eq!(stability, Stability::Settled(Behavior::Extinct));
+        assert_eq!(world.generation_count, 1);
+    }
+
+    #[test]
+    fn spaceships_are_detected_with_displacement() {
+        let (world, stability) = until_stable(LifePattern::Glider.cells(), 100);
+        let Stability::Settled(glider) = stability else {
+            panic!("glider was not detected");
+        };
+        assert_eq!(world.generation_count, 4);
+        assert_eq!(
+            glider,
+            Behavior::Spaceship {
+                period: 4,
+                displacement: (1, 1),
+            }
+        );
+        assert_eq!(glider.to_string(), "c/4 spaceship");
+
+        let (_, stability) = until_stable(LifePattern::Lwss.cells(), 100);
+        let Stability::Settled(lwss) = stability else {
+            panic!("LWSS was not detected");
+        };
+        assert_eq!(lwss.speed(), Some((1, 2)));
+        assert_eq!(lwss.to_string(), "c/2 spaceship");
+    }
+
+    #[test]
+    fn chaotic_pattern_stops_at_limit() {
+        let (world, stability) = until_stable(LifePattern::RPentomino.cells(), 50);
+        assert_eq!(stability, Stability::Unsettled);
+        assert_eq!(world.generation_count, 50);
+    }
+
+    #[test]
+    fn behavior_labels() {
+        assert_eq!(Behavior::Extinct.to_string(), "Extinct");
+        assert_eq!(Behavior::StillLife.to_string(), "Still life");
+        assert_eq!(
+            Behavior::Oscillator { period: 3 }.to_string(),
+            "P3 oscillator"
+        );
+        let spaceship = Behavior::Spaceship {
+            period: 5,
+            displacement: (0, -2),
+        };
+        assert_eq!(spaceship.to_string(), "2c/5 spaceship");
+        assert!(Behavior::StillLife.is_static());
+        assert!(!spaceship.is_static());
+    }
+
+    #[test]
+    fn non_consecutive_generation_restarts_detection() {
+        let block: Bitboard = [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().collect();
+        let mut detector = CycleDetector::new();
+        assert_eq!(detector.observe(&block, 0), None);
+        // 世代が飛んだ（早送り・巻き戻し）ので以前の記録とは比較しない
+        assert_eq!(detector.observe(&block, 5), None);
+        assert_eq!(detector.behavior(), None);
+        assert_eq!(detector.observe(&block, 6), Some(Behavior::StillLife));
+        // 検出済みの振る舞いは次の世代以降も保持し、改めて返さない
+        assert_eq!(detector.observe(&block, 7), None);
+        assert_eq!(detector.behavior(), Some(Behavior::StillLife));
+    }
+}
@@ -0,0 +1,373 @@
+//! 物体の国勢調査（センサス）
+//!
+//! 生存セルを8近傍で連結した島に分け、島ごとに単独で世代を進めて固定物体・振動子・移動物体に分類する。
+//! 分類した島は全位相・8通りの対称変換のうち最も短い（同じ長さなら辞書順で最小の）
+//! 拡張Wechsler形式で表した apgcode（例: ブロックは `xs4_33`）で識別し、既知の物体には名前を付ける。
+//!
+//! 8近傍で接していない部品からなる物体（パルサーなど）は部品ごとに数え、
+//! 周期を特定できない島は [`UNKNOWN_APGCODE`] にまとめる。
+//! Generations ルールでは、島から生じる衰退中のセルも含めて世代を進める。
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rustc_hash::FxHashSet;
+
+use super::{Behavior, CycleDetector};
+use crate::bitboard::Bitboard;
+use crate::consts::{SQUARE_COORDINATES, STABILITY_WINDOW};
+use crate::generations::DecayingCells;
+use crate::patterns::CellTransform;
+use crate::rules::Rule;
+
+/// 周期を特定できなかった島の apgcode
+pub const UNKNOWN_APGCODE: &str = "zz_UNKNOWN";
+
+/// apgcode と一般的な名前の対応表（B3/S23での名前）
+const KNOWN_OBJECTS: &[(&str, &str)] = &[
+    ("xs4_33", "block"),
+    ("xs4_252", "tub"),
+    ("xs5_253", "boat"),
+    ("xs6_356", "ship"),
+    ("xs6_696", "beehive"),
+    ("xs7_2596", "loaf"),
+    ("xs8_6996", "pond"),
+    ("xp2_7", "blinker"),
+    ("xp2_7e", "toad"),
+    ("xp2_318c", "beacon"),
+    ("xq4_153", "glider"),
+    ("xq4_6frc", "LWSS"),
+];
+
+/// センサスの1項目（同じ apgcode の物体の数）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CensusEntry {
+    /// 物体の apgcode
+    pub apgcode: String,
+    /// 既知の物体の名前
+    pub name: Option<&'static str>,
+    /// 物体の数
+    pub count: usize,
+}
+
+impl fmt::Display for CensusEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "{}", self.apgcode),
+        }
+    }
+}
+
+/// ワールド内の物体の種類ごとの数
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    /// 数の多い順（同数なら apgcode 順）に並べた項目
+    pub entries: Vec<CensusEntry>,
+}
+
+impl Census {
+    /// 数えた物体の総数を返す
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// 指定した apgcode の物体の数を返す
+    pub fn count(&self, apgcode: &str) -> usize {
+        self.entries
+            .iter()
+            .find(|entry| entry.apgcode == apgcode)
+            .map_or(0, |entry| entry.count)
+    }
+}
+
+/// 生存セルを島に分け、ルール `rule` のもとで分類して数える
+pub fn take_census(cells: &Bitboard, rule: &Rule) -> Census {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for island in islands(cells) {
+        *counts.entry(classify(&island, rule)).or_default() += 1;
+    }
+    let mut entries: Vec<CensusEntry> = counts
+        .into_iter()
+        .map(|(apgcode, count)| CensusEntry {
+            name: known_name(&apgcode),
+            apgcode,
+            count,
+        })
+        .collect();
+    // NOTE: BTreeMap から取り出した apgcode 順を安定ソートで保ったまま数の多い順に並べる
+    entries.sort_by_key(|entry| Reverse(entry.count));
+    Census { entries }
+}
+
+/// apgcode に対応する既知の物体の名前を返す
+pub fn known_name(apgcode: &str) -> Option<&'static str> {
+    KNOWN_OBJECTS
+        .iter()
+        .find(|&&(code, _)| code == apgcode)
+        .map(|&(_, name)| name)
+}
+
+/// 生存セルを8近傍で連結した島に分ける
+fn islands(cells: &Bitboard) -> Vec<Vec<(i32, i32)>> {
+    let mut remaining: FxHashSet<(i32, i32)> = cells.iter().collect();
+    let mut islands = Vec::new();
+    while let Some(&start) = remaining.iter().next() {
+        remaining.remove(&start);
+        let mut island = vec![start];
+        let mut index = 0;
+        while let Some(&(x, y)) = island.get(index) {
+            index += 1;
+            for &(dx, dy) in &SQUARE_COORDINATES {
+                let neighbor = (x + i32::from(dx), y + i32::from(dy));
+                if remaining.remove(&neighbor) {
+                    island.push(neighbor);
+                }
+            }
+        }
+        islands.push(island);
+    }
+    islands
+}
+
+/// 島を単独で世代を進めて分類し、apgcode を返す
+fn classify(island: &[(i32, i32)], rule: &Rule) -> String {
+    let mut board: Bitboard = island.iter().copied().collect();
+    let mut decaying = DecayingCells::new();
+    let mut detector = CycleDetector::new();
+    let mut generation = 0;
+    let behavior = loop {
+        if let Some(behavior) = detector.observe(&board, generation) {
+            break behavior;
+        }
+        if generation >= STABILITY_WINDOW as u64 {
+            return UNKNOWN_APGCODE.to_string();
+        }
+        board = step(&board, &mut decaying, rule);
+        generation += 1;
+    };
+
+    let (prefix, period) = match behavior {
+        Behavior::Extinct => return UNKNOWN_APGCODE.to_string(),
+        Behavior::StillLife => (format!("xs{}", board.len()), 1),
+        Behavior::Oscillator { period } => (format!("xp{period}"), period),
+        Behavior::Spaceship { period, .. } => (format!("xq{period}"), period),
+    };
+    let mut best: Option<String> = None;
+    for _ in 0..period {
+        let cells: Vec<(i32, i32)> = board.iter().collect();
+        for code in orientations(&cells).iter().map(|cells| wechsler(cells)) {
+            if best
+                .as_ref()
+                .is_none_or(|best| (code.len(), &code) < (best.len(), best))
+            {
+                best = Some(code);
+            }
+        }
+        board = step(&board, &mut decaying, rule);
+    }
+    format!("{prefix}_{}", best.unwrap_or_default())
+}
+
+/// 衰退中のセルを含めて島を1世代進める
+fn step(board: &Bitboard, decaying: &mut DecayingCells, rule: &Rule) -> Bitboard {
+    let mut next = board.step(rule);
+    decaying.advance(board, &mut next, rule.states());
+    next
+}
+
+/// 回転・反転による8通りの向きのセル座標列を返す
+fn orientations(cells: &[(i32, i32)]) -> Vec<Vec<(i32, i32)>> {
+    let flipped = CellTransform::FlipHorizontal.apply_all(cells);
+    [cells.to_vec(), flipped]
+        .into_iter()
+        .flat_map(|mut cells| {
+            (0..4).map(move |_| {
+                let current = cells.clone();
+                cells = CellTransform::RotateClockwise.apply_all(&cells);
+                current
+            })
+        })
+        .collect()
+}
+
+/// セル座標列を拡張Wechsler形式で符号化する
+///
+/// 左上を原点に平行移動し、5行ずつの帯に分けて各列の5セルを1文字（`0-9a-v`）で表す。
+/// 帯は `z` で区切り、帯末尾の空列は省く。連続する空列は `w`（2列）・`x`（3列）・
+/// `y` と1文字（4〜39列）に圧縮する。
+fn wechsler(cells: &[(i32, i32)]) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let Some(min_x) = cells.iter().map(|&(x, _)| x).min() else {
+        return String::new();
+    };
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let width = cells.iter().map(|&(x, _)| x - min_x + 1).max().unwrap_or(0) as usize;
+    let height = cells.iter().map(|&(_, y)| y - min_y + 1).max().unwrap_or(0) as usize;
+
+    let strips = height.div_ceil(5);
+    let mut columns = vec![vec![0u8; width]; strips];
+    for &(x, y) in cells {
+        let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+        columns[y / 5][x] |= 1 << (y % 5);
+    }
+
+    let mut code = String::new();
+    for (index, strip) in columns.iter().enumerate() {
+        if index > 0 {
+            code.push('z');
+        }
+        let used = strip
+            .iter()
+            .rposition(|&column| column != 0)
+            .map_or(0, |i| i + 1);
+        let mut zeros = 0;
+        for &column in &strip[..used] {
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_zeros(&mut code, zeros, DIGITS);
+            zeros = 0;
+            code.push(DIGITS[column as usize] as char);
+        }
+    }
+    code
+}
+
+/// 連続する `zeros` 列の空列を圧縮して追加する
+fn push_zeros(code: &mut String, mut zeros: usize, digits: &[u8; 36]) {
+    while zeros > 0 {
+        match zeros {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                let run = zeros.min(39);
+                code.push('y');
+                code.push(digits[run - 4] as char);
+                zeros -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::LifePattern;
+
+    fn apgcode(cells: &[(i32, i32)]) -> String {
+        classify(cells, &Rule::conway())
+    }
+
+    #[test]
+    fn known_objects_have_expected_apgcodes() {
+        let cases: [(&[(i32, i32)], &str); 12] = [
+            (&[(0, 0), (1, 0), (0, 1), (1, 1)], "xs4_33"),
+            (&[(1, 0), (0, 1), (2, 1), (1, 2)], "xs4_252"),
+            (&[(0, 0), (1, 0), (0, 1), (2, 1), (1, 2)], "xs5_253"),
+            (&[(0, 0), (1, 0), (0, 1), (2, 1), (1, 2), (2, 2)], "xs6_356"),
+            (&[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)], "xs6_696"),
+            (
+                &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (3, 2), (2, 3)],
+                "xs7_2596",
+            ),
+            (
+                &[
+                    (1, 0),
+                    (2, 0),
+                    (0, 1),
+                    (3, 1),
+                    (0, 2),
+                    (3, 2),
+                    (1, 3),
+                    (2, 3),
+                ],
+                "xs8_6996",
+            ),
+            (&[(0, 0), (1, 0), (2, 0)], "xp2_7"),
+            (&[(1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1)], "xp2_7e"),
+            (
+                &[
+                    (0, 0),
+                    (1, 0),
+                    (0, 1),
+                    (1, 1),
+                    (2, 2),
+                    (3, 2),
+                    (2, 3),
+                    (3, 3),
+                ],
+                "xp2_318c",
+            ),
+            (LifePattern::Glider.cells(), "xq4_153"),
+            (LifePattern::Lwss.cells(), "xq4_6frc"),
+        ];
+        for (cells, expected) in cases {
+            assert_eq!(apgcode(cells), expected, "cells: {cells:?}");
+            assert!(known_name(expected).is_some());
+        }
+    }
+
+    #[test]
+    fn apgcode_is_independent_of_orientation_and_phase() {
+        let glider = LifePattern::Glider.cells();
+        for cells in orientations(glider) {
+            assert_eq!(apgcode(&cells), "xq4_153");
+        }
+        let mut board: Bitboard = glider.iter().copied().collect();
+        board = board.step(&Rule::conway()).step(&Rule::conway());
+        assert_eq!(apgcode(&board.iter().collect::<Vec<_>>()), "xq4_153");
+    }
+
+    #[test]
+    fn wechsler_compresses_gaps_and_strips() {
+        // 空列の圧縮: 1列・2列(w)・3列(x)・4列以上(y+文字)
+        assert_eq!(wechsler(&[(0, 0), (2, 0)]), "101");
+        assert_eq!(wechsler(&[(0, 0), (3, 0)]), "1w1");
+        assert_eq!(wechsler(&[(0, 0), (4, 0)]), "1x1");
+        assert_eq!(wechsler(&[(0, 0), (5, 0)]), "1y01");
+        // 43列 = 39列(yz) + 4列(y0)
+        assert_eq!(wechsler(&[(0, 0), (44, 0)]), "1yzy01");
+        // 6行目以降は次の帯になり、帯末尾の空列は省く
+        assert_eq!(wechsler(&[(0, 0), (1, 0), (0, 5)]), "11z1");
+    }
+
+    #[test]
+    fn census_counts_islands_by_type() {
+        let mut cells: Vec<(i32, i32)> = Vec::new();
+        let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        for offset in [0, 10, 20] {
+            cells.extend(block.iter().map(|&(x, y)| (x + offset, y)));
+        }
+        cells.extend([(0, 10), (1, 10), (2, 10)]);
+        cells.extend(
+            LifePattern::Glider
+                .cells()
+                .iter()
+                .map(|&(x, y)| (x + 20, y + 20)),
+        );
+        // 単独では死滅するセルは分類できない
+        cells.push((40, 40));
+
+        let census = take_census(&cells.into_iter().collect(), &Rule::conway());
+        assert_eq!(census.total(), 6);
+        assert_eq!(census.count("xs4_33"), 3);
+        assert_eq!(census.count("xp2_7"), 1);
+        assert_eq!(census.count("xq4_153"), 1);
+        assert_eq!(census.count(UNKNOWN_APGCODE), 1);
+        assert_eq!(census.entries[0].to_string(), "block");
+        assert_eq!(census.entries[0].count, 3);
+    }
+
+    #[test]
+    fn empty_world_has_empty_census() {
+        let census = take_census(&Bitboard::new(), &Rule::conway());
+        assert_eq!(census, Census::default());
+        assert_eq!(census.total(), 0);
+    }
+}
@@ -0,0 +1,711 @@
+//! チャンク単位のビットボード
+//!
+//! 1チャンク（64×64セル）を `[u64; 64]` の行配列で表し、ビット `x` が行内のセル `x` に対応する。
+//! 世代計算は1行64セルをまとめてビット演算の加算器で数え上げ、
+//! チャンク境界のセルは隣接チャンクの端の行・列を参照して補う。
+//! 各チャンクの次世代は現世代のみから決まるため、チャンク単位で並列に計算できる。
+
+use crate::consts::{CHUNK_SIZE, PARALLEL_MIN_CHUNKS};
+use crate::rules::Rule;
+#[cfg(feature = "bevy")]
+use bevy_tasks::TaskPool;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// チャンクの座標キー (chunk_x, chunk_y)
+pub type ChunkKey = (i32, i32);
+
+/// 1チャンク分のセル行（行 `y` のビット `x` がローカル座標 `(x, y)` のセル）
+pub type ChunkRows = [u64; CHUNK_SIZE as usize];
+
+// 1行を u64 1語で表すため、チャンクサイズは64固定
+const _: () = assert!(CHUNK_SIZE == 64);
+
+/// 全セルが死亡しているチャンク
+const EMPTY_ROWS: ChunkRows = [0; CHUNK_SIZE as usize];
+
+/// 最終行のインデックス
+const LAST: usize = CHUNK_SIZE as usize - 1;
+
+/// 生存セルを含むチャンクのみを保持する無限平面のビットボード
+///
+/// 全セルが死亡したチャンクは保持しない。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bitboard {
+    chunks: FxHashMap<ChunkKey, ChunkRows>,
+}
+
+impl Bitboard {
+    /// 空のビットボードを生成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// セル座標からチャンクキーを計算する
+    pub fn chunk_key(x: i32, y: i32) -> ChunkKey {
+        (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE))
+    }
+
+    /// セル座標からチャンク内のローカル座標 `(列, 行)` を計算する
+    fn local(x: i32, y: i32) -> (usize, usize) {
+        (
+            x.rem_euclid(CHUNK_SIZE) as usize,
+            y.rem_euclid(CHUNK_SIZE) as usize,
+        )
+    }
+
+    /// 指定座標のセルが生きているかを返す
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        let (lx, ly) = Self::local(x, y);
+        self.chunks
+            .get(&Self::chunk_key(x, y))
+            .is_some_and(|rows| rows[ly] >> lx & 1 == 1)
+    }
+
+    /// 指定座標のセルの生死を設定し、状態が変わったかを返す
+    pub fn set(&mut self, x: i32, y: i32, alive: bool) -> bool {
+        let key = Self::chunk_key(x, y);
+        let (lx, ly) = Self::local(x, y);
+        let bit = 1u64 << lx;
+        if alive {
+            let row = &mut self.chunks.entry(key).or_insert(EMPTY_ROWS)[ly];
+            let changed = *row & bit == 0;
+            *row |= bit;
+            changed
+        } else {
+            let Some(rows) = self.chunks.get_mut(&key) else {
+                return false;
+            };
+            let changed = rows[ly] & bit != 0;
+            rows[ly] &= !bit;
+            if rows.iter().all(|&row| row == 0) {
+                self.chunks.remove(&key);
+            }
+            changed
+        }
+    }
+
+    /// 指定チャンクのセル行を返す（生存セルがなければ `None`）
+    pub fn chunk(&self, key: ChunkKey) -> Option<&ChunkRows> {
+        self.chunks.get(&key)
+    }
+
+    /// 生存セルを含むチャンクのキーを返す
+    pub fn chunk_keys(&self) -> impl Iterator<Item = ChunkKey> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// 生存セル数を返す
+    pub fn len(&self) -> usize {
+        self.chunks
+            .values()
+            .flat_map(|rows| rows.iter())
+            .map(|row| row.count_ones() as usize)
+            .sum()
+    }
+
+    /// 生存セルが1つもないかを返す
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// 全セルを死亡状態にする
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// 生存セルの座標を列挙する（順序は不定）
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.iter().flat_map(|(&(cx, cy), rows)| {
+            rows.iter().enumerate().flat_map(move |(ly, &row)| {
+                BitIter(row)
+                    .map(move |lx| (cx * CHUNK_SIZE + lx as i32, cy * CHUNK_SIZE + ly as i32))
+            })
+        })
+    }
+
+    /// 矩形 `min`〜`max`（両端を含む）内の生存セルの座標を列挙する（順序は不定）
+    pub fn iter_rect(
+        &self,
+        min: (i32, i32),
+        max: (i32, i32),
+    ) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let (min_cx, min_cy) = Self::chunk_key(min.0, min.1);
+        let (max_cx, max_cy) = Self::chunk_key(max.0, max.1);
+        self.chunks
+            .keys()
+            .filter(move |&&(cx, cy)| {
+                (min_cx..=max_cx).contains(&cx) && (min_cy..=max_cy).contains(&cy)
+            })
+            .flat_map(move |&(cx, cy)| {
+                let rows = &self.chunks[&(cx, cy)];
+                rows.iter().enumerate().flat_map(move |(ly, &row)| {
+                    BitIter(row)
+                        .map(move |lx| (cx * CHUNK_SIZE + lx as i32, cy * CHUNK_SIZE + ly as i32))
+                })
+            })
+            .filter(move |&(x, y)| (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y))
+    }
+
+    /// 2つのビットボードで内容が異なるチャンクのキーを返す
+    pub fn diff_chunks(&self, other: &Bitboard) -> FxHashSet<ChunkKey> {
+        let mut keys: FxHashSet<ChunkKey> = self
+            .chunks
+            .iter()
+            .filter(|&(key, rows)| other.chunks.get(key) != Some(rows))
+            .map(|(&key, _)| key)
+            .collect();
+        keys.extend(
+            other
+                .chunks
+                .keys()
+                .filter(|key| !self.chunks.contains_key(key)),
+        );
+        keys
+    }
+
+    /// 2つのビットボードの排他的論理和（どちらか一方のみで生存しているセル）を返す
+    ///
+    /// 結果を元のどちらかと再度XORすると、もう一方が得られる。
+    pub fn xor(&self, other: &Bitboard) -> Bitboard {
+        let mut result = self.clone();
+        result.xor_assign(other);
+        result
+    }
+
+    /// `other` との排他的論理和で自身を更新する
+    pub fn xor_assign(&mut self, other: &Bitboard) {
+        for (&key, other_rows) in &other.chunks {
+            let rows = self.chunks.entry(key).or_insert(EMPTY_ROWS);
+            for (row, other_row) in rows.iter_mut().zip(other_rows) {
+                *row ^= other_row;
+            }
+            if *rows == EMPTY_ROWS {
+                self.chunks.remove(&key);
+            }
+        }
+    }
+
+    /// 自身にあって `other` にないセルを返す
+    pub fn difference(&self, other: &Bitboard) -> Bitboard {
+        let mut result = self.clone();
+        result.difference_assign(other);
+        result
+    }
+
+    /// `other` で生存しているセルを自身から取り除く
+    pub fn difference_assign(&mut self, other: &Bitboard) {
+        for (key, other_rows) in &other.chunks {
+            let Some(rows) = self.chunks.get_mut(key) else {
+                continue;
+            };
+            for (row, other_row) in rows.iter_mut().zip(other_rows) {
+                *row &= !other_row;
+            }
+            if *rows == EMPTY_ROWS {
+                self.chunks.remove(key);
+            }
+        }
+    }
+
+    /// 矩形 `min`〜`max`（両端を含む）の外側のセルを全て死亡状態にする
+    pub fn retain_rect(&mut self, min: (i32, i32), max: (i32, i32)) {
+        self.chunks.retain(|&(cx, cy), rows| {
+            let (x0, y0) = (cx * CHUNK_SIZE, cy * CHUNK_SIZE);
+            let lo = (min.0 - x0).clamp(0, CHUNK_SIZE);
+            let hi = (max.0 - x0 + 1).clamp(0, CHUNK_SIZE);
+            if lo >= hi {
+                return false;
+            }
+            let mask = (u64::MAX >> (CHUNK_SIZE - (hi - lo))) << lo;
+            for (ly, row) in rows.iter_mut().enumerate() {
+                let y = y0 + ly as i32;
+                if (min.1..=max.1).contains(&y) {
+                    *row &= mask;
+                } else {
+                    *row = 0;
+                }
+            }
+            *rows != EMPTY_ROWS
+        });
+    }
+
+    /// 保持しているチャンクのおおよそのメモリ使用量（バイト）を返す
+    pub fn memory_bytes(&self) -> usize {
+        self.chunks.len() * (size_of::<ChunkKey>() + size_of::<ChunkRows>())
+    }
+
+    /// 次世代で生存セルを持ちうるチャンクのキーを返す
+    ///
+    /// 生存セルを含むチャンクに加え、辺・角に生存セルが接している隣接チャンクを含む。
+    pub fn step_candidates(&self) -> FxHashSet<ChunkKey> {
+        let mut keys = FxHashSet::default();
+        for (&(cx, cy), rows) in &self.chunks {
+            keys.insert((cx, cy));
+            let left = rows.iter().any(|&row| row & 1 != 0);
+            let right = rows.iter().any(|&row| row >> LAST != 0);
+            let top = rows[0] != 0;
+            let bottom = rows[LAST] != 0;
+            let edges = [
+                ((-1, 0), left),
+                ((1, 0), right),
+                ((0, -1), top),
+                ((0, 1), bottom),
+                ((-1, -1), rows[0] & 1 != 0),
+                ((1, -1), rows[0] >> LAST != 0),
+                ((-1, 1), rows[LAST] & 1 != 0),
+                ((1, 1), rows[LAST] >> LAST != 0),
+            ];
+            for ((dx, dy), touching) in edges {
+                if touching {
+                    keys.insert((cx + dx, cy + dy));
+                }
+            }
+        }
+        keys
+    }
+
+    /// 指定チャンクを1世代進めたセル行を返す
+    ///
+    /// チャンク外周の近傍は隣接8チャンクの端の行・列から取得する。
+    pub fn step_chunk(&self, key: ChunkKey, rule: &Rule) -> ChunkRows {
+        self.step_chunk_with(key, &RuleMasks::new(rule))
+    }
+
+    /// 事前に求めたルールの条件で指定チャンクを1世代進めたセル行を返す
+    fn step_chunk_with(&self, key: ChunkKey, rule_masks: &RuleMasks) -> ChunkRows {
+        let (cx, cy) = key;
+        let rows_of =
+            |dx: i32, dy: i32| self.chunks.get(&(cx + dx, cy + dy)).unwrap_or(&EMPTY_ROWS);
+        let center = rows_of(0, 0);
+        let (west, east) = (rows_of(-1, 0), rows_of(1, 0));
+        let (north, north_west, north_east) = (rows_of(0, -1), rows_of(-1, -1), rows_of(1, -1));
+        let (south, south_west, south_east) = (rows_of(0, 1), rows_of(-1, 1), rows_of(1, 1));
+
+        // ローカル行 y（-1..=64）の (行, 西隣の行, 東隣の行)
+        let row_at = |y: isize| -> (u64, u64, u64) {
+            match y {
+                -1 => (north[LAST], north_west[LAST], north_east[LAST]),
+                64 => (south[0], south_west[0], south_east[0]),
+                _ => {
+                    let y = y as usize;
+                    (center[y], west[y], east[y])
+                }
+            }
+        };
+
+        let mut next = EMPTY_ROWS;
+        for (y, out) in next.iter_mut().enumerate() {
+            let y = y as isize;
+            let mut counter = NeighborCounter::default();
+            for dy in [-1, 0, 1] {
+                let (row, w, e) = row_at(y + dy);
+                counter.add(shift_west(row, w));
+                counter.add(shift_east(row, e));
+                if dy != 0 {
+                    counter.add(row);
+                }
+            }
+            *out = rule_masks.apply(center[y as usize], &counter);
+        }
+        next
+    }
+
+    /// 全チャンクを1世代進めたビットボードを返す
+    ///
+    /// 隣接セルはルールの近傍によらずムーア近傍で数え、隣接セルの配置は区別しない。
+    pub fn step(&self, rule: &Rule) -> Bitboard {
+        let rule_masks = RuleMasks::new(rule);
+        let mut chunks = FxHashMap::default();
+        for key in self.step_candidates() {
+            let rows = self.step_chunk_with(key, &rule_masks);
+            if rows.iter().any(|&row| row != 0) {
+                chunks.insert(key, rows);
+            }
+        }
+        Bitboard { chunks }
+    }
+
+    /// 全チャンクを1世代進めたビットボードを、`threads` 本のスレッドで並列に計算して返す
+    ///
+    /// 対象チャンクをキー順に並べてスレッド数で分割し、各スレッドの結果を
+    /// 分割順に統合する。結果は `step` と常に一致する。
+    /// 対象が `PARALLEL_MIN_CHUNKS` 未満の場合は `step` と同じく逐次計算する。
+    pub fn step_parallel(&self, rule: &Rule, threads: usize) -> Bitboard {
+        let Some(keys) = self.parallel_candidates(threads) else {
+            return self.step(rule);
+        };
+        let batch_size = keys.len().div_ceil(threads);
+        let rule_masks = &RuleMasks::new(rule);
+        let chunks = std::thread::scope(|scope| {
+            let handles: Vec<_> = keys
+                .chunks(batch_size)
+                .map(|batch| scope.spawn(move || self.step_batch(batch, rule_masks)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+                })
+                .collect()
+        });
+        Bitboard { chunks }
+    }
+
+    /// 全チャンクを1世代進めたビットボードを、タスクプール上で並列に計算して返す
+    ///
+    /// 分割と統合の方法は `step_parallel` と同じで、結果は `step` と常に一致する。
+    #[cfg(feature = "bevy")]
+    pub fn step_on_pool(&self, rule: &Rule, pool: &TaskPool) -> Bitboard {
+        let Some(keys) = self.parallel_candidates(pool.thread_num()) else {
+            return self.step(rule);
+        };
+        let batch_size = keys.len().div_ceil(pool.thread_num());
+        let rule_masks = &RuleMasks::new(rule);
+        let batches = pool.scope(|scope| {
+            for batch in keys.chunks(batch_size) {
+                scope.spawn(async move { self.step_batch(batch, rule_masks) });
+            }
+        });
+        Bitboard {
+            chunks: batches.into_iter().flatten().collect(),
+        }
+    }
+
+    /// 並列に計算する対象チャンクをキー順に返す（逐次計算で済む場合は `None`）
+    fn parallel_candidates(&self, threads: usize) -> Option<Vec<ChunkKey>> {
+        let mut keys: Vec<ChunkKey> = self.step_candidates().into_iter().collect();
+        if keys.len() < PARALLEL_MIN_CHUNKS || threads <= 1 {
+            return None;
+        }
+        keys.sort_unstable();
+        Some(keys)
+    }
+
+    /// 指定チャンクをそれぞれ1世代進め、生存セルが残るチャンクだけを返す
+    fn step_batch(&self, batch: &[ChunkKey], rule_masks: &RuleMasks) -> Vec<(ChunkKey, ChunkRows)> {
+        batch
+            .iter()
+            .map(|&key| (key, self.step_chunk_with(key, rule_masks)))
+            .filter(|(_, rows)| rows.iter().any(|&row| row != 0))
+            .collect()
+    }
+}
+
+impl FromIterator<(i32, i32)> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = (i32, i32)>>(iter: I) -> Self {
+        let mut board = Bitboard::new();
+        for (x, y) in iter {
+            board.set(x, y, true);
+        }
+        board
+    }
+}
+
+/// 各ビット位置に西隣（x-1）のセルを並べた語を返す
+///
+/// ビット0には西隣チャンクの同じ行の最終列が入る。
+fn shift_west(row: u64, west_row: u64) -> u64 {
+    row << 1 | west_row >> LAST
+}
+
+/// 各ビット位置に東隣（x+1）のセルを並べた語を返す
+///
+/// ビット63には東隣チャンクの同じ行の先頭列が入る。
+fn shift_east(row: u64, east_row: u64) -> u64 {
+    row >> 1 | east_row << LAST
+}
+
+/// 64セル分の近傍数を4枚のビット平面で保持するビットスライス加算器
+#[derive(Default)]
+struct NeighborCounter([u64; 4]);
+
+impl NeighborCounter {
+    /// 各ビット位置の近傍数に入力語のビットを加算する
+    fn add(&mut self, mut carry: u64) {
+        for plane in &mut self.0 {
+            let next_carry = *plane & carry;
+            *plane ^= carry;
+            carry = next_carry;
+        }
+    }
+
+    /// 近傍数がちょうど `n` のビット位置を返す
+    fn equals(&self, n: u32) -> u64 {
+        self.0.iter().enumerate().fold(!0, |mask, (bit, &plane)| {
+            mask & if n >> bit & 1 == 1 { plane } else { !plane }
+        })
+    }
+}
+
+/// ルールの誕生・生存条件に該当する近傍数の一覧
+///
+/// 1世代の計算で1回だけ生成し、全チャンクで共有する。
+struct RuleMasks {
+    birth: Vec<u32>,
+    survival: Vec<u32>,
+}
+
+impl RuleMasks {
+    fn new(rule: &Rule) -> Self {
+        Self {
+            birth: (0..=8).filter(|&n| rule.is_birth(n as u16)).collect(),
+            survival: (0..=8).filter(|&n| rule.is_survival(n as u16)).collect(),
+        }
+    }
+
+    /// 現在の行と近傍数から次世代の行を計算する
+    fn apply(&self, alive: u64, counter: &NeighborCounter) -> u64 {
+        let born = self.birth.iter().fold(0, |m, &n| m | counter.equals(n));
+        let survive = self.survival.iter().fold(0, |m, &n| m | counter.equals(n));
+        (born & !alive) | (survive & alive)
+    }
+}
+
+/// 語の立っているビット位置を昇順に列挙するイテレータ
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+        let bit = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SparseEngine;
+    use crate::patterns::LifePattern;
+    use crate::rules::RulePreset;
+
+    fn sorted(board: &Bitboard) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = board.iter().collect();
+        cells.sort();
+        cells
+    }
+
+    fn sparse_step(board: &Bitboard, rule: &Rule) -> Vec<(i32, i32)> {
+        let set: FxHashSet<_> = board.iter().collect();
+        let mut cells: Vec<_> = SparseEngine::step(&set, rule).into_iter().collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn set_and_get_with_negative_coordinates() {
+        let mut board = Bitboard::new();
+        assert!(board.set(-1, -1, true));
+        assert!(!board.set(-1, -1, true));
+        assert!(board.get(-1, -1));
+        assert!(!board.get(-1, 0));
+        assert_eq!(board.chunk((-1, -1)).unwrap()[63], 1 << 63);
+    }
+
+    #[test]
+    fn clearing_last_cell_removes_chunk() {
+        let mut board = Bitboard::new();
+        board.set(100, 5, true);
+        assert!(board.set(100, 5, false));
+        assert!(board.is_empty());
+        assert!(!board.set(100, 5, false));
+    }
+
+    #[test]
+    fn len_counts_cells_across_chunks() {
+        let board: Bitboard = [(0, 0), (63, 63), (64, 0), (-1, 0)].into_iter().collect();
+        assert_eq!(board.len(), 4);
+        assert_eq!(board.chunk_keys().count(), 3);
+    }
+
+    #[test]
+    fn iter_returns_all_cells() {
+        let cells = [(0, 0), (63, 1), (-64, -64), (-65, 200)];
+        let board: Bitboard = cells.into_iter().collect();
+        let mut expected = cells.to_vec();
+        expected.sort();
+        assert_eq!(sorted(&board), expected);
+    }
+
+    #[test]
+    fn iter_rect_filters_across_chunks() {
+        let board: Bitboard = [(-1, -1), (0, 0), (63, 64), (64, 64), (200, 0)]
+            .into_iter()
+            .collect();
+        let mut cells: Vec<_> = board.iter_rect((-1, -1), (63, 64)).collect();
+        cells.sort();
+        assert_eq!(cells, vec![(-1, -1), (0, 0), (63, 64)]);
+    }
+
+    #[test]
+    fn xor_is_self_inverse_and_drops_empty_chunks() {
+        let a: Bitboard = [(0, 0), (1, 0), (-1, 70)].into_iter().collect();
+        let b: Bitboard = [(1, 0), (-1, 70), (200, 200)].into_iter().collect();
+        let diff = a.xor(&b);
+        assert_eq!(sorted(&diff), vec![(0, 0), (200, 200)]);
+        assert!(diff.chunk((-1, 1)).is_none());
+        assert_eq!(a.xor(&diff), b);
+        assert!(a.xor(&a).is_empty());
+        assert_eq!(
+            diff.memory_bytes(),
+            2 * (size_of::<ChunkKey>() + size_of::<ChunkRows>())
+        );
+    }
+
+    #[test]
+    fn difference_removes_other_cells_and_drops_empty_chunks() {
+        let a: Bitboard = [(0, 0), (1, 0), (-1, 70)].into_iter().collect();
+        let b: Bitboard = [(1, 0), (-1, 70), (200, 200)].into_iter().collect();
+        let diff = a.difference(&b);
+        assert_eq!(sorted(&diff), vec![(0, 0)]);
+        assert!(diff.chunk((-1, 1)).is_none());
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn retain_rect_clips_cells_across_chunks() {
+        let mut board: Bitboard = [(-1, 0), (0, 0), (63, 5), (64, 5), (70, 70), (10, -1)]
+            .into_iter()
+            .collect();
+        board.retain_rect((0, 0), (63, 63));
+        assert_eq!(sorted(&board), vec![(0, 0), (63, 5)]);
+        assert_eq!(board.chunk_keys().count(), 1);
+    }
+
+    #[test]
+    fn blinker_on_chunk_corner() {
+        // 4チャンクの角 (0,0) をまたぐ縦ブリンカー
+        let board: Bitboard = [(0, -1), (0, 0), (0, 1)].into_iter().collect();
+        let next = board.step(&Rule::conway());
+        assert_eq!(sorted(&next), vec![(-1, 0), (0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn birth_in_empty_neighbor_chunk() {
+        // チャンク(0,0)の上端の3セルから、チャンク(0,-1)に誕生する
+        let board: Bitboard = [(10, 0), (11, 0), (12, 0)].into_iter().collect();
+        let next = board.step(&Rule::conway());
+        assert!(next.get(11, -1));
+        assert!(next.chunk((0, -1)).is_some());
+    }
+
+    #[test]
+    fn step_candidates_include_touched_neighbors_only() {
+        let board: Bitboard = [(63, 63)].into_iter().collect();
+        let candidates = board.step_candidates();
+        for key in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert!(candidates.contains(&key));
+        }
+        assert!(!candidates.contains(&(-1, 0)));
+        assert!(!candidates.contains(&(0, -1)));
+    }
+
+    #[test]
+    fn matches_sparse_stepper_for_all_patterns() {
+        for pattern in LifePattern::all() {
+            // チャンク境界をまたぐ位置に配置
+            let mut board: Bitboard = pattern
+                .cells()
+                .iter()
+                .map(|&(x, y)| (x + 64, y - 1))
+                .collect();
+            for generation in 0..60 {
+                let expected = sparse_step(&board, &Rule::conway());
+                board = board.step(&Rule::conway());
+                assert_eq!(sorted(&board), expected, "{pattern:?} gen {generation}");
+            }
+        }
+    }
+
+    #[test]
+    fn matches_sparse_stepper_for_other_rules() {
+        for preset in RulePreset::all() {
+            let rule = preset.rule();
+            let mut board: Bitboard = LifePattern::Acorn
+                .cells()
+                .iter()
+                .map(|&(x, y)| (x - 1, y + 63))
+                .collect();
+            for _ in 0..30 {
+                let expected = sparse_step(&board, &rule);
+                board = board.step(&rule);
+                assert_eq!(sorted(&board), expected, "{preset:?}");
+            }
+        }
+    }
+
+    /// テスト用の xorshift64 乱数生成器
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    /// 原点を中心とする `size`×`size` の範囲に約50%の密度でセルを配置する
+    fn random_soup(seed: u64, size: i32) -> Bitboard {
+        let mut rng = XorShift(seed);
+        let half = size / 2;
+        (-half..half)
+            .flat_map(|y| (-half..half).map(move |x| (x, y)))
+            .filter(|_| rng.next() & 1 == 1)
+            .collect()
+    }
+
+    #[test]
+    fn parallel_step_matches_serial_for_random_soup() {
+        let rule = Rule::conway();
+        let mut serial = random_soup(0x9E37_79B9_7F4A_7C15, 192);
+        let mut parallel = serial.clone();
+        for generation in 0..1000 {
+            serial = serial.step(&rule);
+            parallel = parallel.step_parallel(&rule, 4);
+            assert_eq!(parallel, serial, "diverged at generation {generation}");
+        }
+        assert!(!serial.is_empty());
+    }
+
+    #[test]
+    fn parallel_step_with_single_thread() {
+        let board = random_soup(42, 128);
+        assert_eq!(
+            board.step_parallel(&Rule::conway(), 1),
+            board.step(&Rule::conway())
+        );
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn pool_step_matches_serial_for_random_soup() {
+        let pool = bevy_tasks::TaskPoolBuilder::new().num_threads(4).build();
+        let rule = Rule::conway();
+        let mut serial = random_soup(0x9E37_79B9_7F4A_7C15, 192);
+        let mut parallel = serial.clone();
+        for generation in 0..200 {
+            serial = serial.step(&rule);
+            parallel = parallel.step_on_pool(&rule, &pool);
+            assert_eq!(parallel, serial, "diverged at generation {generation}");
+        }
+    }
+
+    #[test]
+    fn diff_chunks_reports_changed_added_and_removed() {
+        let a: Bitboard = [(0, 0), (100, 0), (-5, -5)].into_iter().collect();
+        let b: Bitboard = [(1, 0), (100, 0), (200, 200)].into_iter().collect();
+        let diff = a.diff_chunks(&b);
+        let mut keys: Vec<_> = diff.into_iter().collect();
+        keys.sort();
+        assert_eq!(keys, vec![(-1, -1), (0, 0), (3, 3)]);
+    }
+}
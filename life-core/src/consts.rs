@@ -0,0 +1,62 @@
+//! シミュレーションで使用する定数定義
+
+// チャンク設定
+/// 1チャンクの1辺のセル数
+pub const CHUNK_SIZE: i32 = 64;
+
+// 並列計算
+/// 世代計算を複数スレッドに分散する最小チャンク数（未満なら逐次計算）
+pub const PARALLEL_MIN_CHUNKS: usize = 8;
+
+// HashLife
+/// HashLifeエンジンのノードキャッシュ上限（超過時は次回計算前に破棄）
+pub const HASHLIFE_MAX_NODES: usize = 1 << 22;
+
+// トポロジー
+/// 有限トポロジーの幅・高さの上限（セル数）
+pub const TOPOLOGY_MAX_SIZE: u32 = 4096;
+
+// セルの年齢
+/// 死亡したセルの軌跡を残す世代数
+pub const AGE_TRAIL_LENGTH: u32 = 8;
+
+// ヒートマップ
+/// 状態の反転を数える直近の世代数の初期値
+pub const HEATMAP_DEFAULT_WINDOW: usize = 64;
+/// 1世代古くなるごとに反転の重みに掛ける係数の初期値
+pub const HEATMAP_DEFAULT_DECAY: f32 = 0.95;
+/// これを下回った重み付きの反転回数は0とみなして追跡をやめる
+pub const HEATMAP_MIN_HEAT: f32 = 1e-3;
+
+// Generations
+/// 衰退中のセルの最初の状態（0が死亡、1が生存）
+pub const FIRST_DECAYING_STATE: u8 = 2;
+
+// 多状態オートマトン
+/// 利用者定義の多状態オートマトンが持てる状態数の上限（空を含む）
+pub const MULTI_STATE_MAX_STATES: u8 = 16;
+/// Wireworld の状態ごとの描画色（空・電子の頭・電子の尾・導線の順のRGB値）
+pub const WIREWORLD_PALETTE: [(u8, u8, u8); 4] =
+    [(15, 15, 20), (70, 150, 255), (235, 70, 50), (230, 180, 40)];
+
+// 近傍
+/// Larger than Life ルールの近傍の範囲（半径）の上限
+pub const NEIGHBORHOOD_MAX_RANGE: u8 = 10;
+
+// 解析
+/// 周期の検出のために保持する直近の世代数（これより長い周期は検出しない）
+pub const STABILITY_WINDOW: usize = 64;
+
+/// ムーア近傍の8方向の相対座標
+///
+/// あるセルの周囲8マスを走査するために使用する。
+pub const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
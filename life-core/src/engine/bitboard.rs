@@ -0,0 +1,77 @@
+//! ビットボードによる世代計算エンジン
+
+#[cfg(not(feature = "bevy"))]
+use std::num::NonZeroUsize;
+#[cfg(not(feature = "bevy"))]
+use std::sync::OnceLock;
+#[cfg(not(feature = "bevy"))]
+use std::thread;
+
+#[cfg(feature = "bevy")]
+use bevy_tasks::{ComputeTaskPool, TaskPool};
+
+use crate::rules::Rule;
+
+use super::{EngineKind, LifeEngine};
+use crate::bitboard::Bitboard;
+
+/// チャンク単位のビットボードを1世代ずつ進めるエンジン
+///
+/// 1チャンクの各行を `u64` 1語として、64セル分の近傍数をビット演算でまとめて数える。
+/// 各世代のチャンク計算は、`bevy` フィーチャーが有効なら `ComputeTaskPool` 上で、
+/// 無効なら利用可能なCPU数のスレッドで並列に行う。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BitboardEngine;
+
+impl LifeEngine for BitboardEngine {
+    fn kind(&self) -> EngineKind {
+        EngineKind::Bitboard
+    }
+
+    fn advance(&mut self, cells: &Bitboard, rule: &Rule, generations: u64) -> Bitboard {
+        let mut current = cells.clone();
+        #[cfg(feature = "bevy")]
+        {
+            let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+            for _ in 0..generations {
+                current = current.step_on_pool(rule, pool);
+            }
+        }
+        #[cfg(not(feature = "bevy"))]
+        {
+            let threads = available_threads();
+            for _ in 0..generations {
+                current = current.step_parallel(rule, threads);
+            }
+        }
+        current
+    }
+}
+
+/// 利用可能なCPU数を返す（初回に問い合わせた値を使い回す）
+#[cfg(not(feature = "bevy"))]
+fn available_threads() -> usize {
+    static THREADS: OnceLock<usize> = OnceLock::new();
+    *THREADS.get_or_init(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SparseEngine;
+    use crate::patterns::LifePattern;
+
+    #[test]
+    fn matches_sparse_engine_on_gosper_gun() {
+        let board: Bitboard = LifePattern::GosperGliderGun
+            .cells()
+            .iter()
+            .copied()
+            .collect();
+        let rule = Rule::conway();
+        assert_eq!(
+            BitboardEngine.advance(&board, &rule, 200),
+            SparseEngine.advance(&board, &rule, 200)
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! HashLife による世代計算エンジン
+//!
+//! ワールドを四分木で表現し、同一の部分木をハッシュコンシングで共有する。
+//! 各ノードの「中心部を 2^j 世代進めた結果」をメモ化することで、
+//! 周期的・反復的なパターンでは指数的な世代数を一度に計算できる。
+
+use crate::consts::{HASHLIFE_MAX_NODES, SQUARE_COORDINATES};
+use crate::rules::Rule;
+use rustc_hash::FxHashMap;
+
+use super::{EngineKind, LifeEngine};
+use crate::bitboard::Bitboard;
+use crate::simulation;
+
+/// ノードの識別子（`nodes` のインデックス）
+type NodeId = u32;
+
+/// 死亡セルを表す葉ノード
+const DEAD: NodeId = 0;
+/// 生存セルを表す葉ノード
+const ALIVE: NodeId = 1;
+
+/// 四分木のノード
+///
+/// レベル L のノードは 2^L × 2^L セルの正方形領域を表す。レベル0は1セル（葉）。
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    level: u8,
+    population: u64,
+}
+
+/// メモ化四分木で世代を進めるエンジン
+///
+/// 任意の世代数を2進分解し、2^k 世代ずつまとめて進める。
+/// ノード数が `HASHLIFE_MAX_NODES` を超えた場合は次回の計算前にキャッシュを破棄する。
+#[derive(Debug)]
+pub struct HashLifeEngine {
+    /// 全ノード（ハッシュコンシング済み）
+    nodes: Vec<Node>,
+    /// 子ノードの組からノードを引く表
+    lookup: FxHashMap<[NodeId; 4], NodeId>,
+    /// `(ノード, j)` → 中心部を 2^j 世代進めた結果のメモ
+    results: FxHashMap<(NodeId, u8), NodeId>,
+    /// レベルごとの空ノード
+    empty: Vec<NodeId>,
+    /// メモの計算に使用したルール
+    rule: Rule,
+}
+
+impl Default for HashLifeEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashLifeEngine {
+    /// 空のキャッシュでエンジンを生成する
+    pub fn new() -> Self {
+        let leaf = |population| Node {
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            level: 0,
+            population,
+        };
+        Self {
+            nodes: vec![leaf(0), leaf(1)],
+            lookup: FxHashMap::default(),
+            results: FxHashMap::default(),
+            empty: vec![DEAD],
+            rule: Rule::default(),
+        }
+    }
+
+    /// 4つの子ノードから親ノードを取得（なければ生成）する
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let key = [nw, ne, sw, se];
+        if let Some(&id) = self.lookup.get(&key) {
+            return id;
+        }
+        let population = key.iter().map(|&c| self.nodes[c as usize].population).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            nw,
+            ne,
+            sw,
+            se,
+            level: self.nodes[nw as usize].level + 1,
+            population,
+        });
+        self.lookup.insert(key, id);
+        id
+    }
+
+    /// 指定レベルの空ノードを返す
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = self.empty[self.empty.len() - 1];
+            let parent = self.join(e, e, e, e);
+            self.empty.push(parent);
+        }
+        self.empty[level as usize]
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    /// ノードの中心部（1つ下のレベル）を返す
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let n = self.node(id);
+        let (nw, ne, sw, se) = (
+            self.node(n.nw),
+            self.node(n.ne),
+            self.node(n.sw),
+            self.node(n.se),
+        );
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    /// 中心を保ったまま周囲を空セルで囲み、1つ上のレベルに拡張する
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let n = self.node(id);
+        let e = self.empty(n.level - 1);
+        let nw = self.join(e, e, e, n.nw);
+        let ne = self.join(e, e, n.ne, e);
+        let sw = self.join(e, n.sw, e, e);
+        let se = self.join(n.se, e, e, e);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// レベル2ノード（4×4）の中心2×2を1世代進める
+    fn step_base(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        let n = self.node(id);
+        for (quadrant, (ox, oy)) in [
+            (n.nw, (0, 0)),
+            (n.ne, (2, 0)),
+            (n.sw, (0, 2)),
+            (n.se, (2, 2)),
+        ] {
+            let q = self.node(quadrant);
+            for (leaf, (dx, dy)) in [
+                (q.nw, (0, 0)),
+                (q.ne, (1, 0)),
+                (q.sw, (0, 1)),
+                (q.se, (1, 1)),
+            ] {
+                grid[oy + dy][ox + dx] = leaf == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let count = SQUARE_COORDINATES
+                .iter()
+                .filter(|&&(dy, dx)| grid[(y as i8 + dy) as usize][(x as i8 + dx) as usize])
+                .count();
+            if simulation::next_cell_state(&self.rule, grid[y][x], count) {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// レベル L ノードの中心部（レベル L-1）を 2^j 世代進めた結果を返す
+    ///
+    /// `j <= L - 2` であること。
+    fn step(&mut self, id: NodeId, j: u8) -> NodeId {
+        let n = self.node(id);
+        if n.population == 0 {
+            return self.empty(n.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if n.level == 2 {
+            self.step_base(id)
+        } else {
+            let (nw, ne, sw, se) = (
+                self.node(n.nw),
+                self.node(n.ne),
+                self.node(n.sw),
+                self.node(n.se),
+            );
+            // 3×3 に重なり合うレベル L-1 の部分ノード
+            let subs = [
+                n.nw,
+                self.join(nw.ne, ne.nw, nw.se, ne.sw),
+                n.ne,
+                self.join(nw.sw, nw.se, sw.nw, sw.ne),
+                self.join(nw.se, ne.sw, sw.ne, se.nw),
+                self.join(ne.sw, ne.se, se.nw, se.ne),
+                n.sw,
+                self.join(sw.ne, se.nw, sw.se, se.sw),
+                n.se,
+            ];
+
+            // 最大速度（j = L-2）なら2回とも進め、そうでなければ1回目は中心を取るだけ
+            let full_speed = j == n.level - 2;
+            let mut first = [DEAD; 9];
+            for (out, &sub) in first.iter_mut().zip(&subs) {
+                *out = if full_speed {
+                    self.step(sub, j - 1)
+                } else {
+                    self.center(sub)
+                };
+            }
+
+            let second_j = if full_speed { j - 1 } else { j };
+            let quads = [
+                self.join(first[0], first[1], first[3], first[4]),
+                self.join(first[1], first[2], first[4], first[5]),
+                self.join(first[3], first[4], first[6], first[7]),
+                self.join(first[4], first[5], first[7], first[8]),
+            ];
+            let mut second = [DEAD; 4];
+            for (out, &quad) in second.iter_mut().zip(&quads) {
+                *out = self.step(quad, second_j);
+            }
+            self.join(second[0], second[1], second[2], second[3])
+        };
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// 原点を中心とするルートノードを 2^k 世代進める
+    fn advance_pow2(&mut self, mut root: NodeId, k: u8) -> NodeId {
+        // パターンがルートの中心部に収まり、かつ十分なレベルになるまで拡張し、
+        // さらに1段拡張して 2^k 世代分の移動余白を確保する
+        loop {
+            let n = self.node(root);
+            if n.level >= k + 2 {
+                let center = self.center(root);
+                if self.node(center).population == n.population {
+                    break;
+                }
+            }
+            root = self.expand(root);
+        }
+        root = self.expand(root);
+        self.step(root, k)
+    }
+
+    /// 生存セル集合から原点中心のルートノードを構築する
+    fn build_root(&mut self, cells: &Bitboard) -> NodeId {
+        let cells: Vec<(i128, i128)> = cells.iter().map(|(x, y)| (x as i128, y as i128)).collect();
+        let mut level = 3u8;
+        while cells.iter().any(|&(x, y)| {
+            let half = 1i128 << (level - 1);
+            x < -half || x >= half || y < -half || y >= half
+        }) {
+            level += 1;
+        }
+        let half = 1i128 << (level - 1);
+        self.build(&cells, -half, -half, level)
+    }
+
+    /// 左上 `(x0, y0)` のレベル `level` 領域に含まれるセルからノードを構築する
+    fn build(&mut self, cells: &[(i128, i128)], x0: i128, y0: i128, level: u8) -> NodeId {
+        if cells.is_empty() {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return ALIVE;
+        }
+        let half = 1i128 << (level - 1);
+        let mut quadrants: [Vec<(i128, i128)>; 4] = Default::default();
+        for &(x, y) in cells {
+            let index = usize::from(x >= x0 + half) + 2 * usize::from(y >= y0 + half);
+            quadrants[index].push((x, y));
+        }
+        let nw = self.build(&quadrants[0], x0, y0, level - 1);
+        let ne = self.build(&quadrants[1], x0 + half, y0, level - 1);
+        let sw = self.build(&quadrants[2], x0, y0 + half, level - 1);
+        let se = self.build(&quadrants[3], x0 + half, y0 + half, level - 1);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// ノードの生存セルを `out` に書き出す
+    ///
+    /// `i32` の範囲外に出たセルは表現できないため捨てる。
+    fn collect(&self, id: NodeId, x0: i128, y0: i128, out: &mut Bitboard) {
+        let n = self.node(id);
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            if let (Ok(x), Ok(y)) = (i32::try_from(x0), i32::try_from(y0)) {
+                out.set(x, y, true);
+            }
+            return;
+        }
+        let half = 1i128 << (n.level - 1);
+        self.collect(n.nw, x0, y0, out);
+        self.collect(n.ne, x0 + half, y0, out);
+        self.collect(n.sw, x0, y0 + half, out);
+        self.collect(n.se, x0 + half, y0 + half, out);
+    }
+
+    /// ルール変更時・ノード数超過時にキャッシュを破棄する
+    fn prepare(&mut self, rule: &Rule) {
+        if self.nodes.len() > HASHLIFE_MAX_NODES {
+            *self = Self::new();
+        }
+        if self.rule != *rule {
+            self.results.clear();
+            self.rule = *rule;
+        }
+    }
+}
+
+impl LifeEngine for HashLifeEngine {
+    fn kind(&self) -> EngineKind {
+        EngineKind::HashLife
+    }
+
+    fn advance(&mut self, cells: &Bitboard, rule: &Rule, generations: u64) -> Bitboard {
+        // B0 ルールは扱わないため、空のワールドは常に空のまま
+        if generations == 0 || cells.is_empty() {
+            return cells.clone();
+        }
+        self.prepare(rule);
+
+        let mut root = self.build_root(cells);
+        for k in 0..u64::BITS as u8 {
+            if generations >> k == 0 {
+                break;
+            }
+            if generations >> k & 1 == 1 {
+                root = self.advance_pow2(root, k);
+            }
+        }
+
+        let mut out = Bitboard::new();
+        let half = 1i128 << (self.node(root).level - 1);
+        self.collect(root, -half, -half, &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SparseEngine;
+    use crate::patterns::LifePattern;
+    use crate::rules::RulePreset;
+
+    fn set(cells: &[(i32, i32)]) -> Bitboard {
+        cells.iter().copied().collect()
+    }
+
+    fn shifted(cells: &[(i32, i32)], dx: i32, dy: i32) -> Bitboard {
+        cells.iter().map(|&(x, y)| (x + dx, y + dy)).collect()
+    }
+
+    #[test]
+    fn matches_sparse_for_all_patterns() {
+        let rule = Rule::conway();
+        for pattern in LifePattern::all() {
+            let cells = set(pattern.cells());
+            for generations in [1, 2, 3, 7, 64, 100] {
+                let expected = SparseEngine.advance(&cells, &rule, generations);
+                let actual = HashLifeEngine::new().advance(&cells, &rule, generations);
+                assert_eq!(actual, expected, "{pattern:?} at {generations}");
+            }
+        }
+    }
+
+    #[test]
+    fn matches_sparse_for_other_rules() {
+        let cells = shifted(LifePattern::Acorn.cells(), -40, 25);
+        for preset in [
+            RulePreset::HighLife,
+            RulePreset::DayAndNight,
+            RulePreset::Seeds,
+        ] {
+            let rule = preset.rule();
+            let expected = SparseEngine.advance(&cells, &rule, 50);
+            let actual = HashLifeEngine::new().advance(&cells, &rule, 50);
+            assert_eq!(actual, expected, "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn acorn_matches_sparse_after_1000_generations() {
+        let cells = set(LifePattern::Acorn.cells());
+        let rule = Rule::conway();
+        let expected = SparseEngine.advance(&cells, &rule, 1000);
+        assert_eq!(HashLifeEngine::new().advance(&cells, &rule, 1000), expected);
+    }
+
+    #[test]
+    fn repeated_small_steps_equal_one_jump() {
+        let cells = set(LifePattern::RPentomino.cells());
+        let rule = Rule::conway();
+        let mut engine = HashLifeEngine::new();
+        let mut stepped = cells.clone();
+        for _ in 0..130 {
+            stepped = engine.advance(&stepped, &rule, 1);
+        }
+        assert_eq!(engine.advance(&cells, &rule, 130), stepped);
+    }
+
+    #[test]
+    fn glider_travels_far_with_negative_coordinates() {
+        // グライダーは4世代で (+1, +1) 移動する
+        let start: Vec<_> = LifePattern::Glider
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x - 1_000, y - 1_000))
+            .collect();
+        let generations = 1u64 << 20;
+        let offset = (generations / 4) as i32;
+        let result = HashLifeEngine::new().advance(&set(&start), &Rule::conway(), generations);
+        assert_eq!(result, shifted(&start, offset, offset));
+    }
+
+    #[test]
+    fn rule_change_invalidates_memo() {
+        let cells = set(LifePattern::RPentomino.cells());
+        let mut engine = HashLifeEngine::new();
+        engine.advance(&cells, &Rule::conway(), 32);
+        let highlife = RulePreset::HighLife.rule();
+        let expected = SparseEngine.advance(&cells, &highlife, 32);
+        assert_eq!(engine.advance(&cells, &highlife, 32), expected);
+    }
+
+    #[test]
+    fn empty_world_stays_empty() {
+        let empty = Bitboard::new();
+        assert!(
+            HashLifeEngine::new()
+                .advance(&empty, &Rule::conway(), 1 << 40)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! 疎集合による世代計算エンジン
+
+use crate::consts::SQUARE_COORDINATES;
+use crate::rules::Rule;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{EngineKind, LifeEngine};
+use crate::bitboard::Bitboard;
+use crate::simulation;
+
+/// 生存セルとその近傍のみを毎世代数え上げるエンジン
+///
+/// 1世代ごとに近傍カウントの `FxHashMap` を構築するため、
+/// 計算量は世代数 × 生存セル数に比例する。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SparseEngine;
+
+impl SparseEngine {
+    /// 1世代進めた生存セル集合を返す
+    pub fn step(cells: &FxHashSet<(i32, i32)>, rule: &Rule) -> FxHashSet<(i32, i32)> {
+        // 候補セル = 生存セル + その8近傍のカウントを構築
+        let mut candidates: FxHashMap<(i32, i32), u8> = FxHashMap::default();
+
+        for &(x, y) in cells {
+            // 自分自身を候補に
+            candidates.entry((x, y)).or_insert(0);
+            // 8近傍のカウントを+1
+            for &(dy, dx) in &SQUARE_COORDINATES {
+                let nx = x + dx as i32;
+                let ny = y + dy as i32;
+                *candidates.entry((nx, ny)).or_insert(0) += 1;
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|&(pos, count)| {
+                simulation::next_cell_state(rule, cells.contains(&pos), count as usize)
+            })
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+}
+
+impl LifeEngine for SparseEngine {
+    fn kind(&self) -> EngineKind {
+        EngineKind::Sparse
+    }
+
+    fn advance(&mut self, cells: &Bitboard, rule: &Rule, generations: u64) -> Bitboard {
+        let mut current: FxHashSet<(i32, i32)> = cells.iter().collect();
+        for _ in 0..generations {
+            current = Self::step(&current, rule);
+        }
+        current.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(cells: &[(i32, i32)]) -> FxHashSet<(i32, i32)> {
+        cells.iter().copied().collect()
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let horizontal = set(&[(-1, 0), (0, 0), (1, 0)]);
+        let vertical = set(&[(0, -1), (0, 0), (0, 1)]);
+        let rule = Rule::conway();
+        assert_eq!(SparseEngine::step(&horizontal, &rule), vertical);
+        let board: Bitboard = horizontal.iter().copied().collect();
+        assert_eq!(SparseEngine.advance(&board, &rule, 2), board);
+    }
+
+    #[test]
+    fn advance_zero_generations_is_identity() {
+        let board: Bitboard = [(3, 4), (-7, 2)].into_iter().collect();
+        assert_eq!(SparseEngine.advance(&board, &Rule::conway(), 0), board);
+    }
+}
@@ -0,0 +1,250 @@
+//! パターンファイル形式の読み書き
+//!
+//! 外部のパターンアーカイブで配布されている形式を解析し、
+//! `World::place_pattern` がそのまま受け付けられる `(i32, i32)` のセル列に変換する。
+
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+use std::fmt;
+
+use crate::rules::{Rule, RuleParseError};
+
+/// パターンファイルから読み込んだパターン
+///
+/// セル座標は `(x, y)`（Y+が下方向）で、ファイルに位置情報がなければ
+/// バウンディングボックスの左上が `(0, 0)` になる。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatternFile {
+    /// パターン名（RLEの `#N` 行）
+    pub name: Option<String>,
+    /// 作者（RLEの `#O` 行）
+    pub author: Option<String>,
+    /// コメント行（RLEの `#C` 行）
+    pub comments: Vec<String>,
+    /// ファイルに指定されたルール
+    pub rule: Option<Rule>,
+    /// 生存セルの座標
+    pub cells: Vec<(i32, i32)>,
+}
+
+impl PatternFile {
+    /// セル座標のみからパターンを生成する
+    pub fn from_cells(cells: Vec<(i32, i32)>) -> Self {
+        Self {
+            cells,
+            ..Self::default()
+        }
+    }
+}
+
+/// パターンファイルの形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternFormat {
+    /// RLE（`.rle`）
+    Rle,
+    /// Plaintext（`.cells`）
+    Plaintext,
+    /// Life 1.05（`.lif`）
+    Life105,
+    /// Life 1.06（`.lif`）
+    Life106,
+}
+
+impl PatternFormat {
+    /// ファイルの拡張子から形式を推定する（`.lif` はバージョンを区別できないため `None`）
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(Self::Rle),
+            "cells" => Some(Self::Plaintext),
+            _ => None,
+        }
+    }
+
+    /// ファイルの内容から形式を推定する
+    ///
+    /// Life 1.05 / 1.06 はヘッダー行、Plaintext は `!` コメントまたは `.` / `O` のみの行で判定し、
+    /// いずれでもなければRLEとみなす。
+    pub fn detect(input: &str) -> Self {
+        let first = input.lines().map(str::trim).find(|line| !line.is_empty());
+        match first {
+            Some(line) if line.starts_with(life105::HEADER) => Self::Life105,
+            Some(line) if line.starts_with(life106::HEADER) => Self::Life106,
+            Some(line) if line.starts_with('!') || line.chars().all(|c| matches!(c, '.' | 'O')) => {
+                Self::Plaintext
+            }
+            _ => Self::Rle,
+        }
+    }
+
+    /// この形式として文字列を解析する
+    pub fn parse(self, input: &str) -> Result<PatternFile, FormatError> {
+        match self {
+            Self::Rle => rle::parse(input),
+            Self::Plaintext => plaintext::parse(input),
+            Self::Life105 => life105::parse(input),
+            Self::Life106 => life106::parse(input),
+        }
+    }
+
+    /// この形式の文字列に変換する
+    pub fn write(self, pattern: &PatternFile) -> String {
+        match self {
+            Self::Rle => rle::write(pattern),
+            Self::Plaintext => plaintext::write(pattern),
+            Self::Life105 => life105::write(pattern),
+            Self::Life106 => life106::write(pattern),
+        }
+    }
+}
+
+/// パターンファイルの解析エラー
+///
+/// 行・列は1始まりで、エラーの原因となった文字の位置を指す。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatError {
+    /// エラー発生行（1始まり）
+    pub line: usize,
+    /// エラー発生列（1始まり）
+    pub column: usize,
+    /// エラーの種類
+    pub kind: FormatErrorKind,
+}
+
+/// パターンファイルの解析エラーの種類
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatErrorKind {
+    /// 想定外の文字
+    UnexpectedChar(char),
+    /// ヘッダー行（`x = , y = , rule =`）の書式誤り
+    InvalidHeader,
+    /// ルール文字列の解析失敗
+    InvalidRule(RuleParseError),
+    /// 数値の書式誤り・範囲外
+    InvalidNumber,
+}
+
+impl FormatError {
+    pub(crate) fn new(line: usize, column: usize, kind: FormatErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            FormatErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{c}'"),
+            FormatErrorKind::InvalidHeader => write!(f, "invalid header line"),
+            FormatErrorKind::InvalidRule(e) => write!(f, "invalid rule: {e}"),
+            FormatErrorKind::InvalidNumber => write!(f, "invalid number"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// セル集合のバウンディングボックス `(min_x, min_y, max_x, max_y)` を返す
+///
+/// セルが空の場合は `None`。
+pub fn bounding_box(cells: &[(i32, i32)]) -> Option<(i32, i32, i32, i32)> {
+    let (&(x0, y0), rest) = cells.split_first()?;
+    Some(
+        rest.iter()
+            .fold((x0, y0, x0, y0), |(min_x, min_y, max_x, max_y), &(x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            }),
+    )
+}
+
+/// 行を空白区切りのトークンに分割し、`(列（1始まり）, トークン)` を返す
+pub(crate) fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(char::is_whitespace)
+        .scan(1, |start, token| {
+            let item = (*start, token);
+            *start += token.chars().count() + 1;
+            Some(item)
+        })
+        .filter(|(_, token)| !token.is_empty())
+}
+
+/// 座標の数値を解析する（失敗時はトークンの列を指すエラー）
+pub(crate) fn parse_number(token: &str, line: usize, column: usize) -> Result<i32, FormatError> {
+    token
+        .parse()
+        .map_err(|_| FormatError::new(line, column, FormatErrorKind::InvalidNumber))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_format_from_content() {
+        assert_eq!(
+            PatternFormat::detect("#Life 1.05\n#P 0 0\n.*\n"),
+            PatternFormat::Life105
+        );
+        assert_eq!(
+            PatternFormat::detect("\n#Life 1.06\n0 0\n"),
+            PatternFormat::Life106
+        );
+        assert_eq!(
+            PatternFormat::detect("!Name: Glider\n.O\n"),
+            PatternFormat::Plaintext
+        );
+        assert_eq!(
+            PatternFormat::detect(".O\n..O\nOOO\n"),
+            PatternFormat::Plaintext
+        );
+        assert_eq!(
+            PatternFormat::detect("#N Glider\nx = 3, y = 3\nbob$2bo$3o!"),
+            PatternFormat::Rle
+        );
+        assert_eq!(PatternFormat::detect("bo$2bo$3o!"), PatternFormat::Rle);
+    }
+
+    #[test]
+    fn detected_format_parses_its_own_output() {
+        let pattern = PatternFile::from_cells(vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        for format in [
+            PatternFormat::Rle,
+            PatternFormat::Plaintext,
+            PatternFormat::Life105,
+            PatternFormat::Life106,
+        ] {
+            let text = format.write(&pattern);
+            assert_eq!(PatternFormat::detect(&text), format, "{text}");
+            let mut cells = format.parse(&text).unwrap().cells;
+            cells.sort_unstable_by_key(|&(x, y)| (y, x));
+            assert_eq!(cells, pattern.cells, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn bounding_box_of_empty_is_none() {
+        assert_eq!(bounding_box(&[]), None);
+    }
+
+    #[test]
+    fn bounding_box_with_negative_coordinates() {
+        assert_eq!(
+            bounding_box(&[(0, 0), (-3, 2), (5, -1)]),
+            Some((-3, -1, 5, 2))
+        );
+    }
+
+    #[test]
+    fn tokens_report_columns() {
+        let fields: Vec<_> = tokens("  12\t-3  x").collect();
+        assert_eq!(fields, vec![(3, "12"), (6, "-3"), (10, "x")]);
+    }
+
+    #[test]
+    fn error_display_reports_position() {
+        let e = FormatError::new(3, 7, FormatErrorKind::UnexpectedChar('z'));
+        assert_eq!(e.to_string(), "line 3, column 7: unexpected character 'z'");
+    }
+}
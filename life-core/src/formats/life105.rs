@@ -0,0 +1,219 @@
+//! Life 1.05 形式の読み書き
+//!
+//! `#P x y` で指定した位置から `.`（死亡）と `*`（生存）のセル行を並べる形式。
+//!
+//! ```text
+//! #Life 1.05
+//! #D Glider
+//! #N
+//! #P -1 -1
+//! .*.
+//! ..*
+//! ***
+//! ```
+//!
+//! - `#D` 説明文、`#N` 標準ルール（`23/3`）、`#R s/b` ルール指定
+//! - `#P x y` 以降のセル行は `(x, y)` を左上とするブロックとして配置される
+
+use super::{FormatError, FormatErrorKind, PatternFile, bounding_box, parse_number, tokens};
+use crate::rules::Rule;
+
+/// 形式を識別するヘッダー行
+pub const HEADER: &str = "#Life 1.05";
+
+/// Life 1.05 文字列を解析してパターンを返す
+pub fn parse(input: &str) -> Result<PatternFile, FormatError> {
+    let mut pattern = PatternFile::default();
+    let (mut origin_x, mut origin_y) = (0, 0);
+    let mut row = 0;
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line_no = line_index + 1;
+        let trimmed = line.trim_end();
+
+        if let Some(directive) = trimmed.strip_prefix('#') {
+            let mut chars = directive.chars();
+            let tag = chars.next().unwrap_or(' ');
+            let content = chars.as_str();
+            // 内容の先頭列（'#' とタグの2文字分 + 1始まり）
+            let content_column = 3;
+            match tag {
+                'D' => pattern.comments.push(content.trim().to_string()),
+                'N' => pattern.rule = Some(Rule::conway()),
+                'R' => {
+                    let rule = Rule::parse(content).map_err(|e| {
+                        FormatError::new(line_no, content_column, FormatErrorKind::InvalidRule(e))
+                    })?;
+                    pattern.rule = Some(rule);
+                }
+                'P' => {
+                    let mut fields = tokens(content);
+                    let (x_column, x) = fields.next().ok_or(FormatError::new(
+                        line_no,
+                        content_column,
+                        FormatErrorKind::InvalidNumber,
+                    ))?;
+                    let (y_column, y) = fields.next().ok_or(FormatError::new(
+                        line_no,
+                        trimmed.chars().count() + 1,
+                        FormatErrorKind::InvalidNumber,
+                    ))?;
+                    origin_x = parse_number(x, line_no, x_column + content_column - 1)?;
+                    origin_y = parse_number(y, line_no, y_column + content_column - 1)?;
+                    row = 0;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        for (col_index, c) in trimmed.chars().enumerate() {
+            match c {
+                '.' => {}
+                '*' => pattern
+                    .cells
+                    .push((origin_x + col_index as i32, origin_y + row)),
+                _ => {
+                    return Err(FormatError::new(
+                        line_no,
+                        col_index + 1,
+                        FormatErrorKind::UnexpectedChar(c),
+                    ));
+                }
+            }
+        }
+        row += 1;
+    }
+    Ok(pattern)
+}
+
+/// パターンを Life 1.05 文字列に変換する
+///
+/// バウンディングボックス全体を1つの `#P` ブロックとして出力する。
+/// ルールがコンウェイ（または未指定）の場合は `#N`、それ以外は `#R` を出力する。
+pub fn write(pattern: &PatternFile) -> String {
+    let mut out = format!("{HEADER}\n");
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("#D {name}\n"));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("#D {comment}\n"));
+    }
+    match pattern.rule {
+        Some(rule) if rule != Rule::conway() => {
+            out.push_str(&format!("#R {}\n", rule.to_sb_notation()));
+        }
+        _ => out.push_str("#N\n"),
+    }
+
+    let Some((min_x, min_y, max_x, max_y)) = bounding_box(&pattern.cells) else {
+        return out;
+    };
+    out.push_str(&format!("#P {min_x} {min_y}\n"));
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut grid = vec![vec!['.'; width]; height];
+    for &(x, y) in &pattern.cells {
+        grid[(y - min_y) as usize][(x - min_x) as usize] = '*';
+    }
+    for line in grid {
+        let line: String = line.into_iter().collect();
+        out.push_str(line.trim_end_matches('.'));
+        if line.chars().all(|c| c == '.') {
+            out.push('.');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::CHUNK_SIZE;
+    use crate::patterns::LifePattern;
+
+    const GLIDER_105: &str = "#Life 1.05\n#D Glider\n#N\n#P -1 -1\n.*\n..*\n***\n";
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn parse_glider_with_negative_position() {
+        let pattern = parse(GLIDER_105).unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            sorted(LifePattern::Glider.cells().to_vec())
+        );
+        assert_eq!(pattern.rule, Some(Rule::conway()));
+        assert_eq!(pattern.comments, vec!["Glider".to_string()]);
+    }
+
+    #[test]
+    fn parse_multiple_blocks() {
+        let pattern = parse("#Life 1.05\n#P 0 0\n**\n#P -10 5\n*\n.*\n").unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            vec![(-10, 5), (-9, 6), (0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn parse_rule_in_sb_notation() {
+        let pattern = parse("#Life 1.05\n#R 23/36\n*\n").unwrap();
+        assert_eq!(pattern.rule, Rule::parse("B36/S23").ok());
+    }
+
+    #[test]
+    fn parse_reports_invalid_rule_position() {
+        let err = parse("#Life 1.05\n#R 29/3\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 3);
+        assert!(matches!(err.kind, FormatErrorKind::InvalidRule(_)));
+    }
+
+    #[test]
+    fn parse_reports_invalid_block_position() {
+        let err = parse("#Life 1.05\n#P 4 q\n").unwrap_err();
+        assert_eq!(err, FormatError::new(2, 6, FormatErrorKind::InvalidNumber));
+    }
+
+    #[test]
+    fn parse_reports_unexpected_char_position() {
+        let err = parse("#Life 1.05\n#P 0 0\n.*.\n*O\n").unwrap_err();
+        assert_eq!(
+            err,
+            FormatError::new(4, 2, FormatErrorKind::UnexpectedChar('O'))
+        );
+    }
+
+    #[test]
+    fn write_glider() {
+        let mut pattern = parse(GLIDER_105).unwrap();
+        pattern.rule = None;
+        assert_eq!(write(&pattern), GLIDER_105);
+    }
+
+    #[test]
+    fn write_non_conway_rule() {
+        let pattern = PatternFile {
+            rule: Rule::parse("B36/S23").ok(),
+            ..PatternFile::from_cells(vec![(0, 0)])
+        };
+        assert!(write(&pattern).contains("#R 23/36\n"));
+    }
+
+    #[test]
+    fn roundtrip_across_chunk_boundaries() {
+        // 負のチャンクと正のチャンクの境界（x = -1 | 0）をまたぐパターン
+        let cells: Vec<_> = LifePattern::GosperGliderGun
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x - 18, y - CHUNK_SIZE - 3))
+            .collect();
+        let parsed = parse(&write(&PatternFile::from_cells(cells.clone()))).unwrap();
+        assert_eq!(sorted(parsed.cells), sorted(cells));
+    }
+}
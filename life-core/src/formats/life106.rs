@@ -0,0 +1,133 @@
+//! Life 1.06 形式の読み書き
+//!
+//! 生存セルの座標を1行に1つずつ列挙する形式。負の座標をそのまま表現できる。
+//!
+//! ```text
+//! #Life 1.06
+//! 0 -1
+//! 1 0
+//! -1 1
+//! 0 1
+//! 1 1
+//! ```
+
+use super::{FormatError, FormatErrorKind, PatternFile, parse_number, tokens};
+
+/// 形式を識別するヘッダー行
+pub const HEADER: &str = "#Life 1.06";
+
+/// Life 1.06 文字列を解析してパターンを返す
+///
+/// `#` で始まる行（ヘッダーを含む）と空行は読み飛ばす。
+pub fn parse(input: &str) -> Result<PatternFile, FormatError> {
+    let mut pattern = PatternFile::default();
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line_no = line_index + 1;
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut fields = tokens(line);
+        let Some((x_column, x)) = fields.next() else {
+            continue;
+        };
+        let x = parse_number(x, line_no, x_column)?;
+        let (y_column, y) = fields.next().ok_or(FormatError::new(
+            line_no,
+            line.chars().count() + 1,
+            FormatErrorKind::InvalidNumber,
+        ))?;
+        let y = parse_number(y, line_no, y_column)?;
+        if let Some((column, extra)) = fields.next() {
+            let c = extra.chars().next().unwrap_or(' ');
+            return Err(FormatError::new(
+                line_no,
+                column,
+                FormatErrorKind::UnexpectedChar(c),
+            ));
+        }
+        pattern.cells.push((x, y));
+    }
+    Ok(pattern)
+}
+
+/// パターンを Life 1.06 文字列に変換する
+///
+/// 座標は行優先（y, x の昇順）で出力する。名前・コメントは形式上保存できない。
+pub fn write(pattern: &PatternFile) -> String {
+    let mut cells = pattern.cells.clone();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    cells.dedup();
+
+    let mut out = format!("{HEADER}\n");
+    for (x, y) in cells {
+        out.push_str(&format!("{x} {y}\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::CHUNK_SIZE;
+    use crate::patterns::LifePattern;
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn parse_glider_with_negative_coordinates() {
+        let pattern = parse("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            sorted(LifePattern::Glider.cells().to_vec())
+        );
+    }
+
+    #[test]
+    fn parse_skips_blank_lines_and_extra_spaces() {
+        let pattern = parse("#Life 1.06\n\n  3\t-4  \n").unwrap();
+        assert_eq!(pattern.cells, vec![(3, -4)]);
+    }
+
+    #[test]
+    fn parse_reports_invalid_number_position() {
+        let err = parse("#Life 1.06\n0 0\n1 y2\n").unwrap_err();
+        assert_eq!(err, FormatError::new(3, 3, FormatErrorKind::InvalidNumber));
+    }
+
+    #[test]
+    fn parse_reports_missing_coordinate() {
+        let err = parse("#Life 1.06\n12\n").unwrap_err();
+        assert_eq!(err, FormatError::new(2, 3, FormatErrorKind::InvalidNumber));
+    }
+
+    #[test]
+    fn parse_reports_extra_field() {
+        let err = parse("1 2 3\n").unwrap_err();
+        assert_eq!(
+            err,
+            FormatError::new(1, 5, FormatErrorKind::UnexpectedChar('3'))
+        );
+    }
+
+    #[test]
+    fn write_is_sorted_row_major() {
+        let text = write(&PatternFile::from_cells(vec![(5, 1), (-2, 1), (0, -7)]));
+        assert_eq!(text, "#Life 1.06\n0 -7\n-2 1\n5 1\n");
+    }
+
+    #[test]
+    fn roundtrip_across_chunk_boundaries() {
+        // チャンク境界 (-1|0, CHUNK_SIZE-1|CHUNK_SIZE) をまたぐパターン
+        let cells: Vec<_> = LifePattern::Pulsar
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x, y + CHUNK_SIZE))
+            .collect();
+        let parsed = parse(&write(&PatternFile::from_cells(cells.clone()))).unwrap();
+        assert_eq!(sorted(parsed.cells), sorted(cells));
+    }
+}
@@ -0,0 +1,188 @@
+//! Plaintext（`.cells`）形式の読み書き
+//!
+//! LifeWiki などで配布されている最も単純な形式。例（グライダー）:
+//!
+//! ```text
+//! !Name: Glider
+//! .O
+//! ..O
+//! OOO
+//! ```
+//!
+//! - `!` で始まる行はコメント（`!Name:` / `!Author:` は名前・作者として扱う）
+//! - `.` は死亡セル、`O`（または `*`）は生存セル。1行が1セル行に対応する
+
+use super::{FormatError, FormatErrorKind, PatternFile, bounding_box};
+
+/// Plaintext文字列を解析してパターンを返す
+///
+/// 位置情報を持たない形式のため、1行目・1列目が `(0, 0)` になる。
+pub fn parse(input: &str) -> Result<PatternFile, FormatError> {
+    let mut pattern = PatternFile::default();
+    let mut y = 0;
+
+    for (line_index, line) in input.lines().enumerate() {
+        if let Some(comment) = line.strip_prefix('!') {
+            parse_comment(comment.trim(), &mut pattern);
+            continue;
+        }
+        for (col_index, c) in line.trim_end().chars().enumerate() {
+            match c {
+                '.' => {}
+                'O' | '*' => pattern.cells.push((col_index as i32, y)),
+                _ => {
+                    return Err(FormatError::new(
+                        line_index + 1,
+                        col_index + 1,
+                        FormatErrorKind::UnexpectedChar(c),
+                    ));
+                }
+            }
+        }
+        y += 1;
+    }
+    Ok(pattern)
+}
+
+/// コメント行の内容を名前・作者・コメントに振り分ける
+fn parse_comment(comment: &str, pattern: &mut PatternFile) {
+    if let Some(name) = comment.strip_prefix("Name:") {
+        pattern.name = Some(name.trim().to_string());
+    } else if let Some(author) = comment.strip_prefix("Author:") {
+        pattern.author = Some(author.trim().to_string());
+    } else {
+        pattern.comments.push(comment.to_string());
+    }
+}
+
+/// パターンをPlaintext文字列に変換する
+///
+/// バウンディングボックスの左上を原点として出力する（位置情報は失われる）。
+/// 各行の末尾の死亡セルは省略し、空行は `.` 1文字で表す。
+pub fn write(pattern: &PatternFile) -> String {
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("!Name: {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        out.push_str(&format!("!Author: {author}\n"));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("!{comment}\n"));
+    }
+
+    let Some((min_x, min_y, _, max_y)) = bounding_box(&pattern.cells) else {
+        return out;
+    };
+    let height = (max_y - min_y + 1) as usize;
+    let mut rows: Vec<Vec<bool>> = vec![Vec::new(); height];
+    for &(x, y) in &pattern.cells {
+        let row = &mut rows[(y - min_y) as usize];
+        let col = (x - min_x) as usize;
+        if row.len() <= col {
+            row.resize(col + 1, false);
+        }
+        row[col] = true;
+    }
+
+    for row in rows {
+        if row.is_empty() {
+            out.push('.');
+        }
+        out.extend(row.iter().map(|&alive| if alive { 'O' } else { '.' }));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::LifePattern;
+
+    const GLIDER_CELLS: &str =
+        "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship.\n.O\n..O\nOOO\n";
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort();
+        cells
+    }
+
+    /// バウンディングボックスの左上を原点に平行移動する
+    fn normalized(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let (min_x, min_y, _, _) = bounding_box(cells).unwrap();
+        sorted(cells.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect())
+    }
+
+    #[test]
+    fn parse_glider() {
+        let pattern = parse(GLIDER_CELLS).unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]
+        );
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(
+            pattern.comments,
+            vec!["The smallest spaceship.".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_accepts_asterisk_and_blank_rows() {
+        let pattern = parse("*\n\n.*\n").unwrap();
+        assert_eq!(sorted(pattern.cells), vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn parse_reports_unexpected_char_position() {
+        let err = parse("!Name: bad\n..O\n.Ox.\n").unwrap_err();
+        assert_eq!(
+            err,
+            FormatError::new(3, 3, FormatErrorKind::UnexpectedChar('x'))
+        );
+    }
+
+    #[test]
+    fn write_glider() {
+        let pattern = parse(GLIDER_CELLS).unwrap();
+        assert_eq!(write(&pattern), GLIDER_CELLS);
+    }
+
+    #[test]
+    fn write_empty_rows_as_dot() {
+        let text = write(&PatternFile::from_cells(vec![(0, 0), (0, 2)]));
+        assert_eq!(text, "O\n.\nO\n");
+    }
+
+    #[test]
+    fn write_normalizes_negative_coordinates() {
+        let text = write(&PatternFile::from_cells(vec![(-10, -5), (-8, -4)]));
+        assert_eq!(text, "O\n..O\n");
+    }
+
+    #[test]
+    fn roundtrip_negative_coordinates_keeps_shape() {
+        let cells: Vec<_> = LifePattern::Lwss
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x - 100, y - 37))
+            .collect();
+        let parsed = parse(&write(&PatternFile::from_cells(cells.clone()))).unwrap();
+        assert_eq!(normalized(&parsed.cells), normalized(&cells));
+    }
+
+    #[test]
+    fn roundtrip_all_patterns() {
+        for pattern in LifePattern::all() {
+            let parsed = parse(&write(&PatternFile::from_cells(pattern.cells().to_vec()))).unwrap();
+            assert_eq!(
+                normalized(&parsed.cells),
+                normalized(pattern.cells()),
+                "{:?} failed to roundtrip",
+                pattern
+            );
+        }
+    }
+}
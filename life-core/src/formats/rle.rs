@@ -0,0 +1,441 @@
+//! RLE（Run Length Encoded）形式の読み書き
+//!
+//! Golly などで標準的に使われる形式。例（グライダー）:
+//!
+//! ```text
+//! #N Glider
+//! x = 3, y = 3, rule = B3/S23
+//! bob$2bo$3o!
+//! ```
+//!
+//! - `b` / `.` は死亡セル、`o` は生存セル、`$` は行末、`!` はパターン終端
+//! - 直前の数字は繰り返し回数（`3o` = 生存セル3個、`2$` = 2行進む）
+//! - `#N` 名前、`#O` 作者、`#C` コメント、`#CXRLE Pos=x,y` / `#R x y` / `#P x y` 位置
+
+use super::{FormatError, FormatErrorKind, PatternFile, bounding_box};
+use crate::rules::Rule;
+
+/// 出力時の1行あたりの最大文字数
+const MAX_LINE_LENGTH: usize = 70;
+
+/// RLE文字列を解析してパターンを返す
+///
+/// ヘッダー行は省略可能。`!` 以降の内容は無視する。
+pub fn parse(input: &str) -> Result<PatternFile, FormatError> {
+    let mut pattern = PatternFile::default();
+    let mut offset = (0, 0);
+    let mut body_started = false;
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0i32, 0i32);
+    let mut run: Option<i32> = None;
+
+    'lines: for (line_index, line) in input.lines().enumerate() {
+        let line_no = line_index + 1;
+        let trimmed = line.trim_start();
+
+        if !body_started && trimmed.starts_with('#') {
+            parse_comment_line(trimmed, line_no, &mut pattern, &mut offset)?;
+            continue;
+        }
+        if !body_started && trimmed.starts_with('x') {
+            parse_header(line, line_no, &mut pattern)?;
+            body_started = true;
+            continue;
+        }
+
+        for (col_index, c) in line.chars().enumerate() {
+            let column = col_index + 1;
+            let error = |kind| FormatError::new(line_no, column, kind);
+            if c.is_whitespace() {
+                continue;
+            }
+            body_started = true;
+
+            if let Some(digit) = c.to_digit(10) {
+                let value = run
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(digit as i32))
+                    .ok_or(error(FormatErrorKind::InvalidNumber))?;
+                run = Some(value);
+                continue;
+            }
+
+            let count = run.take().unwrap_or(1);
+            match c {
+                'b' | '.' => x = x.saturating_add(count),
+                'o' => {
+                    cells.extend((0..count).map(|i| (x + i, y)));
+                    x = x.saturating_add(count);
+                }
+                '$' => {
+                    y = y.saturating_add(count);
+                    x = 0;
+                }
+                '!' => break 'lines,
+                _ => return Err(error(FormatErrorKind::UnexpectedChar(c))),
+            }
+        }
+    }
+
+    pattern.cells = cells
+        .into_iter()
+        .map(|(cx, cy)| (cx + offset.0, cy + offset.1))
+        .collect();
+    Ok(pattern)
+}
+
+/// `#` で始まるコメント行を解析する
+fn parse_comment_line(
+    line: &str,
+    line_no: usize,
+    pattern: &mut PatternFile,
+    offset: &mut (i32, i32),
+) -> Result<(), FormatError> {
+    let mut chars = line.chars();
+    chars.next(); // '#'
+    let tag = chars.next().unwrap_or(' ');
+    let content = chars.as_str().trim();
+
+    match tag {
+        'N' => pattern.name = Some(content.to_string()),
+        'O' => pattern.author = Some(content.to_string()),
+        'C' | 'c' => {
+            if let Some(position) = content.strip_prefix("XRLE") {
+                if let Some(pos) = parse_xrle_position(position) {
+                    *offset = pos;
+                }
+            } else {
+                pattern.comments.push(content.to_string());
+            }
+        }
+        'R' | 'P' => {
+            let mut numbers = content.split_whitespace().map(str::parse::<i32>);
+            match (numbers.next(), numbers.next()) {
+                (Some(Ok(px)), Some(Ok(py))) => *offset = (px, py),
+                _ => {
+                    return Err(FormatError::new(line_no, 3, FormatErrorKind::InvalidNumber));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// `#CXRLE Pos=x,y Gen=n` から位置を取り出す
+fn parse_xrle_position(content: &str) -> Option<(i32, i32)> {
+    let pos = content
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("Pos="))?;
+    let (px, py) = pos.split_once(',')?;
+    Some((px.trim().parse().ok()?, py.trim().parse().ok()?))
+}
+
+/// ヘッダー行 `x = m, y = n, rule = abc` を解析する
+///
+/// `x` / `y` はパターン本体から求まるため検証のみ行い、ルールのみ記録する。
+/// Larger than Life のルール文字列はカンマを含むため、`rule` は行末までを値とする。
+fn parse_header(line: &str, line_no: usize, pattern: &mut PatternFile) -> Result<(), FormatError> {
+    let mut column = 1;
+    let mut rest = line;
+    while !rest.is_empty() {
+        let field = match rest.split_once(',') {
+            Some((field, _)) if !is_rule_field(field) => field,
+            _ => rest,
+        };
+        rest = rest.get(field.len() + 1..).unwrap_or_default();
+        let field_column = column + field.len() - field.trim_start().len();
+        column += field.chars().count() + 1;
+
+        let error = |kind| FormatError::new(line_no, field_column, kind);
+        let (key, value) = field
+            .split_once('=')
+            .ok_or(error(FormatErrorKind::InvalidHeader))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" | "y" => {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| error(FormatErrorKind::InvalidNumber))?;
+            }
+            "rule" => {
+                let rule =
+                    Rule::parse(value).map_err(|e| error(FormatErrorKind::InvalidRule(e)))?;
+                pattern.rule = Some(rule);
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// ヘッダー行のフィールドが `rule = ...` かを返す
+fn is_rule_field(field: &str) -> bool {
+    field
+        .split_once('=')
+        .is_some_and(|(key, _)| key.trim() == "rule")
+}
+
+/// パターンをRLE文字列に変換する
+///
+/// バウンディングボックスの左上が `(0, 0)` でない場合は `#CXRLE Pos=x,y` 行で位置を保存する。
+/// ルールが未指定の場合はコンウェイのルールを出力する。
+pub fn write(pattern: &PatternFile) -> String {
+    let mut out = String::new();
+    if let Some(name) = &pattern.name {
+        out.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &pattern.author {
+        out.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &pattern.comments {
+        out.push_str(&format!("#C {comment}\n"));
+    }
+
+    let rule = pattern.rule.unwrap_or_default();
+    let mut cells = pattern.cells.clone();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    cells.dedup();
+
+    let Some((min_x, min_y, max_x, max_y)) = bounding_box(&cells) else {
+        out.push_str(&format!("x = 0, y = 0, rule = {rule}\n!\n"));
+        return out;
+    };
+    if (min_x, min_y) != (0, 0) {
+        out.push_str(&format!("#CXRLE Pos={min_x},{min_y}\n"));
+    }
+    out.push_str(&format!(
+        "x = {}, y = {}, rule = {rule}\n",
+        max_x - min_x + 1,
+        max_y - min_y + 1
+    ));
+
+    let tokens = body_tokens(&cells, min_x, min_y);
+    let mut line = String::new();
+    for token in tokens {
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+/// 行・列順にソート済みのセルからRLE本体のトークン列を生成する
+fn body_tokens(cells: &[(i32, i32)], min_x: i32, min_y: i32) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut row = min_y;
+    let mut next_x = min_x;
+    let mut i = 0;
+
+    while i < cells.len() {
+        let (x, y) = cells[i];
+        if y != row {
+            tokens.push(run_token(y - row, '$'));
+            row = y;
+            next_x = min_x;
+        }
+        // 連続する生存セルをまとめる
+        let mut len = 1;
+        while i + len < cells.len() && cells[i + len] == (x + len as i32, y) {
+            len += 1;
+        }
+        if x > next_x {
+            tokens.push(run_token(x - next_x, 'b'));
+        }
+        tokens.push(run_token(len as i32, 'o'));
+        next_x = x + len as i32;
+        i += len;
+    }
+    tokens.push("!".to_string());
+    tokens
+}
+
+/// 繰り返し回数付きのトークンを生成する（1回の場合は数字を省略）
+fn run_token(count: i32, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{count}{tag}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::LifePattern;
+    use crate::rules::RulePreset;
+
+    const GLIDER_RLE: &str = "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    fn sorted(mut cells: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn parse_glider() {
+        let pattern = parse(GLIDER_RLE).unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]
+        );
+        assert_eq!(pattern.rule, Some(Rule::conway()));
+    }
+
+    #[test]
+    fn parse_comment_lines() {
+        let pattern = parse(GLIDER_RLE).unwrap();
+        assert_eq!(pattern.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(
+            pattern.comments,
+            vec!["The smallest spaceship.".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_header_without_spaces() {
+        let pattern = parse("x=2,y=1,rule=B36/S23\n2o!").unwrap();
+        assert_eq!(pattern.rule, Rule::parse("B36/S23").ok());
+        assert_eq!(sorted(pattern.cells), vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn parse_without_header() {
+        let pattern = parse("3o!").unwrap();
+        assert_eq!(pattern.rule, None);
+        assert_eq!(pattern.cells.len(), 3);
+    }
+
+    #[test]
+    fn parse_wrapped_lines() {
+        // 行の途中で折り返されたRLE
+        let pattern = parse("x = 3, y = 3\nbo\nb$2b\no$3\no!").unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn parse_multi_row_skip() {
+        let pattern = parse("o3$o!").unwrap();
+        assert_eq!(sorted(pattern.cells), vec![(0, 0), (0, 3)]);
+    }
+
+    #[test]
+    fn parse_ignores_text_after_terminator() {
+        let pattern = parse("2o!\nthis is not rle").unwrap();
+        assert_eq!(pattern.cells.len(), 2);
+    }
+
+    #[test]
+    fn parse_dot_as_dead_cell() {
+        let pattern = parse(".o!").unwrap();
+        assert_eq!(pattern.cells, vec![(1, 0)]);
+    }
+
+    #[test]
+    fn parse_xrle_position() {
+        let pattern = parse("#CXRLE Pos=-5,-3 Gen=0\nx = 1, y = 1\no!").unwrap();
+        assert_eq!(pattern.cells, vec![(-5, -3)]);
+        assert!(pattern.comments.is_empty());
+    }
+
+    #[test]
+    fn parse_r_offset() {
+        let pattern = parse("#R -2 4\no!").unwrap();
+        assert_eq!(pattern.cells, vec![(-2, 4)]);
+    }
+
+    #[test]
+    fn parse_reports_unexpected_char_position() {
+        let err = parse("x = 3, y = 1\n2oz!").unwrap_err();
+        assert_eq!(
+            err,
+            FormatError::new(2, 3, FormatErrorKind::UnexpectedChar('z'))
+        );
+    }
+
+    #[test]
+    fn parse_reports_invalid_rule_position() {
+        let err = parse("x = 3, y = 1, rule = B9/S23\n3o!").unwrap_err();
+        assert_eq!(err.line, 1);
+        assert_eq!(err.column, 15);
+        assert!(matches!(err.kind, FormatErrorKind::InvalidRule(_)));
+    }
+
+    #[test]
+    fn parse_larger_than_life_rule_with_commas() {
+        let pattern = parse("x = 1, y = 1, rule = R5,C0,M1,S34..58,B34..45,NM\no!").unwrap();
+        assert_eq!(pattern.rule, Some(RulePreset::Bosco.rule()));
+        let written = write(&pattern);
+        assert!(written.contains("rule = R5,C0,M1,S34..58,B34..45,NM\n"));
+    }
+
+    #[test]
+    fn parse_reports_invalid_header() {
+        let err = parse("x = 3, y\n3o!").unwrap_err();
+        assert_eq!(err, FormatError::new(1, 8, FormatErrorKind::InvalidHeader));
+    }
+
+    #[test]
+    fn parse_reports_run_count_overflow() {
+        let err = parse("99999999999o!").unwrap_err();
+        assert_eq!(err.kind, FormatErrorKind::InvalidNumber);
+    }
+
+    #[test]
+    fn write_glider() {
+        let pattern = parse(GLIDER_RLE).unwrap();
+        assert_eq!(write(&pattern), GLIDER_RLE);
+    }
+
+    #[test]
+    fn write_empty_pattern() {
+        let rle = write(&PatternFile::default());
+        assert_eq!(rle, "x = 0, y = 0, rule = B3/S23\n!\n");
+        assert!(parse(&rle).unwrap().cells.is_empty());
+    }
+
+    #[test]
+    fn write_collapses_empty_rows() {
+        let rle = write(&PatternFile::from_cells(vec![(0, 0), (0, 3)]));
+        assert!(rle.ends_with("o3$o!\n"), "{rle}");
+    }
+
+    #[test]
+    fn write_records_offset() {
+        let rle = write(&PatternFile::from_cells(vec![(-4, 7)]));
+        assert!(rle.starts_with("#CXRLE Pos=-4,7\n"), "{rle}");
+    }
+
+    #[test]
+    fn write_wraps_long_lines() {
+        // 市松模様の行はトークンが多く折り返しが発生する
+        let cells: Vec<_> = (0..200).step_by(2).map(|x| (x, 0)).collect();
+        let rle = write(&PatternFile::from_cells(cells.clone()));
+        assert!(rle.lines().all(|l| l.len() <= MAX_LINE_LENGTH));
+        assert_eq!(sorted(parse(&rle).unwrap().cells), cells);
+    }
+
+    #[test]
+    fn roundtrip_all_patterns() {
+        for pattern in LifePattern::all() {
+            let cells = sorted(pattern.cells().to_vec());
+            let rle = write(&PatternFile::from_cells(cells.clone()));
+            assert_eq!(
+                sorted(parse(&rle).unwrap().cells),
+                cells,
+                "{:?} failed to roundtrip",
+                pattern
+            );
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Generations ルールの衰退中のセル
+//!
+//! Generations ルールでは、生存条件を満たさなかった生存セルはすぐには死亡せず、
+//! 状態2から `C - 1` まで1世代ずつ衰退してから死亡する（`C` はルールの状態数）。
+//! 衰退中のセルは隣接数に数えられず、誕生もしない。
+//! 状態ごとに1枚の `Bitboard` を持ち、生存セルと同じくXOR差分で巻き戻せるようにする。
+
+use rustc_hash::FxHashSet;
+
+use crate::bitboard::{Bitboard, ChunkKey, ChunkRows};
+use crate::consts::FIRST_DECAYING_STATE;
+
+/// 衰退中のセルの状態ごとの集合
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecayingCells {
+    /// 状態ごとのセル（添字 `i` が状態 `i + 2`、末尾は常に空でない）
+    layers: Vec<Bitboard>,
+}
+
+impl DecayingCells {
+    /// 空の集合を生成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定座標のセルの衰退中の状態を返す（衰退中でなければ `None`）
+    pub fn get(&self, x: i32, y: i32) -> Option<u8> {
+        self.layers
+            .iter()
+            .position(|layer| layer.get(x, y))
+            .map(|index| index as u8 + FIRST_DECAYING_STATE)
+    }
+
+    /// 指定座標のセルを衰退中の状態 `state` にし、変更前の状態を返す
+    ///
+    /// `None` または2未満の状態を渡すと衰退中のセルから取り除く。
+    pub fn set(&mut self, x: i32, y: i32, state: Option<u8>) -> Option<u8> {
+        let state = state.filter(|&state| state >= FIRST_DECAYING_STATE);
+        let previous = self.get(x, y);
+        if previous == state {
+            return previous;
+        }
+        if let Some(previous) = previous {
+            self.layers[(previous - FIRST_DECAYING_STATE) as usize].set(x, y, false);
+        }
+        if let Some(state) = state {
+            let index = (state - FIRST_DECAYING_STATE) as usize;
+            if self.layers.len() <= index {
+                self.layers.resize_with(index + 1, Bitboard::new);
+            }
+            self.layers[index].set(x, y, true);
+        }
+        self.trim();
+        previous
+    }
+
+    /// 衰退中のセルが1つもないかを返す
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// 衰退中のセルの数を返す
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(Bitboard::len).sum()
+    }
+
+    /// 全ての衰退中のセルを取り除く
+    pub fn clear(&mut self) {
+        self.layers.clear();
+    }
+
+    /// 衰退中のセルの座標と状態を列挙する（順序は不定）
+    pub fn iter(&self) -> impl Iterator<Item = ((i32, i32), u8)> + '_ {
+        self.layers.iter().enumerate().flat_map(|(index, layer)| {
+            let state = index as u8 + FIRST_DECAYING_STATE;
+            layer.iter().map(move |cell| (cell, state))
+        })
+    }
+
+    /// 衰退中のセルを含むチャンクのキーを返す
+    pub fn chunk_keys(&self) -> FxHashSet<ChunkKey> {
+        self.layers.iter().flat_map(Bitboard::chunk_keys).collect()
+    }
+
+    /// 指定チャンクに衰退中のセルを持つ状態と、その状態のセル行を列挙する
+    pub fn chunk_layers(&self, key: ChunkKey) -> impl Iterator<Item = (u8, &ChunkRows)> + '_ {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, layer)| {
+                layer
+                    .chunk(key)
+                    .map(|rows| (index as u8 + FIRST_DECAYING_STATE, rows))
+            })
+    }
+
+    /// 2つの集合で内容が異なるチャンクのキーを返す
+    pub fn diff_chunks(&self, other: &DecayingCells) -> FxHashSet<ChunkKey> {
+        let empty = Bitboard::new();
+        (0..self.layers.len().max(other.layers.len()))
+            .flat_map(|index| {
+                let layer = self.layers.get(index).unwrap_or(&empty);
+                layer.diff_chunks(other.layers.get(index).unwrap_or(&empty))
+            })
+            .collect()
+    }
+
+    /// 2状態の世代計算の結果に衰退を適用し、1世代進める
+    ///
+    /// `before` は進める前の生存セル、`after` は衰退中のセルを考慮せずに計算した次世代の生存セル。
+    /// 衰退中のセルでの誕生を `after` から取り除き、生存し続けなかったセルを状態2にする。
+    /// 既存の衰退中のセルは状態が1つ進み、`states` に達したものは死亡する。
+    pub fn advance(&mut self, before: &Bitboard, after: &mut Bitboard, states: u8) {
+        for layer in &self.layers {
+            after.difference_assign(layer);
+        }
+        if states <= FIRST_DECAYING_STATE {
+            self.layers.clear();
+            return;
+        }
+        self.layers.insert(0, before.difference(after));
+        self.layers
+            .truncate((states - FIRST_DECAYING_STATE) as usize);
+        self.trim();
+    }
+
+    /// 2つの集合の状態ごとの排他的論理和を返す
+    ///
+    /// 結果を元のどちらかと再度XORすると、もう一方が得られる。
+    pub fn xor(&self, other: &DecayingCells) -> DecayingCells {
+        let mut result = self.clone();
+        result.xor_assign(other);
+        result
+    }
+
+    /// `other` との状態ごとの排他的論理和で自身を更新する
+    pub fn xor_assign(&mut self, other: &DecayingCells) {
+        if self.layers.len() < other.layers.len() {
+            self.layers.resize_with(other.layers.len(), Bitboard::new);
+        }
+        for (layer, other_layer) in self.layers.iter_mut().zip(&other.layers) {
+            layer.xor_assign(other_layer);
+        }
+        self.trim();
+    }
+
+    /// 矩形 `min`〜`max`（両端を含む）の外側の衰退中のセルを取り除く
+    pub fn retain_rect(&mut self, min: (i32, i32), max: (i32, i32)) {
+        for layer in &mut self.layers {
+            layer.retain_rect(min, max);
+        }
+        self.trim();
+    }
+
+    /// 状態数 `states` のルールでは存在しない状態のセルを取り除く
+    pub fn truncate(&mut self, states: u8) {
+        self.layers
+            .truncate(states.saturating_sub(FIRST_DECAYING_STATE) as usize);
+        self.trim();
+    }
+
+    /// 保持しているセルのおおよそのメモリ使用量（バイト）を返す
+    pub fn memory_bytes(&self) -> usize {
+        self.layers.iter().map(Bitboard::memory_bytes).sum()
+    }
+
+    /// 末尾の空の状態を取り除く
+    fn trim(&mut self) {
+        while self.layers.last().is_some_and(Bitboard::is_empty) {
+            self.layers.pop();
+        }
+    }
+}
+
+impl FromIterator<((i32, i32), u8)> for DecayingCells {
+    fn from_iter<I: IntoIterator<Item = ((i32, i32), u8)>>(iter: I) -> Self {
+        let mut cells = Self::new();
+        for ((x, y), state) in iter {
+            cells.set(x, y, Some(state));
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rule;
+
+    /// 衰退を含めて1世代進める
+    fn step(cells: &Bitboard, decaying: &mut DecayingCells, rule: &Rule) -> Bitboard {
+        let mut next = cells.step(rule);
+        decaying.advance(cells, &mut next, rule.states());
+        next
+    }
+
+    #[test]
+    fn dying_cells_pass_through_each_state() {
+        let rule = Rule::parse("B2/S/C4").unwrap();
+        let mut cells: Bitboard = [(0, 0)].into_iter().collect();
+        let mut decaying = DecayingCells::new();
+        cells = step(&cells, &mut decaying, &rule);
+        assert!(cells.is_empty());
+        assert_eq!(decaying.get(0, 0), Some(2));
+        cells = step(&cells, &mut decaying, &rule);
+        assert_eq!(decaying.get(0, 0), Some(3));
+        step(&cells, &mut decaying, &rule);
+        assert_eq!(decaying.get(0, 0), None);
+        assert!(decaying.is_empty());
+    }
+
+    #[test]
+    fn decaying_cells_are_not_born() {
+        let rule = Rule::parse("B2/S/C3").unwrap();
+        let cells: Bitboard = [(0, 0), (2, 0)].into_iter().collect();
+        let mut decaying: DecayingCells = [((1, 1), 2)].into_iter().collect();
+        let next = step(&cells, &mut decaying, &rule);
+        // 上下とも隣接2だが、衰退中の (1, 1) は誕生せず死亡する
+        assert!(next.get(1, -1));
+        assert!(!next.get(1, 1));
+        assert_eq!(decaying.get(1, 1), None);
+        assert_eq!(decaying.get(0, 0), Some(2));
+        assert_eq!(decaying.len(), 2);
+    }
+
+    #[test]
+    fn life_like_rule_has_no_decaying_cells() {
+        let rule = Rule::conway();
+        let cells: Bitboard = [(0, 0)].into_iter().collect();
+        let mut decaying = DecayingCells::new();
+        step(&cells, &mut decaying, &rule);
+        assert!(decaying.is_empty());
+    }
+
+    #[test]
+    fn set_moves_cell_between_states() {
+        let mut decaying = DecayingCells::new();
+        assert_eq!(decaying.set(3, 4, Some(5)), None);
+        assert_eq!(decaying.set(3, 4, Some(2)), Some(5));
+        assert_eq!(decaying.get(3, 4), Some(2));
+        assert_eq!(decaying.set(3, 4, Some(1)), Some(2));
+        assert!(decaying.is_empty());
+    }
+
+    #[test]
+    fn xor_is_self_inverse() {
+        let a: DecayingCells = [((0, 0), 2), ((1, 0), 3)].into_iter().collect();
+        let b: DecayingCells = [((0, 0), 3), ((70, 0), 4)].into_iter().collect();
+        let diff = a.xor(&b);
+        assert_eq!(a.xor(&diff), b);
+        assert_eq!(b.xor(&diff), a);
+        assert!(a.xor(&a).is_empty());
+        assert_eq!(
+            a.diff_chunks(&b),
+            [(0, 0), (1, 0)].into_iter().collect::<FxHashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn truncate_and_retain_rect_drop_cells() {
+        let mut decaying: DecayingCells = [((0, 0), 2), ((1, 0), 5), ((90, 0), 2)]
+            .into_iter()
+            .collect();
+        decaying.truncate(4);
+        assert_eq!(decaying.get(1, 0), None);
+        decaying.retain_rect((0, 0), (10, 10));
+        assert_eq!(decaying.iter().collect::<Vec<_>>(), vec![((0, 0), 2)]);
+        assert_eq!(decaying.chunk_layers((0, 0)).count(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! セルの状態変化の頻度（活動度）のヒートマップ
+//!
+//! 世代進行のたびに状態が反転したセルを記録し、直近 `window` 世代の反転回数を
+//! 古い世代ほど `decay` 倍ずつ小さく数えて積算する。
+//! `decay` が1.0なら窓の中の反転回数そのものになる。
+
+use std::collections::VecDeque;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::bitboard::{Bitboard, ChunkKey};
+use crate::consts::{HEATMAP_DEFAULT_DECAY, HEATMAP_DEFAULT_WINDOW, HEATMAP_MIN_HEAT};
+
+/// ヒートマップの集計方法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeatmapConfig {
+    /// 反転を数える直近の世代数（1以上）
+    pub window: usize,
+    /// 1世代古くなるごとに反転1回の重みに掛ける係数（0.0より大きく1.0以下）
+    pub decay: f32,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            window: HEATMAP_DEFAULT_WINDOW,
+            decay: HEATMAP_DEFAULT_DECAY,
+        }
+    }
+}
+
+/// セルごとの状態変化の頻度
+#[derive(Clone, Debug)]
+pub struct ActivityHeatmap {
+    /// 集計方法
+    config: HeatmapConfig,
+    /// 窓の中の全ての世代で反転し続けたセルの重み付きの反転回数（正規化の分母）
+    max_heat: f32,
+    /// セルごとの重み付きの反転回数
+    heat: FxHashMap<(i32, i32), f32>,
+    /// 窓の中の世代ごとの反転したセル（新しい順）
+    flips: VecDeque<Bitboard>,
+}
+
+impl ActivityHeatmap {
+    /// 空のヒートマップを生成する（窓と係数は有効な範囲に丸める）
+    pub fn new(config: HeatmapConfig) -> Self {
+        let config = HeatmapConfig {
+            window: config.window.max(1),
+            decay: if config.decay > 0.0 {
+                config.decay.min(1.0)
+            } else {
+                1.0
+            },
+        };
+        Self {
+            config,
+            max_heat: (0..config.window)
+                .map(|age| config.decay.powi(age as i32))
+                .sum(),
+            heat: FxHashMap::default(),
+            flips: VecDeque::with_capacity(config.window),
+        }
+    }
+
+    /// 集計方法を返す
+    pub fn config(&self) -> HeatmapConfig {
+        self.config
+    }
+
+    /// 1世代分の反転したセル（世代進行の前後のXOR）を記録する
+    pub fn record(&mut self, flipped: Bitboard) {
+        let decay = self.config.decay;
+        if decay < 1.0 {
+            for heat in self.heat.values_mut() {
+                *heat *= decay;
+            }
+        }
+        if self.flips.len() == self.config.window
+            && let Some(expired) = self.flips.pop_back()
+        {
+            // NOTE: 窓から外れた世代の反転は、これまでに掛かった減衰ぶんだけ差し引く
+            let weight = decay.powi(self.config.window as i32);
+            for cell in expired.iter() {
+                if let Some(heat) = self.heat.get_mut(&cell) {
+                    *heat -= weight;
+                }
+            }
+        }
+        for cell in flipped.iter() {
+            *self.heat.entry(cell).or_insert(0.0) += 1.0;
+        }
+        self.heat.retain(|_, heat| *heat >= HEATMAP_MIN_HEAT);
+        self.flips.push_front(flipped);
+    }
+
+    /// 指定座標のセルの活動度を0.0〜1.0で返す
+    ///
+    /// 窓の中の全ての世代で反転し続けたセルが1.0になる。
+    pub fn intensity(&self, x: i32, y: i32) -> f32 {
+        self.heat
+            .get(&(x, y))
+            .map_or(0.0, |heat| (heat / self.max_heat).clamp(0.0, 1.0))
+    }
+
+    /// 活動度が0より大きいセルがあるかを返す
+    pub fn is_empty(&self) -> bool {
+        self.heat.is_empty()
+    }
+
+    /// 活動度が0より大きいセルを含むチャンクのキーを返す
+    pub fn chunk_keys(&self) -> FxHashSet<ChunkKey> {
+        self.heat
+            .keys()
+            .map(|&(x, y)| Bitboard::chunk_key(x, y))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rule;
+
+    fn flips(cells: &[(i32, i32)]) -> Bitboard {
+        cells.iter().copied().collect()
+    }
+
+    #[test]
+    fn counts_flips_within_window() {
+        let mut heatmap = ActivityHeatmap::new(HeatmapConfig {
+            window: 4,
+            decay: 1.0,
+        });
+        heatmap.record(flips(&[(0, 0), (1, 0)]));
+        heatmap.record(flips(&[(0, 0)]));
+        assert_eq!(heatmap.intensity(0, 0), 0.5);
+        assert_eq!(heatmap.intensity(1, 0), 0.25);
+        assert_eq!(heatmap.intensity(9, 9), 0.0);
+
+        // 窓から外れた反転は数えない
+        for _ in 0..3 {
+            heatmap.record(Bitboard::new());
+        }
+        assert_eq!(heatmap.intensity(0, 0), 0.25);
+        assert_eq!(heatmap.intensity(1, 0), 0.0);
+        heatmap.record(Bitboard::new());
+        assert!(heatmap.is_empty());
+    }
+
+    #[test]
+    fn decay_weights_recent_flips_more() {
+        let mut heatmap = ActivityHeatmap::new(HeatmapConfig {
+            window: 8,
+            decay: 0.5,
+        });
+        heatmap.record(flips(&[(0, 0)]));
+        heatmap.record(flips(&[(1, 0)]));
+        assert!(heatmap.intensity(1, 0) > heatmap.intensity(0, 0));
+        assert!((heatmap.intensity(0, 0) * 2.0 - heatmap.intensity(1, 0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn blinker_ends_are_fully_active() {
+        let config = HeatmapConfig {
+            window: 6,
+            decay: 0.9,
+        };
+        let mut heatmap = ActivityHeatmap::new(config);
+        let mut cells = flips(&[(-1, 0), (0, 0), (1, 0)]);
+        for _ in 0..10 {
+            let next = cells.step(&Rule::conway());
+            heatmap.record(cells.xor(&next));
+            cells = next;
+        }
+        // 端のセルは毎世代反転し、中心のセルは一度も反転しない
+        assert!((heatmap.intensity(-1, 0) - 1.0).abs() < 1e-5);
+        assert!((heatmap.intensity(0, 1) - 1.0).abs() < 1e-5);
+        assert_eq!(heatmap.intensity(0, 0), 0.0);
+        assert_eq!(heatmap.chunk_keys().len(), 3);
+    }
+
+    #[test]
+    fn invalid_config_is_clamped() {
+        let heatmap = ActivityHeatmap::new(HeatmapConfig {
+            window: 0,
+            decay: -1.0,
+        });
+        assert_eq!(
+            heatmap.config(),
+            HeatmapConfig {
+                window: 1,
+                decay: 1.0
+            }
+        );
+    }
+}
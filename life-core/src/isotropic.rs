@@ -0,0 +1,559 @@
+//! 等方的な非総和型ルール（Hensel 表記）
+//!
+//! ムーア近傍の8セルのうち生きているセルの配置を、回転・鏡映で移り合うものを同じ種類として
+//! 近傍数と英字で表す（`2a`、`3-jr` など）。英字を付けない近傍数は、その数の全ての配置を表す。
+//! 配置は8ビットのマスクで表し、bit 0 から順に北西・北・北東・西・東・南西・南・南東のセルに対応する。
+//! 近傍数5〜7の配置は、死亡しているセルの配置が同じ英字の近傍数3〜1の配置になるものを表す。
+
+use rustc_hash::FxHashMap;
+
+use crate::bitboard::Bitboard;
+use crate::rules::{Rule, RuleParseError};
+use crate::simulation;
+use crate::topology::Topology;
+
+/// 配置のマスクの各ビットに対応する近傍セルの相対座標（北西から行ごとに並べる）
+///
+/// 反対方向のセルは `7 - bit` 番目のビットに対応する。
+pub const CONFIGURATION_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// 近傍数1〜4の配置を表す英字と、その代表の配置
+///
+/// 英字は Hensel 表記の標準の順に並べる。
+const HENSEL_LETTERS: [&[(char, u8)]; 4] = [
+    &[('c', 0b0000_0001), ('e', 0b0000_0010)],
+    &[
+        ('c', 0b0000_0101),
+        ('e', 0b0000_1010),
+        ('k', 0b0001_0001),
+        ('a', 0b0000_0011),
+        ('i', 0b0001_1000),
+        ('n', 0b0010_0100),
+    ],
+    &[
+        ('c', 0b0010_0101),
+        ('e', 0b0001_1010),
+        ('k', 0b0011_0010),
+        ('a', 0b0000_1011),
+        ('i', 0b0000_0111),
+        ('n', 0b0000_1101),
+        ('y', 0b0011_0001),
+        ('q', 0b0010_0110),
+        ('j', 0b0000_1110),
+        ('r', 0b0001_1001),
+    ],
+    &[
+        ('c', 0b1010_0101),
+        ('e', 0b0101_1010),
+        ('k', 0b0011_0011),
+        ('a', 0b0000_1111),
+        ('i', 0b0001_1101),
+        ('n', 0b0010_0111),
+        ('y', 0b0011_0101),
+        ('q', 0b0011_0110),
+        ('j', 0b0011_1010),
+        ('r', 0b0001_1011),
+        ('t', 0b0011_1001),
+        ('w', 0b0010_1110),
+        ('z', 0b0011_1100),
+    ],
+];
+
+/// 近傍数 `count` の配置を表す英字と代表の配置を返す（近傍数0と8は英字を持たない）
+pub fn letters(count: u32) -> Vec<(char, u8)> {
+    match count {
+        1..=4 => HENSEL_LETTERS[count as usize - 1].to_vec(),
+        5..=7 => HENSEL_LETTERS[7 - count as usize]
+            .iter()
+            .map(|&(letter, configuration)| (letter, !configuration))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 配置を回転・鏡映した8通りの配置を返す
+pub fn symmetries(configuration: u8) -> [u8; 8] {
+    let transform = |f: &dyn Fn(i32, i32) -> (i32, i32)| {
+        CONFIGURATION_OFFSETS
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| configuration >> bit & 1 == 1)
+            .fold(0u8, |image, (_, &(dx, dy))| {
+                let target = f(dx, dy);
+                let bit = CONFIGURATION_OFFSETS
+                    .iter()
+                    .position(|&offset| offset == target)
+                    .unwrap_or_default();
+                image | 1 << bit
+            })
+    };
+    [
+        transform(&|dx, dy| (dx, dy)),
+        transform(&|dx, dy| (-dy, dx)),
+        transform(&|dx, dy| (-dx, -dy)),
+        transform(&|dx, dy| (dy, -dx)),
+        transform(&|dx, dy| (-dx, dy)),
+        transform(&|dx, dy| (dx, -dy)),
+        transform(&|dx, dy| (dy, dx)),
+        transform(&|dx, dy| (-dy, -dx)),
+    ]
+}
+
+/// 配置の近傍数と、Hensel 表記の英字を返す（近傍数0と8は英字なし）
+pub fn classify(configuration: u8) -> (u32, Option<char>) {
+    let count = configuration.count_ones();
+    let images = symmetries(configuration);
+    let letter = letters(count)
+        .into_iter()
+        .find(|(_, representative)| images.contains(representative))
+        .map(|(letter, _)| letter);
+    (count, letter)
+}
+
+/// 近傍の配置（8ビットのマスク）の集合
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConfigurationSet([u64; 4]);
+
+impl ConfigurationSet {
+    /// 空の集合
+    pub const EMPTY: Self = Self([0; 4]);
+
+    /// 配置を含むかを返す
+    pub fn contains(&self, configuration: u8) -> bool {
+        self.0[configuration as usize / 64] >> (configuration % 64) & 1 == 1
+    }
+
+    /// 配置を1つ加えた集合を返す
+    fn with(mut self, configuration: u8) -> Self {
+        self.0[configuration as usize / 64] |= 1 << (configuration % 64);
+        self
+    }
+
+    /// 配置を回転・鏡映したものを全て加えた集合を返す
+    fn with_class(self, configuration: u8) -> Self {
+        symmetries(configuration).into_iter().fold(self, Self::with)
+    }
+
+    /// 近傍数 `count` の配置を全て加えた集合を返す
+    fn with_count(self, count: u32) -> Self {
+        (0..=u8::MAX)
+            .filter(|configuration| configuration.count_ones() == count)
+            .fold(self, Self::with)
+    }
+
+    /// 近傍数 `count` の配置を全て含むかを返す
+    pub fn contains_count(&self, count: u32) -> bool {
+        (0..=u8::MAX)
+            .filter(|configuration| configuration.count_ones() == count)
+            .all(|configuration| self.contains(configuration))
+    }
+
+    /// 近傍数ごとに全ての配置を含むか全く含まないか（B/S表記の数字だけで表せるか）を返す
+    pub fn is_totalistic(&self) -> bool {
+        (0..=8).all(|count| {
+            let mut configurations =
+                (0..=u8::MAX).filter(|configuration| configuration.count_ones() == count);
+            let first = configurations.next().is_some_and(|c| self.contains(c));
+            configurations.all(|c| self.contains(c) == first)
+        })
+    }
+}
+
+/// 誕生・生存の条件を近傍の配置で持つ Hensel 表記のルールの遷移
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IsotropicTransitions {
+    /// 死亡セルが誕生する配置
+    birth: ConfigurationSet,
+    /// 生存セルが生存し続ける配置
+    survival: ConfigurationSet,
+}
+
+impl IsotropicTransitions {
+    /// 誕生・生存の配置から遷移を生成する
+    ///
+    /// どちらも近傍数だけで表せる場合は、配置を区別する必要がないため `None` を返す。
+    pub fn new(birth: ConfigurationSet, survival: ConfigurationSet) -> Option<Self> {
+        (!birth.is_totalistic() || !survival.is_totalistic()).then_some(Self { birth, survival })
+    }
+
+    /// 死亡セルが誕生する配置を返す
+    pub fn birth(&self) -> &ConfigurationSet {
+        &self.birth
+    }
+
+    /// 生存セルが生存し続ける配置を返す
+    pub fn survival(&self) -> &ConfigurationSet {
+        &self.survival
+    }
+
+    /// 近傍の配置から次世代のセル状態を決定する
+    pub fn next_state(&self, alive: bool, configuration: u8) -> bool {
+        let set = if alive { &self.survival } else { &self.birth };
+        set.contains(configuration)
+    }
+}
+
+/// 誕生・生存の条件（`3`、`2-a`、`2ae3aeijr`）を配置の集合に変換する
+///
+/// 近傍数の直後の英字はその配置だけを、`-` に続く英字はその配置を除いた残りを表す。
+pub(crate) fn parse_transitions(s: &str) -> Result<ConfigurationSet, RuleParseError> {
+    let mut set = ConfigurationSet::EMPTY;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = match c.to_digit(10) {
+            Some(count) if count <= 8 => count,
+            _ => return Err(RuleParseError::InvalidDigit(c)),
+        };
+        let configurations = letters(count);
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut selected = Vec::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            let configuration = configurations
+                .iter()
+                .find(|&&(l, _)| l == letter.to_ascii_lowercase())
+                .map(|&(_, configuration)| configuration)
+                .ok_or(RuleParseError::InvalidConfiguration(letter))?;
+            selected.push(configuration);
+        }
+        set = match (negated, selected.is_empty()) {
+            (true, true) => return Err(RuleParseError::InvalidDigit('-')),
+            (false, true) => set.with_count(count),
+            (false, false) => selected.into_iter().fold(set, ConfigurationSet::with_class),
+            (true, false) => configurations
+                .into_iter()
+                .map(|(_, configuration)| configuration)
+                .filter(|configuration| !selected.contains(configuration))
+                .fold(set, ConfigurationSet::with_class),
+        };
+    }
+    Ok(set)
+}
+
+/// 配置の集合を Hensel 表記の条件に変換する
+///
+/// 近傍数ごとに、含む英字と `-` に続く除く英字のうち短い方で出力する。
+pub(crate) fn format_transitions(set: &ConfigurationSet) -> String {
+    let mut out = String::new();
+    for count in 0..=8u32 {
+        let configurations = letters(count);
+        let (included, excluded): (Vec<_>, Vec<_>) = configurations
+            .iter()
+            .partition(|&&(_, configuration)| set.contains(configuration));
+        if configurations.is_empty() {
+            if set.contains_count(count) {
+                out.extend(char::from_digit(count, 10));
+            }
+            continue;
+        }
+        if included.is_empty() {
+            continue;
+        }
+        out.extend(char::from_digit(count, 10));
+        if excluded.is_empty() {
+            continue;
+        }
+        if excluded.len() < included.len() {
+            out.push('-');
+            out.extend(excluded.iter().map(|&(letter, _)| letter));
+        } else {
+            out.extend(included.iter().map(|&(letter, _)| letter));
+        }
+    }
+    out
+}
+
+/// 生存セルの周囲の配置を求め、近傍の配置で判定するルールでトポロジー上を1世代進める
+///
+/// 各セルについて生存している近傍セルの方向を8ビットのマスクに集め、ルールで次の状態を決める。
+/// 有限トポロジーでは領域の外周に貼り合わせ先のセルを複製してから配置を求め、結果を領域内に限る。
+pub fn step(rule: &Rule, cells: &Bitboard, topology: &Topology) -> Bitboard {
+    let padded = topology.pad(cells, 1);
+    let mut configurations: FxHashMap<(i32, i32), u8> = FxHashMap::default();
+    for (x, y) in padded.iter() {
+        if topology.contains(x, y) {
+            configurations.entry((x, y)).or_default();
+        }
+        for (bit, &(dx, dy)) in CONFIGURATION_OFFSETS.iter().enumerate() {
+            let (nx, ny) = (x + dx, y + dy);
+            if topology.contains(nx, ny) {
+                // NOTE: 近傍のセルから見ると、このセルは反対方向にある
+                *configurations.entry((nx, ny)).or_default() |= 1 << (7 - bit);
+            }
+        }
+    }
+    configurations
+        .into_iter()
+        .filter(|&((x, y), configuration)| {
+            simulation::next_cell_state_with_configuration(rule, cells.get(x, y), configuration)
+        })
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Surface;
+
+    /// 3x3 の図（`/` で行を区切り、`o` が生存）から中心を除いた配置を求める
+    fn configuration(picture: &str) -> u8 {
+        let cells: Vec<char> = picture.chars().filter(|&c| c != '/').collect();
+        assert_eq!(cells.len(), 9, "{picture}");
+        CONFIGURATION_OFFSETS
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(dx, dy))| cells[((dy + 1) * 3 + dx + 1) as usize] == 'o')
+            .fold(0, |configuration, (bit, _)| configuration | 1 << bit)
+    }
+
+    fn sorted(cells: &Bitboard) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = cells.iter().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn letters_partition_every_configuration() {
+        let mut classes = [0; 9];
+        for count in 0..=8 {
+            let letters = letters(count);
+            classes[count as usize] = letters.len().max(1);
+            for &(_, configuration) in &letters {
+                assert_eq!(configuration.count_ones(), count);
+            }
+        }
+        assert_eq!(classes, [1, 2, 6, 10, 13, 10, 6, 2, 1]);
+        for configuration in 0..=u8::MAX {
+            let (count, letter) = classify(configuration);
+            assert_eq!(
+                letter.is_some(),
+                (1..=7).contains(&count),
+                "{configuration:#010b}"
+            );
+            // 回転・鏡映した配置は同じ英字になる
+            for image in symmetries(configuration) {
+                assert_eq!(classify(image), (count, letter));
+            }
+        }
+    }
+
+    #[test]
+    fn known_configurations_have_hensel_letters() {
+        let table = [
+            ("o../.x./...", 1, 'c'),
+            (".o./.x./...", 1, 'e'),
+            ("o.o/.x./...", 2, 'c'),
+            (".o./ox./...", 2, 'e'),
+            (".o./.x./o..", 2, 'k'),
+            ("oo./.x./...", 2, 'a'),
+            (".o./.x./.o.", 2, 'i'),
+            ("o../.x./..o", 2, 'n'),
+            ("o.o/.x./o..", 3, 'c'),
+            (".o./oxo/...", 3, 'e'),
+            (".o./.xo/o..", 3, 'k'),
+            ("oo./ox./...", 3, 'a'),
+            ("ooo/.x./...", 3, 'i'),
+            ("o.o/ox./...", 3, 'n'),
+            ("o../.xo/o..", 3, 'y'),
+            (".oo/.x./o..", 3, 'q'),
+            (".oo/ox./...", 3, 'j'),
+            ("o../oxo/...", 3, 'r'),
+            ("o.o/.x./o.o", 4, 'c'),
+            (".o./oxo/.o.", 4, 'e'),
+            ("ooo/ox./...", 4, 'a'),
+            ("ooo/.x./.o.", 4, 't'),
+            ("o../ox./.oo", 4, 'w'),
+            ("oo./.x./.oo", 4, 'z'),
+            (".o./oxo/.oo", 5, 'c'),
+            ("o.o/.x./ooo", 5, 'e'),
+            ("o.o/oxo/.o.", 5, 'y'),
+            ("ooo/oxo/...", 5, 'i'),
+            ("ooo/oxo/o..", 6, 'a'),
+            ("ooo/.x./ooo", 6, 'i'),
+            ("o.o/oxo/ooo", 7, 'e'),
+            (".oo/oxo/ooo", 7, 'c'),
+        ];
+        for (picture, count, letter) in table {
+            assert_eq!(
+                classify(configuration(picture)),
+                (count, Some(letter)),
+                "{picture}"
+            );
+        }
+        assert_eq!(classify(configuration(".../.x./...")), (0, None));
+        assert_eq!(classify(configuration("ooo/oxo/ooo")), (8, None));
+    }
+
+    #[test]
+    fn rules_follow_known_transitions() {
+        // (ルール, 周囲の図, 中心が生存しているか, 次世代に生存するか)
+        let table = [
+            ("B2-a/S12", "oo./.x./...", false, false),
+            ("B2-a/S12", ".o./.x./.o.", false, true),
+            ("B2-a/S12", "o.o/.x./...", false, true),
+            ("B2-a/S12", ".o./.x./...", true, true),
+            ("B2-a/S12", ".o./.x./o..", true, true),
+            ("B2-a/S12", "ooo/.x./...", true, false),
+            ("B3/S2-i34q", ".o./.x./.o.", true, false),
+            ("B3/S2-i34q", "o.o/.x./...", true, true),
+            ("B3/S2-i34q", ".oo/.x./o..", true, true),
+            ("B3/S2-i34q", ".oo/.xo/o..", true, true),
+            ("B3/S2-i34q", "ooo/.x./.o.", true, false),
+            ("B3/S2-i34q", "ooo/.x./...", false, true),
+            ("B2e/S", ".o./ox./...", false, true),
+            ("B2e/S", "o.o/.x./...", false, false),
+            ("B2e/S", ".o./ox./...", true, false),
+            ("B5c/S", ".o./oxo/.oo", false, true),
+            ("B5c/S", "ooo/oxo/...", false, false),
+            ("B3/S23", ".oo/.x./o..", false, true),
+            ("B3/S23", "ooo/.x./.o.", true, false),
+        ];
+        for (rulestring, picture, alive, expected) in table {
+            let rule = Rule::parse(rulestring).unwrap();
+            assert_eq!(
+                rule.next_state_with_configuration(alive, configuration(picture)),
+                expected,
+                "{rulestring} {picture} alive={alive}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_and_format_transitions() {
+        let table = [
+            ("2-a", "2-a"),
+            ("2ceikn", "2-a"),
+            ("2aeik", "2-cn"),
+            ("2ae3aeijr", "2ea3eaijr"),
+            ("1e2ce3-jr", "1e2ce3-jr"),
+            ("2-i34q", "2-i34q"),
+            ("23cekainyqjr", "23"),
+            ("5-c6n", "5-c6n"),
+            ("08", "08"),
+            ("", ""),
+        ];
+        for (input, expected) in table {
+            let set = parse_transitions(input).unwrap();
+            assert_eq!(format_transitions(&set), expected, "{input}");
+            assert_eq!(parse_transitions(expected), Ok(set));
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_letters() {
+        assert_eq!(
+            parse_transitions("2x"),
+            Err(RuleParseError::InvalidConfiguration('x'))
+        );
+        assert_eq!(
+            parse_transitions("1a"),
+            Err(RuleParseError::InvalidConfiguration('a'))
+        );
+        assert_eq!(
+            parse_transitions("0c"),
+            Err(RuleParseError::InvalidConfiguration('c'))
+        );
+        assert_eq!(
+            parse_transitions("3-"),
+            Err(RuleParseError::InvalidDigit('-'))
+        );
+        assert_eq!(
+            parse_transitions("a3"),
+            Err(RuleParseError::InvalidDigit('a'))
+        );
+    }
+
+    #[test]
+    fn totalistic_step_matches_bitboard() {
+        let rule = Rule::conway();
+        let mut cells: Bitboard = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]
+            .into_iter()
+            .collect();
+        let mut expected = cells.clone();
+        for _ in 0..20 {
+            cells = step(&rule, &cells, &Topology::Infinite);
+            expected = expected.step(&rule);
+        }
+        assert_eq!(sorted(&cells), sorted(&expected));
+    }
+
+    #[test]
+    fn domino_is_still_life_without_2a_births() {
+        let domino: Bitboard = [(0, 0), (1, 0)].into_iter().collect();
+        let rule = Rule::parse("B2-a/S12").unwrap();
+        assert_eq!(
+            sorted(&step(&rule, &domino, &Topology::Infinite)),
+            sorted(&domino)
+        );
+        // 全ての配置で誕生する B2 では上下に4セル誕生する
+        let totalistic = Rule::parse("B2/S12").unwrap();
+        assert_eq!(step(&totalistic, &domino, &Topology::Infinite).len(), 6);
+    }
+
+    #[test]
+    fn configurations_wrap_around_torus() {
+        let rule = Rule::parse("B2-a/S12").unwrap();
+        let torus = Topology::finite(Surface::Torus, 8, 8);
+        let rect = torus.bounds().unwrap();
+        let domino: Bitboard = [(rect.max.0, 0), (rect.min.0, 0)].into_iter().collect();
+        assert_eq!(sorted(&step(&rule, &domino, &torus)), sorted(&domino));
+    }
+
+    #[test]
+    fn totalistic_step_matches_topology_on_every_surface() {
+        let rule = Rule::conway();
+        let isotropic = Rule::parse("B3/S23").unwrap();
+        // NOTE: 端と角をまたぐよう、領域全体に散らばった疑似乱数のスープを使う
+        let mut seed = 0x2545_f491_u32;
+        let mut soup = Bitboard::new();
+        for y in -4..4 {
+            for x in -5..5 {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                if seed.is_multiple_of(3) {
+                    soup.set(x, y, true);
+                }
+            }
+        }
+        for &surface in Surface::all() {
+            let topology = Topology::finite(surface, 10, 8);
+            let (mut expected, mut actual) = (soup.clone(), soup.clone());
+            for generation in 0..16 {
+                expected = topology.step(&expected, &rule);
+                actual = step(&isotropic, &actual, &topology);
+                assert_eq!(
+                    sorted(&actual),
+                    sorted(&expected),
+                    "{surface:?} generation {generation}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn configurations_follow_mirrored_gluing() {
+        // 8×8 の領域は x: -4..=3, y: -4..=3
+        let born = Rule::parse("B2i/S").unwrap();
+        let not_born = Rule::parse("B2-i/S").unwrap();
+        // クラインの壺では左上の角の上が右下の角になり、上下に向かい合う 2i の配置になる
+        let klein = Topology::finite(Surface::KleinBottle, 8, 8);
+        let cells: Bitboard = [(3, 3), (-4, -3)].into_iter().collect();
+        assert!(step(&born, &cells, &klein).get(-4, -4));
+        assert!(!step(&not_born, &cells, &klein).get(-4, -4));
+        // 交差帽では右端の (3, 0) の右が上下を反転した左端の (-4, -1) になり、左右に向かい合う 2i の配置になる
+        let cross = Topology::finite(Surface::CrossSurface, 8, 8);
+        let cells: Bitboard = [(-4, -1), (2, 0)].into_iter().collect();
+        assert!(step(&born, &cells, &cross).get(3, 0));
+        assert!(!step(&not_born, &cells, &cross).get(3, 0));
+    }
+}
@@ -0,0 +1,24 @@
+//! ライフゲームのシミュレーションコア
+//!
+//! ワールドと世代計算エンジン、ルール、パターンファイル形式、セルの状態の解析を提供する。
+//! Bevyに依存しないため、ゲーム本体のほかコマンドラインツールやベンチマークからも使用できる。
+//! `bevy` フィーチャーを有効にすると `World` をそのままBevyのリソースとして使用でき、
+//! 世代計算の並列化にBevyのタスクプールを使う。
+
+pub mod age;
+pub mod analysis;
+pub mod bitboard;
+pub mod consts;
+pub mod engine;
+pub mod formats;
+pub mod generations;
+pub mod heatmap;
+pub mod isotropic;
+pub mod multistate;
+pub mod neighborhood;
+pub mod patterns;
+pub mod rect;
+pub mod rules;
+pub mod simulation;
+pub mod topology;
+pub mod world;
@@ -0,0 +1,425 @@
+//! 利用者定義の多状態セルオートマトン
+//!
+//! 状態0を空とし、各セルの次の状態が自身の状態とムーア近傍の状態ごとのセル数だけで決まる
+//! オートマトンを `MultiStateRule` で定義する。状態ごとの描画色もルールが持つ。
+//! ワールドでは状態1のセルを生存セルのビットボードに、状態2以上のセルを状態ごとのビットボード
+//! （`DecayingCells`）に持つため、Generations ルールと同じく描画・編集・巻き戻しができる。
+//! 組み込みのオートマトンはルール名で、それ以外は `Automaton::new` で包んで `Rule` として選択できる。
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use rustc_hash::FxHashMap;
+
+use crate::bitboard::Bitboard;
+use crate::consts::{MULTI_STATE_MAX_STATES, SQUARE_COORDINATES, WIREWORLD_PALETTE};
+use crate::generations::DecayingCells;
+use crate::topology::Topology;
+
+/// ムーア近傍の状態ごとのセル数（添字が状態）
+pub type NeighborCounts = [u8; MULTI_STATE_MAX_STATES as usize];
+
+/// 多状態セルオートマトンのルール
+pub trait MultiStateRule: Send + Sync {
+    /// ルール名を返す（ルール文字列として保存・解析にも使う）
+    fn name(&self) -> &'static str;
+
+    /// 空（状態0）を含む状態数を返す（`2`〜`MULTI_STATE_MAX_STATES`）
+    fn states(&self) -> u8;
+
+    /// 状態の表示名を返す
+    fn state_name(&self, state: u8) -> &'static str;
+
+    /// 状態の描画色（RGB）を返す
+    fn color(&self, state: u8) -> (u8, u8, u8);
+
+    /// 自身の状態と近傍の状態ごとのセル数から次の状態を返す
+    ///
+    /// 無限平面では空でないセルの周囲だけを計算するため、
+    /// 近傍が全て空の空のセルは空のままでなければならない。
+    fn next_state(&self, state: u8, neighbors: &NeighborCounts) -> u8;
+}
+
+/// Wireworld（空・電子の頭・電子の尾・導線の4状態）
+///
+/// 電子の頭は尾に、尾は導線に戻り、導線は近傍の電子の頭が1個か2個なら電子の頭になる。
+/// 導線に沿って電子が進むため、論理回路を組み立てられる。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Wireworld;
+
+impl Wireworld {
+    /// 空
+    pub const EMPTY: u8 = 0;
+    /// 電子の頭
+    pub const HEAD: u8 = 1;
+    /// 電子の尾
+    pub const TAIL: u8 = 2;
+    /// 導線
+    pub const CONDUCTOR: u8 = 3;
+}
+
+impl MultiStateRule for Wireworld {
+    fn name(&self) -> &'static str {
+        "Wireworld"
+    }
+
+    fn states(&self) -> u8 {
+        4
+    }
+
+    fn state_name(&self, state: u8) -> &'static str {
+        match state {
+            Self::HEAD => "Head",
+            Self::TAIL => "Tail",
+            Self::CONDUCTOR => "Wire",
+            _ => "Empty",
+        }
+    }
+
+    fn color(&self, state: u8) -> (u8, u8, u8) {
+        WIREWORLD_PALETTE[(state as usize).min(WIREWORLD_PALETTE.len() - 1)]
+    }
+
+    fn next_state(&self, state: u8, neighbors: &NeighborCounts) -> u8 {
+        match state {
+            Self::HEAD => Self::TAIL,
+            Self::TAIL => Self::CONDUCTOR,
+            Self::CONDUCTOR if matches!(neighbors[Self::HEAD as usize], 1 | 2) => Self::HEAD,
+            Self::CONDUCTOR => Self::CONDUCTOR,
+            _ => Self::EMPTY,
+        }
+    }
+}
+
+/// ルールで選択する多状態オートマトン
+///
+/// `MultiStateRule` を実装した `'static` な値を指し、ルール名で同一性を判定する。
+/// クレートの外で定義したオートマトンも `Automaton::new` で包めば `Rule` として使えるが、
+/// ルール文字列から名前で探せるのは `Automaton::all` に並べた組み込みのものだけである。
+#[derive(Clone, Copy)]
+pub struct Automaton(&'static dyn MultiStateRule);
+
+impl Automaton {
+    /// Wireworld
+    pub const WIREWORLD: Self = Self(&Wireworld);
+
+    /// 多状態オートマトンのルールを包む
+    pub const fn new(rule: &'static dyn MultiStateRule) -> Self {
+        Self(rule)
+    }
+
+    /// 組み込みのオートマトン一覧を返す
+    pub fn all() -> &'static [Automaton] {
+        &[Self::WIREWORLD]
+    }
+
+    /// オートマトンのルールを返す
+    pub fn rule(&self) -> &'static dyn MultiStateRule {
+        self.0
+    }
+
+    /// ルール名（大文字小文字は区別しない）から組み込みのオートマトンを探す
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|automaton| automaton.rule().name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl PartialEq for Automaton {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name() == other.0.name()
+    }
+}
+
+impl Eq for Automaton {}
+
+impl Hash for Automaton {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.name().hash(state);
+    }
+}
+
+impl fmt::Debug for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Automaton").field(&self.0.name()).finish()
+    }
+}
+
+/// 多状態オートマトンのセルをトポロジー上で1世代進める
+///
+/// `cells` は状態1、`others` は状態2以上のセルで、次世代も同じ形で返す。
+/// 空でないセルとその近傍だけを計算する。有限トポロジーでは領域の外周に貼り合わせ先のセルを
+/// 複製してから数え、結果を領域内に限る。
+pub fn step(
+    rule: &dyn MultiStateRule,
+    cells: &Bitboard,
+    others: &DecayingCells,
+    topology: &Topology,
+) -> (Bitboard, DecayingCells) {
+    let mut padded_cells = cells.clone();
+    let mut padded_others = others.clone();
+    for ((x, y), (sx, sy)) in topology.halo(1) {
+        if cells.get(sx, sy) {
+            padded_cells.set(x, y, true);
+        } else if let Some(state) = others.get(sx, sy) {
+            padded_others.set(x, y, Some(state));
+        }
+    }
+
+    let mut counts: FxHashMap<(i32, i32), NeighborCounts> = FxHashMap::default();
+    for ((x, y), state) in padded_cells
+        .iter()
+        .map(|cell| (cell, 1))
+        .chain(padded_others.iter())
+    {
+        if topology.contains(x, y) {
+            counts.entry((x, y)).or_default();
+        }
+        for &(dx, dy) in &SQUARE_COORDINATES {
+            let (nx, ny) = (x + dx as i32, y + dy as i32);
+            if topology.contains(nx, ny) {
+                counts.entry((nx, ny)).or_default()[state as usize] += 1;
+            }
+        }
+    }
+
+    let last_state = rule.states() - 1;
+    let mut next_cells = Bitboard::new();
+    let mut next_others = DecayingCells::new();
+    for (&(x, y), neighbors) in &mut counts {
+        let occupied_neighbors: u8 = neighbors[1..].iter().sum();
+        neighbors[0] = SQUARE_COORDINATES.len() as u8 - occupied_neighbors;
+        let state = if cells.get(x, y) {
+            1
+        } else {
+            others.get(x, y).unwrap_or(0)
+        };
+        match rule.next_state(state, neighbors).min(last_state) {
+            0 => {}
+            1 => {
+                next_cells.set(x, y, true);
+            }
+            state => {
+                next_others.set(x, y, Some(state));
+            }
+        }
+    }
+    (next_cells, next_others)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Surface;
+
+    /// 導線と電子の状態を1世代ずつ進める
+    fn run(
+        cells: &[((i32, i32), u8)],
+        generations: usize,
+        topology: &Topology,
+    ) -> (Bitboard, DecayingCells) {
+        let mut heads: Bitboard = cells
+            .iter()
+            .filter(|&&(_, state)| state == Wireworld::HEAD)
+            .map(|&(cell, _)| cell)
+            .collect();
+        let mut others: DecayingCells = cells.iter().copied().collect();
+        for _ in 0..generations {
+            (heads, others) = step(&Wireworld, &heads, &others, topology);
+        }
+        (heads, others)
+    }
+
+    /// 右向きの電子を先頭に置いた横一列の導線
+    fn wire(length: i32) -> Vec<((i32, i32), u8)> {
+        (0..length)
+            .map(|x| {
+                let state = match x {
+                    0 => Wireworld::TAIL,
+                    1 => Wireworld::HEAD,
+                    _ => Wireworld::CONDUCTOR,
+                };
+                ((x, 0), state)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn electron_moves_along_wire() {
+        let (heads, others) = run(&wire(8), 3, &Topology::Infinite);
+        assert_eq!(heads.iter().collect::<Vec<_>>(), vec![(4, 0)]);
+        assert_eq!(others.get(3, 0), Some(Wireworld::TAIL));
+        assert_eq!(others.get(0, 0), Some(Wireworld::CONDUCTOR));
+        assert_eq!(others.len(), 7);
+    }
+
+    #[test]
+    fn conductor_with_three_heads_stays_conductor() {
+        let cells = [
+            ((0, 0), Wireworld::HEAD),
+            ((0, 1), Wireworld::HEAD),
+            ((0, 2), Wireworld::HEAD),
+            ((1, 1), Wireworld::CONDUCTOR),
+        ];
+        let (heads, others) = run(&cells, 1, &Topology::Infinite);
+        assert!(heads.is_empty());
+        assert_eq!(others.get(1, 1), Some(Wireworld::CONDUCTOR));
+    }
+
+    #[test]
+    fn electron_wraps_around_torus() {
+        let topology = Topology::finite(Surface::Torus, 8, 8);
+        let rect = topology.bounds().unwrap();
+        let cells: Vec<_> = (rect.min.0..=rect.max.0)
+            .map(|x| {
+                let state = match x - rect.min.0 {
+                    0 => Wireworld::TAIL,
+                    1 => Wireworld::HEAD,
+                    _ => Wireworld::CONDUCTOR,
+                };
+                ((x, 0), state)
+            })
+            .collect();
+        let (heads, _) = run(&cells, 8, &topology);
+        assert_eq!(heads.iter().collect::<Vec<_>>(), vec![(rect.min.0 + 1, 0)]);
+    }
+
+    /// 領域内の全セルについて、近傍を貼り合わせ先から1つずつ読んで次の世代を求める
+    fn reference_step(
+        rule: &dyn MultiStateRule,
+        cells: &DecayingCells,
+        topology: &Topology,
+    ) -> DecayingCells {
+        let rect = topology.bounds().unwrap();
+        let mut next = DecayingCells::new();
+        for y in rect.min.1..=rect.max.1 {
+            for x in rect.min.0..=rect.max.0 {
+                let mut neighbors = NeighborCounts::default();
+                for &(dx, dy) in &SQUARE_COORDINATES {
+                    let state = topology
+                        .wrap(x + dx as i32, y + dy as i32)
+                        .and_then(|(nx, ny)| cells.get(nx, ny))
+                        .unwrap_or(0);
+                    neighbors[state as usize] += 1;
+                }
+                let state = rule.next_state(cells.get(x, y).unwrap_or(0), &neighbors);
+                if state != 0 {
+                    next.set(x, y, Some(state));
+                }
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn step_reads_neighbors_through_every_surface() {
+        let mut seed = 0x1234_5678_u32;
+        let mut soup = DecayingCells::new();
+        for y in -4..4 {
+            for x in -4..4 {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                // NOTE: 電子が動き続けるよう、導線を多めに敷く
+                let state = match seed % 10 {
+                    0 => Some(Wireworld::HEAD),
+                    1 => Some(Wireworld::TAIL),
+                    2..=7 => Some(Wireworld::CONDUCTOR),
+                    _ => None,
+                };
+                soup.set(x, y, state);
+            }
+        }
+        for &surface in Surface::all() {
+            let topology = Topology::finite(surface, 8, 8);
+            let mut expected = soup.clone();
+            let mut heads: Bitboard = soup
+                .iter()
+                .filter(|&(_, state)| state == Wireworld::HEAD)
+                .map(|(cell, _)| cell)
+                .collect();
+            let mut others = soup.clone();
+            for generation in 0..12 {
+                expected = reference_step(&Wireworld, &expected, &topology);
+                (heads, others) = step(&Wireworld, &heads, &others, &topology);
+                let mut actual: Vec<_> = heads
+                    .iter()
+                    .map(|cell| (cell, Wireworld::HEAD))
+                    .chain(others.iter().filter(|&(_, state)| state != Wireworld::HEAD))
+                    .collect();
+                let mut expected: Vec<_> = expected.iter().collect();
+                actual.sort_unstable();
+                expected.sort_unstable();
+                assert_eq!(actual, expected, "{surface:?} generation {generation}");
+            }
+        }
+    }
+
+    /// クレートの外で定義する想定の Brian's Brain（空・発火・不応の3状態）
+    struct BriansBrain;
+
+    impl MultiStateRule for BriansBrain {
+        fn name(&self) -> &'static str {
+            "BriansBrain"
+        }
+
+        fn states(&self) -> u8 {
+            3
+        }
+
+        fn state_name(&self, state: u8) -> &'static str {
+            ["Off", "Firing", "Refractory"][state as usize]
+        }
+
+        fn color(&self, state: u8) -> (u8, u8, u8) {
+            [(0, 0, 0), (255, 255, 255), (0, 0, 255)][state as usize]
+        }
+
+        fn next_state(&self, state: u8, neighbors: &NeighborCounts) -> u8 {
+            match state {
+                0 if neighbors[1] == 2 => 1,
+                1 => 2,
+                _ => 0,
+            }
+        }
+    }
+
+    #[test]
+    fn custom_automaton_becomes_rule() {
+        static BRIANS_BRAIN: BriansBrain = BriansBrain;
+        let automaton = Automaton::new(&BRIANS_BRAIN);
+        let rule = crate::rules::Rule::from_automaton(automaton);
+        assert_eq!(rule.states(), 3);
+        assert_eq!(rule.automaton(), Some(automaton));
+        assert_eq!(rule.multi_state().unwrap().name(), "BriansBrain");
+        assert_eq!(Automaton::from_name("BriansBrain"), None);
+        // 発火中の2セルは不応になり、両脇の空のセルが発火する
+        let cells: Bitboard = [(0, 0), (1, 0)].into_iter().collect();
+        let (firing, others) = step(
+            rule.multi_state().unwrap(),
+            &cells,
+            &DecayingCells::new(),
+            &Topology::Infinite,
+        );
+        let mut firing: Vec<_> = firing.iter().collect();
+        firing.sort_unstable();
+        assert_eq!(firing, vec![(0, -1), (0, 1), (1, -1), (1, 1)]);
+        assert_eq!(others.get(0, 0), Some(2));
+        assert_eq!(others.get(1, 0), Some(2));
+    }
+
+    #[test]
+    fn automaton_is_found_by_name() {
+        assert_eq!(
+            Automaton::from_name("wireworld"),
+            Some(Automaton::WIREWORLD)
+        );
+        assert_eq!(Automaton::from_name("B3/S23"), None);
+        let rule = Automaton::WIREWORLD.rule();
+        assert_eq!(rule.states(), 4);
+        assert_eq!(rule.state_name(Wireworld::CONDUCTOR), "Wire");
+        assert_ne!(rule.color(Wireworld::HEAD), rule.color(Wireworld::TAIL));
+    }
+}
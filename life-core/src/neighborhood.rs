@@ -0,0 +1,329 @@
+//! セルの近傍の形
+//!
+//! 誕生・生存の判定で数える近傍として、ムーア近傍（8セル）のほか
+//! フォン・ノイマン近傍（4セル）と六角形近傍（6セル）を扱う。
+//! Larger than Life ルールでは範囲（半径）を広げ、中心のセル自身を数えることもできる。
+//! 六角形近傍は奇数行を半セル右にずらしたオフセット行の配置で表すため、
+//! 行の偶奇によって近傍の相対座標が変わる。
+//! 標準のムーア近傍以外のルールは、生存セルの周囲を数え上げて世代を進める。
+
+use rustc_hash::FxHashMap;
+
+use crate::bitboard::Bitboard;
+use crate::consts::NEIGHBORHOOD_MAX_RANGE;
+use crate::rules::Rule;
+use crate::topology::Topology;
+
+/// 近傍の形
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NeighborhoodShape {
+    /// チェビシェフ距離が範囲内のセル（範囲1で周囲8セル）
+    #[default]
+    Moore,
+    /// マンハッタン距離が範囲内のセル（範囲1で上下左右の4セル）
+    VonNeumann,
+    /// 六角形格子上の距離が範囲内のセル（範囲1で周囲6セル）
+    Hexagonal,
+}
+
+impl NeighborhoodShape {
+    /// ルール文字列で使う記号を返す（`M` / `V` / `H`）
+    ///
+    /// Larger than Life 表記の `N` 欄では、フォン・ノイマン近傍を `N` で表す。
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Moore => 'M',
+            Self::VonNeumann => 'V',
+            Self::Hexagonal => 'H',
+        }
+    }
+
+    /// ルール文字列の記号（大文字小文字は区別しない）から近傍の形を探す
+    ///
+    /// `N` はフォン・ノイマン近傍として扱う。
+    pub fn from_symbol(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'M' => Some(Self::Moore),
+            'V' | 'N' => Some(Self::VonNeumann),
+            'H' => Some(Self::Hexagonal),
+            _ => None,
+        }
+    }
+
+    /// 範囲 `range` のときに中心から `(dx, dy)` のセルが近傍に含まれるかを返す
+    ///
+    /// 六角形近傍では中心の行が奇数行か（`odd_row`）で判定が変わる。中心のセル自身は含めない。
+    fn contains(&self, dx: i32, dy: i32, range: i32, odd_row: bool) -> bool {
+        if (dx, dy) == (0, 0) {
+            return false;
+        }
+        match self {
+            Self::Moore => dx.abs().max(dy.abs()) <= range,
+            Self::VonNeumann => dx.abs() + dy.abs() <= range,
+            Self::Hexagonal => {
+                // NOTE: オフセット行の座標を斜交座標に直して六角形格子上の距離を測る
+                let row_shift = |y: i32| (y + i32::from(odd_row)).div_euclid(2);
+                let dq = dx - (row_shift(dy) - row_shift(0));
+                (dq.abs() + dy.abs() + (dq + dy).abs()) / 2 <= range
+            }
+        }
+    }
+}
+
+/// 誕生・生存の判定で生存セルを数える近傍
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Neighborhood {
+    /// 近傍の形
+    shape: NeighborhoodShape,
+    /// 近傍の範囲（`1`〜`NEIGHBORHOOD_MAX_RANGE`）
+    range: u8,
+    /// 中心のセル自身を数えるか
+    center: bool,
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Self::MOORE
+    }
+}
+
+impl Neighborhood {
+    /// 標準のムーア近傍（範囲1、中心を含まない）
+    pub const MOORE: Self = Self {
+        shape: NeighborhoodShape::Moore,
+        range: 1,
+        center: false,
+    };
+
+    /// 指定した形と範囲の近傍を生成する（範囲は `1`〜`NEIGHBORHOOD_MAX_RANGE` に丸める）
+    pub fn new(shape: NeighborhoodShape, range: u8) -> Self {
+        Self {
+            shape,
+            range: range.clamp(1, NEIGHBORHOOD_MAX_RANGE),
+            center: false,
+        }
+    }
+
+    /// 中心のセル自身を数えるかを設定した近傍を返す
+    pub fn with_center(self, center: bool) -> Self {
+        Self { center, ..self }
+    }
+
+    /// 近傍の形を返す
+    pub fn shape(&self) -> NeighborhoodShape {
+        self.shape
+    }
+
+    /// 近傍の範囲を返す
+    pub fn range(&self) -> u8 {
+        self.range
+    }
+
+    /// 中心のセル自身を数えるかを返す
+    pub fn includes_center(&self) -> bool {
+        self.center
+    }
+
+    /// 標準のムーア近傍か（ビットボード・HashLife のエンジンで計算できるか）を返す
+    pub fn is_moore(&self) -> bool {
+        *self == Self::MOORE
+    }
+
+    /// 六角形近傍（奇数行を半セル右にずらして描画する）かを返す
+    pub fn is_hexagonal(&self) -> bool {
+        self.shape == NeighborhoodShape::Hexagonal
+    }
+
+    /// B/S表記の数字で近傍数を表せる、範囲1で中心を含まない近傍かを返す
+    pub fn is_outer_totalistic(&self) -> bool {
+        self.range == 1 && !self.center
+    }
+
+    /// 数えるセルの数（近傍数の最大値）を返す
+    pub fn size(&self) -> u16 {
+        (self.offsets(0).len() + usize::from(self.center)) as u16
+    }
+
+    /// 行 `y` のセルから見た近傍のセルの相対座標を返す（中心のセル自身は含めない）
+    ///
+    /// 六角形近傍以外では行によらず同じ座標になる。
+    pub fn offsets(&self, y: i32) -> Vec<(i32, i32)> {
+        let range = self.range as i32;
+        let odd_row = y.rem_euclid(2) == 1;
+        (-range..=range)
+            .flat_map(|dy| (-range..=range).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| self.shape.contains(dx, dy, range, odd_row))
+            .collect()
+    }
+}
+
+/// 標準のムーア近傍以外のルールで、生存セルをトポロジー上で1世代進める
+///
+/// 生存セルとその近傍だけを数え上げる。有限トポロジーでは領域の外周に近傍の範囲だけ
+/// 貼り合わせ先のセルを複製してから数え、結果を領域内に限る。
+pub fn step(rule: &Rule, cells: &Bitboard, topology: &Topology) -> Bitboard {
+    let neighborhood = rule.neighborhood();
+    let offsets = [neighborhood.offsets(0), neighborhood.offsets(1)];
+    let center = u16::from(neighborhood.includes_center());
+    let padded = topology.pad(cells, neighborhood.range() as i32);
+    let mut counts: FxHashMap<(i32, i32), u16> = FxHashMap::default();
+    for (x, y) in padded.iter() {
+        if topology.contains(x, y) {
+            *counts.entry((x, y)).or_default() += center;
+        }
+        for &(dx, dy) in &offsets[y.rem_euclid(2) as usize] {
+            let (nx, ny) = (x + dx, y + dy);
+            if topology.contains(nx, ny) {
+                *counts.entry((nx, ny)).or_default() += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|&((x, y), count)| rule.next_state(cells.get(x, y), count as usize))
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Surface;
+
+    fn sorted(cells: &Bitboard) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = cells.iter().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// 領域内の全セルについて、近傍を貼り合わせ先から1つずつ読んで次の世代を求める
+    fn reference_step(rule: &Rule, cells: &Bitboard, topology: &Topology) -> Bitboard {
+        let neighborhood = rule.neighborhood();
+        let rect = topology.bounds().unwrap();
+        let mut next = Bitboard::new();
+        for y in rect.min.1..=rect.max.1 {
+            for x in rect.min.0..=rect.max.0 {
+                let count = neighborhood
+                    .offsets(y)
+                    .into_iter()
+                    .filter_map(|(dx, dy)| topology.wrap(x + dx, y + dy))
+                    .filter(|&(nx, ny)| cells.get(nx, ny))
+                    .count()
+                    + usize::from(neighborhood.includes_center() && cells.get(x, y));
+                if rule.next_state(cells.get(x, y), count) {
+                    next.set(x, y, true);
+                }
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn neighborhood_sizes() {
+        use NeighborhoodShape::*;
+        assert_eq!(Neighborhood::MOORE.size(), 8);
+        assert_eq!(Neighborhood::new(VonNeumann, 1).size(), 4);
+        assert_eq!(Neighborhood::new(Hexagonal, 1).size(), 6);
+        assert_eq!(Neighborhood::new(Hexagonal, 2).size(), 18);
+        assert_eq!(Neighborhood::new(Moore, 5).with_center(true).size(), 121);
+        assert_eq!(Neighborhood::new(VonNeumann, 3).size(), 24);
+        assert_eq!(Neighborhood::new(Moore, 99).range(), NEIGHBORHOOD_MAX_RANGE);
+    }
+
+    #[test]
+    fn hexagonal_offsets_follow_row_parity() {
+        let hex = Neighborhood::new(NeighborhoodShape::Hexagonal, 1);
+        let mut even = hex.offsets(0);
+        even.sort_unstable();
+        assert_eq!(
+            even,
+            vec![(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0)]
+        );
+        let mut odd = hex.offsets(-1);
+        odd.sort_unstable();
+        assert_eq!(odd, vec![(-1, 0), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn moore_step_matches_bitboard() {
+        let rule = Rule::conway();
+        let cells: Bitboard = [(0, 0), (1, 0), (2, 0), (2, -1), (1, -2)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            sorted(&step(&rule, &cells, &Topology::Infinite)),
+            sorted(&cells.step(&rule))
+        );
+    }
+
+    #[test]
+    fn von_neumann_rule_grows_diamond() {
+        let rule = Rule::parse("B1/S01234V").unwrap();
+        let mut cells: Bitboard = [(0, 0)].into_iter().collect();
+        cells = step(&rule, &cells, &Topology::Infinite);
+        assert_eq!(
+            sorted(&cells),
+            vec![(-1, 0), (0, -1), (0, 0), (0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn center_is_counted_when_included() {
+        // 中心を含む近傍では、孤立したセルの近傍数は自身の1になる
+        let neighborhood = Neighborhood::new(NeighborhoodShape::Moore, 2).with_center(true);
+        let rule = Rule::new(&[], &[1]).with_neighborhood(neighborhood);
+        let cells: Bitboard = [(0, 0)].into_iter().collect();
+        assert_eq!(
+            sorted(&step(&rule, &cells, &Topology::Infinite)),
+            vec![(0, 0)]
+        );
+    }
+
+    #[test]
+    fn large_range_wraps_around_torus() {
+        let rule = Rule::parse("R2,C0,M0,S0..24,B1..1,NM").unwrap();
+        let torus = Topology::finite(Surface::Torus, 8, 8);
+        let rect = torus.bounds().unwrap();
+        let cells: Bitboard = [rect.min].into_iter().collect();
+        let next = step(&rule, &cells, &torus);
+        assert_eq!(next.len(), 25);
+        assert!(next.get(rect.max.0, rect.max.1));
+        assert!(next.iter().all(|(x, y)| torus.contains(x, y)));
+    }
+
+    #[test]
+    fn step_reads_neighbors_through_every_surface() {
+        let rules = [
+            Rule::parse("B2/S34H").unwrap(),
+            Rule::parse("B2/S013V").unwrap(),
+            Rule::parse("R2,C0,M1,S4..8,B5..7,NN").unwrap(),
+        ];
+        let mut seed = 0x9e37_79b9_u32;
+        let mut soup = Bitboard::new();
+        for y in -5..5 {
+            for x in -4..5 {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                if seed.is_multiple_of(3) {
+                    soup.set(x, y, true);
+                }
+            }
+        }
+        for rule in &rules {
+            for &surface in Surface::all() {
+                let topology = Topology::finite(surface, 9, 10);
+                let (mut expected, mut actual) = (soup.clone(), soup.clone());
+                for generation in 0..8 {
+                    expected = reference_step(rule, &expected, &topology);
+                    actual = step(rule, &actual, &topology);
+                    assert_eq!(
+                        sorted(&actual),
+                        sorted(&expected),
+                        "{rule} on {surface:?} generation {generation}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,479 @@
+//! ライフゲームの有名パターン定義
+//!
+//! メニュー画面からワールドに配置できるパターンのデータと、
+//! セル座標列を回転・反転する対称変換を提供する。
+//! Wireworld の回路の例も、状態付きのセルとして提供する。
+
+use crate::multistate::Wireworld;
+
+/// ライフゲームの有名パターン
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum LifePattern {
+    /// パターンなし（デフォルト）
+    #[default]
+    None,
+    /// グライダー（移動体）
+    Glider,
+    /// 軽量宇宙船（移動体）
+    Lwss,
+    /// パルサー（振動子・周期3）
+    Pulsar,
+    /// ゴスパーグライダー銃
+    GosperGliderGun,
+    /// Rペントミノ（メトセラ）
+    RPentomino,
+    /// エイコーン（メトセラ）
+    Acorn,
+}
+
+impl LifePattern {
+    /// パターンのセル座標（原点中心）を返す
+    pub fn cells(&self) -> &'static [(i32, i32)] {
+        match self {
+            Self::None => &[],
+            Self::Glider => &[(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)],
+            Self::Lwss => &[
+                (-1, -1),
+                (2, -1),
+                (-2, 0),
+                (-2, 1),
+                (2, 1),
+                (-2, 2),
+                (-1, 2),
+                (0, 2),
+                (1, 2),
+            ],
+            Self::Pulsar => {
+                // 周期3の振動子 — 4象限対称
+                &[
+                    // 上部（y=-6行）
+                    (-4, -6),
+                    (-3, -6),
+                    (-2, -6),
+                    (2, -6),
+                    (3, -6),
+                    (4, -6),
+                    // y=-4行
+                    (-6, -4),
+                    (-1, -4),
+                    (1, -4),
+                    (6, -4),
+                    // y=-3行
+                    (-6, -3),
+                    (-1, -3),
+                    (1, -3),
+                    (6, -3),
+                    // y=-2行
+                    (-6, -2),
+                    (-1, -2),
+                    (1, -2),
+                    (6, -2),
+                    // y=-1行
+                    (-4, -1),
+                    (-3, -1),
+                    (-2, -1),
+                    (2, -1),
+                    (3, -1),
+                    (4, -1),
+                    // y=1行
+                    (-4, 1),
+                    (-3, 1),
+                    (-2, 1),
+                    (2, 1),
+                    (3, 1),
+                    (4, 1),
+                    // y=2行
+                    (-6, 2),
+                    (-1, 2),
+                    (1, 2),
+                    (6, 2),
+                    // y=3行
+                    (-6, 3),
+                    (-1, 3),
+                    (1, 3),
+                    (6, 3),
+                    // y=4行
+                    (-6, 4),
+                    (-1, 4),
+                    (1, 4),
+                    (6, 4),
+                    // y=6行
+                    (-4, 6),
+                    (-3, 6),
+                    (-2, 6),
+                    (2, 6),
+                    (3, 6),
+                    (4, 6),
+                ]
+            }
+            Self::GosperGliderGun => &[
+                // 左ブロック
+                (0, 4),
+                (0, 5),
+                (1, 4),
+                (1, 5),
+                // 左部品
+                (10, 4),
+                (10, 5),
+                (10, 6),
+                (11, 3),
+                (11, 7),
+                (12, 2),
+                (12, 8),
+                (13, 2),
+                (13, 8),
+                (14, 5),
+                (15, 3),
+                (15, 7),
+                (16, 4),
+                (16, 5),
+                (16, 6),
+                (17, 5),
+                // 右部品
+                (20, 2),
+                (20, 3),
+                (20, 4),
+                (21, 2),
+                (21, 3),
+                (21, 4),
+                (22, 1),
+                (22, 5),
+                (24, 0),
+                (24, 1),
+                (24, 5),
+                (24, 6),
+                // 右ブロック
+                (34, 2),
+                (34, 3),
+                (35, 2),
+                (35, 3),
+            ],
+            Self::RPentomino => &[(0, -1), (1, -1), (-1, 0), (0, 0), (0, 1)],
+            Self::Acorn => &[(-3, 1), (-2, -1), (-2, 1), (0, 0), (1, 1), (2, 1), (3, 1)],
+        }
+    }
+
+    /// UI表示用ラベルを返す
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Glider => "Glider",
+            Self::Lwss => "LWSS",
+            Self::Pulsar => "Pulsar",
+            Self::GosperGliderGun => "Glider Gun",
+            Self::RPentomino => "R-pentomino",
+            Self::Acorn => "Acorn",
+        }
+    }
+
+    /// 全パターン一覧（None除外）を返す
+    pub fn all() -> &'static [LifePattern] {
+        &[
+            Self::Glider,
+            Self::Lwss,
+            Self::Pulsar,
+            Self::GosperGliderGun,
+            Self::RPentomino,
+            Self::Acorn,
+        ]
+    }
+}
+
+/// Wireworld の回路の例
+///
+/// いずれも左端の周期10のクロック（導線の輪を回る電子）から右へ信号を送る。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum WireworldCircuit {
+    /// 回路なし（デフォルト）
+    #[default]
+    None,
+    /// クロック（10世代ごとに右端へ電子を送り出す）
+    Clock,
+    /// ダイオード（クロックの電子を順方向に通す）
+    Diode,
+    /// 逆向きのダイオード（クロックの電子を通さない）
+    ReverseDiode,
+}
+
+impl WireworldCircuit {
+    /// 回路図を返す（`#` が導線、`H` が電子の頭、`t` が電子の尾、`.` が空）
+    fn diagram(&self) -> &'static [&'static str] {
+        match self {
+            Self::None => &[],
+            Self::Clock => &[".tH##.........", "#....#########", ".####........."],
+            Self::Diode => &[
+                ".tH##.....##.......",
+                "#....######.#######",
+                ".####.....##.......",
+            ],
+            Self::ReverseDiode => &[
+                ".tH##.....##.......",
+                "#....#####.########",
+                ".####.....##.......",
+            ],
+        }
+    }
+
+    /// 回路のセル座標（回路図の左上が原点）と Wireworld の状態を返す
+    pub fn cells(&self) -> Vec<((i32, i32), u8)> {
+        self.diagram()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars().enumerate().filter_map(move |(x, c)| {
+                    let state = match c {
+                        '#' => Wireworld::CONDUCTOR,
+                        'H' => Wireworld::HEAD,
+                        't' => Wireworld::TAIL,
+                        _ => return None,
+                    };
+                    Some(((x as i32, y as i32), state))
+                })
+            })
+            .collect()
+    }
+
+    /// UI表示用ラベルを返す
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Clock => "Clock",
+            Self::Diode => "Diode",
+            Self::ReverseDiode => "Diode (rev)",
+        }
+    }
+
+    /// 全回路一覧（None除外）を返す
+    pub fn all() -> &'static [WireworldCircuit] {
+        &[Self::Clock, Self::Diode, Self::ReverseDiode]
+    }
+}
+
+/// パターンの向きを変える対称変換
+///
+/// 座標系はY+が下方向（画面の見た目どおり）。
+/// 時計回り回転と左右反転の組み合わせで、正方形の8通りの対称をすべて作れる。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CellTransform {
+    /// 時計回りに90°回転
+    RotateClockwise,
+    /// 左右反転
+    FlipHorizontal,
+    /// 上下反転
+    FlipVertical,
+    /// 転置（左上〜右下の対角線で反転）
+    Transpose,
+}
+
+impl CellTransform {
+    /// 原点を中心に1セルの座標を変換する
+    pub fn apply(self, (x, y): (i32, i32)) -> (i32, i32) {
+        match self {
+            Self::RotateClockwise => (-y, x),
+            Self::FlipHorizontal => (-x, y),
+            Self::FlipVertical => (x, -y),
+            Self::Transpose => (y, x),
+        }
+    }
+
+    /// 原点を中心にセル座標列を変換する
+    pub fn apply_all(self, cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        cells.iter().map(|&cell| self.apply(cell)).collect()
+    }
+
+    /// 左上が原点の `width`×`height` の矩形内のセル座標列を変換する
+    ///
+    /// 変換後の矩形の左上が原点になるよう平行移動し、`(セル座標列, 幅, 高さ)` を返す。
+    /// 回転・転置では幅と高さが入れ替わる。
+    pub fn apply_in_box(
+        self,
+        cells: &[(i32, i32)],
+        width: u32,
+        height: u32,
+    ) -> (Vec<(i32, i32)>, u32, u32) {
+        let (w, h) = (width as i32, height as i32);
+        let map = |(x, y): (i32, i32)| match self {
+            Self::RotateClockwise => (h - 1 - y, x),
+            Self::FlipHorizontal => (w - 1 - x, y),
+            Self::FlipVertical => (x, h - 1 - y),
+            Self::Transpose => (y, x),
+        };
+        let cells = cells.iter().map(|&cell| map(cell)).collect();
+        match self {
+            Self::RotateClockwise | Self::Transpose => (cells, height, width),
+            Self::FlipHorizontal | Self::FlipVertical => (cells, width, height),
+        }
+    }
+}
+
+/// セル座標列を原点中心に時計回りに90°回転する
+pub fn rotate_clockwise(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    CellTransform::RotateClockwise.apply_all(cells)
+}
+
+/// セル座標列を原点中心に左右反転する
+pub fn flip_horizontal(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    CellTransform::FlipHorizontal.apply_all(cells)
+}
+
+/// セル座標列を原点中心に上下反転する
+pub fn flip_vertical(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    CellTransform::FlipVertical.apply_all(cells)
+}
+
+/// セル座標列を転置する（xとyを入れ替える）
+pub fn transpose(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    CellTransform::Transpose.apply_all(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// バウンディングボックスの左上を原点に平行移動してソートする
+    fn normalized(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let mut cells: Vec<_> = cells.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn all_returns_six_patterns() {
+        let patterns = LifePattern::all();
+        assert_eq!(patterns.len(), 6);
+        assert!(!patterns.contains(&LifePattern::None));
+    }
+
+    #[test]
+    fn glider_has_5_cells() {
+        assert_eq!(LifePattern::Glider.cells().len(), 5);
+    }
+
+    #[test]
+    fn lwss_has_9_cells() {
+        assert_eq!(LifePattern::Lwss.cells().len(), 9);
+    }
+
+    #[test]
+    fn pulsar_has_48_cells() {
+        assert_eq!(LifePattern::Pulsar.cells().len(), 48);
+    }
+
+    #[test]
+    fn gosper_glider_gun_has_36_cells() {
+        assert_eq!(LifePattern::GosperGliderGun.cells().len(), 36);
+    }
+
+    #[test]
+    fn r_pentomino_has_5_cells() {
+        assert_eq!(LifePattern::RPentomino.cells().len(), 5);
+    }
+
+    #[test]
+    fn acorn_has_7_cells() {
+        assert_eq!(LifePattern::Acorn.cells().len(), 7);
+    }
+
+    #[test]
+    fn labels_are_not_empty() {
+        for pattern in LifePattern::all() {
+            assert!(!pattern.label().is_empty(), "{:?} has empty label", pattern);
+        }
+    }
+
+    #[test]
+    fn none_cells_is_empty() {
+        assert_eq!(LifePattern::None.cells().len(), 0);
+    }
+
+    #[test]
+    fn default_is_none() {
+        assert_eq!(LifePattern::default(), LifePattern::None);
+    }
+
+    #[test]
+    fn circuits_have_one_electron_each() {
+        assert!(WireworldCircuit::None.cells().is_empty());
+        for circuit in WireworldCircuit::all() {
+            let cells = circuit.cells();
+            let count = |state| cells.iter().filter(|&&(_, s)| s == state).count();
+            assert_eq!(count(Wireworld::HEAD), 1, "{circuit:?}");
+            assert_eq!(count(Wireworld::TAIL), 1, "{circuit:?}");
+            assert!(!circuit.label().is_empty());
+        }
+    }
+
+    // --- 対称変換 ---
+
+    #[test]
+    fn rotate_clockwise_turns_right_into_down() {
+        // Y+が下方向なので、右向き(1,0)は下向き(0,1)になる
+        assert_eq!(rotate_clockwise(&[(1, 0), (0, 1)]), vec![(0, 1), (-1, 0)]);
+    }
+
+    #[test]
+    fn four_rotations_are_identity() {
+        let cells = LifePattern::RPentomino.cells();
+        let mut rotated = cells.to_vec();
+        for _ in 0..4 {
+            rotated = rotate_clockwise(&rotated);
+        }
+        assert_eq!(rotated, cells);
+    }
+
+    #[test]
+    fn flips_and_transpose_are_involutions() {
+        let cells = LifePattern::Acorn.cells();
+        assert_eq!(flip_horizontal(&flip_horizontal(cells)), cells);
+        assert_eq!(flip_vertical(&flip_vertical(cells)), cells);
+        assert_eq!(transpose(&transpose(cells)), cells);
+    }
+
+    #[test]
+    fn transpose_equals_rotate_then_flip_horizontal() {
+        let cells = LifePattern::Glider.cells();
+        assert_eq!(transpose(cells), flip_horizontal(&rotate_clockwise(cells)));
+    }
+
+    #[test]
+    fn rotate_and_flip_generate_all_eight_symmetries() {
+        // Rペントミノは対称性を持たないので8通りの向きがすべて異なる
+        let mut seen = HashSet::new();
+        let mut cells = LifePattern::RPentomino.cells().to_vec();
+        for _ in 0..4 {
+            seen.insert(normalized(&cells));
+            seen.insert(normalized(&flip_horizontal(&cells)));
+            cells = rotate_clockwise(&cells);
+        }
+        assert_eq!(seen.len(), 8);
+    }
+
+    #[test]
+    fn apply_in_box_keeps_cells_inside_box() {
+        // 3×2 の矩形: 左上と右下
+        let cells = [(0, 0), (2, 1)];
+        for transform in [
+            CellTransform::RotateClockwise,
+            CellTransform::FlipHorizontal,
+            CellTransform::FlipVertical,
+            CellTransform::Transpose,
+        ] {
+            let (moved, w, h) = transform.apply_in_box(&cells, 3, 2);
+            for &(x, y) in &moved {
+                assert!((0..w as i32).contains(&x) && (0..h as i32).contains(&y));
+            }
+            // 形は原点中心の変換と一致する
+            assert_eq!(
+                normalized(&moved),
+                normalized(&transform.apply_all(&cells)),
+                "{transform:?}"
+            );
+        }
+        let (rotated, w, h) = CellTransform::RotateClockwise.apply_in_box(&cells, 3, 2);
+        assert_eq!((w, h), (2, 3));
+        assert_eq!(rotated, vec![(1, 0), (0, 2)]);
+    }
+}
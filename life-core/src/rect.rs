@@ -0,0 +1,61 @@
+//! グリッド座標上の矩形
+
+/// グリッド座標上の矩形（両端を含む）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridRect {
+    /// 左上のセル座標
+    pub min: (i32, i32),
+    /// 右下のセル座標
+    pub max: (i32, i32),
+}
+
+impl GridRect {
+    /// 任意の2隅から矩形を生成する
+    pub fn from_corners(a: (i32, i32), b: (i32, i32)) -> Self {
+        Self {
+            min: (a.0.min(b.0), a.1.min(b.1)),
+            max: (a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    /// 左上のセル座標とサイズから矩形を生成する
+    pub fn from_origin_size(origin: (i32, i32), width: u32, height: u32) -> Self {
+        Self {
+            min: origin,
+            max: (
+                origin.0 + width.max(1) as i32 - 1,
+                origin.1 + height.max(1) as i32 - 1,
+            ),
+        }
+    }
+
+    /// 横方向のセル数
+    pub fn width(&self) -> u32 {
+        self.max.0.abs_diff(self.min.0) + 1
+    }
+
+    /// 縦方向のセル数
+    pub fn height(&self) -> u32 {
+        self.max.1.abs_diff(self.min.1) + 1
+    }
+
+    /// 指定座標のセルが矩形内にあるかを返す
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.min.0..=self.max.0).contains(&x) && (self.min.1..=self.max.1).contains(&y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_from_reversed_corners() {
+        let rect = GridRect::from_corners((3, -2), (-1, 4));
+        assert_eq!(rect.min, (-1, -2));
+        assert_eq!(rect.max, (3, 4));
+        assert_eq!((rect.width(), rect.height()), (5, 7));
+        assert!(rect.contains(3, -2));
+        assert!(!rect.contains(4, 0));
+    }
+}
@@ -0,0 +1,1129 @@
+//! Life-like セルオートマトンのルール定義
+//!
+//! `B36/S23`（B/S表記）と `23/36`（S/B表記）のルール文字列を解析し、
+//! 誕生・生存の判定に使用する `Rule` を提供する。
+//! 状態数を付けた Generations ルール（`B2/S/C3`、`/2/3`）も扱う。
+//! 末尾の `H` / `V` で六角形近傍・フォン・ノイマン近傍を指定でき（`B2/S34H`）、
+//! 範囲の広い近傍は Larger than Life 表記（`R5,C0,M1,S34..58,B34..45,NM`）で指定する。
+//! ムーア近傍では、近傍数に英字を付けて近傍の配置で誕生・生存を決める Hensel 表記（`B2-a/S12`）も扱う。
+//! `Wireworld` のようにルール名で指定する組み込みの多状態オートマトンも `Rule` として選択できる。
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::consts::NEIGHBORHOOD_MAX_RANGE;
+use crate::isotropic::{self, IsotropicTransitions};
+use crate::multistate::{Automaton, MultiStateRule};
+use crate::neighborhood::{Neighborhood, NeighborhoodShape};
+
+/// B/S表記の数字で表せる近傍数の最大値（ムーア近傍の8セル）
+const MAX_NEIGHBORS: u32 = 8;
+
+/// 近傍数の集合を保持するビット列の語数
+///
+/// 範囲 `NEIGHBORHOOD_MAX_RANGE` の中心を含むムーア近傍の近傍数（最大441）まで数えられる。
+const COUNT_MASK_WORDS: usize = 7;
+
+/// Generations ルールの状態数の最大値
+const MAX_STATES: u32 = 255;
+
+/// Life-like（外部総和型）ルール
+///
+/// 誕生・生存する隣接セル数をビットマスクで保持する。
+/// 例: コンウェイのライフゲームは `B3/S23`。
+/// 隣接セルを数える近傍は標準ではムーア近傍で、六角形近傍や範囲の広い近傍も指定できる。
+///
+/// 状態数が3以上の Generations ルールでは、生存条件を満たさなかったセルは
+/// 状態2から `states - 1` まで1世代ずつ衰退してから死亡する。
+/// 衰退中のセルは隣接数に数えられず、誕生もしない。
+///
+/// Hensel 表記のルールでは、誕生・生存を隣接数ではなく生存している隣接セルの配置で判定する。
+///
+/// 多状態オートマトンのルールでは誕生・生存の条件を持たず、世代計算はオートマトンに従う。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// 死亡セルが誕生する隣接数のビットマスク（bit n = 隣接n個）
+    birth: CountMask,
+    /// 生存セルが生存し続ける隣接数のビットマスク（bit n = 隣接n個）
+    survival: CountMask,
+    /// 死亡・生存・衰退中を合わせたセル状態の数（Life-like ルールは2）
+    states: u8,
+    /// 隣接セルを数える近傍
+    neighborhood: Neighborhood,
+    /// 隣接セルの配置で判定する遷移（隣接数だけで判定するルールは `None`）
+    isotropic: Option<IsotropicTransitions>,
+    /// 多状態オートマトン（Life-like・Generations ルールは `None`）
+    automaton: Option<Automaton>,
+}
+
+/// ルール文字列の解析エラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    /// 空文字列
+    Empty,
+    /// `B` / `S` の指定が欠けている、または重複している
+    MissingSection,
+    /// 近傍数として不正な文字（`0`〜`8` 以外、または近傍のセル数を超える数字）
+    InvalidDigit(char),
+    /// 近傍の範囲が `1`〜`NEIGHBORHOOD_MAX_RANGE` の範囲外、または数値でない
+    InvalidRange,
+    /// Larger than Life 表記の近傍数が近傍のセル数を超えている、または範囲の下限が上限を超えている
+    CountOutOfRange(u16),
+    /// `M` / `N` / `H` 以外の近傍の種類
+    UnknownNeighborhood(char),
+    /// 直前の近傍数の配置を表さない Hensel 表記の英字
+    InvalidConfiguration(char),
+    /// 無限平面では扱えない `B0` ルール
+    UnsupportedB0,
+    /// 状態数が `2`〜`255` の範囲外、または数値でない
+    InvalidStates,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "rule string is empty"),
+            Self::MissingSection => write!(f, "rule must contain exactly one B and one S section"),
+            Self::InvalidDigit(c) => write!(f, "invalid neighbor count '{c}'"),
+            Self::InvalidRange => {
+                write!(f, "neighborhood range must be 1-{NEIGHBORHOOD_MAX_RANGE}")
+            }
+            Self::CountOutOfRange(n) => {
+                write!(f, "neighbor count {n} is out of range for the neighborhood")
+            }
+            Self::UnknownNeighborhood(c) => {
+                write!(f, "unknown neighborhood '{c}' (expected M, N or H)")
+            }
+            Self::InvalidConfiguration(c) => {
+                write!(f, "invalid neighborhood configuration letter '{c}'")
+            }
+            Self::UnsupportedB0 => write!(f, "B0 rules are not supported on an infinite plane"),
+            Self::InvalidStates => write!(f, "number of states must be 2-{MAX_STATES}"),
+        }
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+impl Rule {
+    /// 誕生・生存する隣接数のリストからルールを生成する
+    ///
+    /// 8を超える値は無視する。無限平面で扱えない誕生の隣接数0（B0）は `parse` と同じく受け付けず、
+    /// デバッグビルドではパニックし、リリースビルドでは無視する。
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        Self::generations(birth, survival, 2)
+    }
+
+    /// 誕生・生存する隣接数のリストと状態数から Generations ルールを生成する
+    ///
+    /// 8を超える隣接数は無視し、状態数は `2`〜`255` に丸める。
+    /// 誕生の隣接数0（B0）は [`Rule::new`] と同じく受け付けない。
+    pub fn generations(birth: &[u8], survival: &[u8], states: u8) -> Self {
+        Self {
+            birth: to_birth_mask(birth),
+            survival: to_mask(survival),
+            states: states.max(2),
+            neighborhood: Neighborhood::MOORE,
+            isotropic: None,
+            automaton: None,
+        }
+    }
+
+    /// 隣接セルを数える近傍を変えたルールを返す
+    ///
+    /// 近傍のセル数を超える誕生・生存の条件は満たされない。
+    /// 隣接セルの配置による条件はムーア近傍でだけ保ち、それ以外の近傍では隣接数の条件だけが残る。
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Self {
+        Self {
+            neighborhood,
+            isotropic: self.isotropic.filter(|_| neighborhood.is_moore()),
+            ..self
+        }
+    }
+
+    /// 多状態オートマトンのルールを生成する
+    pub fn from_automaton(automaton: Automaton) -> Self {
+        Self {
+            birth: CountMask::EMPTY,
+            survival: CountMask::EMPTY,
+            states: automaton.rule().states(),
+            neighborhood: Neighborhood::MOORE,
+            isotropic: None,
+            automaton: Some(automaton),
+        }
+    }
+
+    /// コンウェイのライフゲーム（`B3/S23`）
+    pub const fn conway() -> Self {
+        Self {
+            birth: CountMask::from_bits(1 << 3),
+            survival: CountMask::from_bits((1 << 2) | (1 << 3)),
+            states: 2,
+            neighborhood: Neighborhood::MOORE,
+            isotropic: None,
+            automaton: None,
+        }
+    }
+
+    /// 死亡・生存・衰退中を合わせたセル状態の数を返す（Life-like ルールは2）
+    ///
+    /// 多状態オートマトンのルールでは、空を含むオートマトンの状態数を返す。
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// 衰退中の状態を持つ Generations ルールかを返す
+    pub fn is_generations(&self) -> bool {
+        self.automaton.is_none() && self.states > 2
+    }
+
+    /// 隣接セルを数える近傍を返す
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// 隣接セルの配置で判定する Hensel 表記のルールの遷移を返す（隣接数だけで判定するルールは `None`）
+    pub fn isotropic(&self) -> Option<&IsotropicTransitions> {
+        self.isotropic.as_ref()
+    }
+
+    /// 標準のムーア近傍の隣接数だけで判定するルール（ビットボード・HashLife のエンジンで計算できるか）を返す
+    pub fn is_moore_totalistic(&self) -> bool {
+        self.neighborhood.is_moore() && self.isotropic.is_none()
+    }
+
+    /// 多状態オートマトンを返す（Life-like・Generations ルールは `None`）
+    pub fn automaton(&self) -> Option<Automaton> {
+        self.automaton
+    }
+
+    /// 多状態オートマトンのルールを返す（Life-like・Generations ルールは `None`）
+    pub fn multi_state(&self) -> Option<&'static dyn MultiStateRule> {
+        self.automaton.map(|automaton| automaton.rule())
+    }
+
+    /// ルール文字列を解析する
+    ///
+    /// 以下の形式を受け付ける（大文字小文字は区別しない）:
+    /// - B/S表記: `B36/S23`, `S23/B36`, `B36S23`
+    /// - S/B表記: `23/36`（スラッシュの前が生存、後ろが誕生）
+    /// - Generations: `B2/S/C3`（B/S表記に状態数を付ける）, `/2/3`（S/B/C表記）
+    /// - 近傍の接尾辞: `B2/S34H`（六角形近傍）, `B1/S1V`（フォン・ノイマン近傍）, `B3/S23M`（ムーア近傍）
+    /// - Hensel 表記: `B2-a/S12`, `B3/S2-i34q`（ムーア近傍の近傍数に配置の英字を付ける）
+    /// - Larger than Life: `R5,C0,M1,S34..58,B34..45,NM`
+    ///   （範囲・状態数・中心を数えるか・生存と誕生の近傍数の範囲・近傍の種類）
+    /// - 多状態オートマトン: `Wireworld`（ルール名）
+    pub fn parse(s: &str) -> Result<Self, RuleParseError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+        if let Some(automaton) = Automaton::from_name(s) {
+            return Ok(Self::from_automaton(automaton));
+        }
+        if s.starts_with(['R', 'r']) && s.contains(',') {
+            return parse_larger_than_life(s);
+        }
+
+        let (s, shape) = split_neighborhood_suffix(s);
+        let neighborhood = Neighborhood::new(shape, 1);
+        let (birth, survival, states) = if s.contains(['B', 'b', 'S', 's']) {
+            parse_bs_notation(s)?
+        } else {
+            match s.split('/').collect::<Vec<_>>()[..] {
+                [survival, birth] => (birth.to_string(), survival.to_string(), 2),
+                [survival, birth, states] => (
+                    birth.to_string(),
+                    survival.to_string(),
+                    parse_states(states)?,
+                ),
+                _ => return Err(RuleParseError::MissingSection),
+            }
+        };
+
+        let (birth, survival, isotropic) = if neighborhood.is_moore() {
+            let birth = isotropic::parse_transitions(&birth)?;
+            let survival = isotropic::parse_transitions(&survival)?;
+            (
+                complete_counts(&birth),
+                complete_counts(&survival),
+                IsotropicTransitions::new(birth, survival),
+            )
+        } else {
+            let max = neighborhood.size();
+            (
+                parse_digits(&birth, max)?,
+                parse_digits(&survival, max)?,
+                None,
+            )
+        };
+        if birth.contains(0) {
+            return Err(RuleParseError::UnsupportedB0);
+        }
+        Ok(Self {
+            birth,
+            survival,
+            states,
+            neighborhood,
+            isotropic,
+            automaton: None,
+        })
+    }
+
+    /// 次世代のセル状態を決定する
+    ///
+    /// - 生存セル: 隣接数が生存条件に含まれれば生存
+    /// - 死亡セル: 隣接数が誕生条件に含まれれば誕生
+    ///
+    /// 中心のセル自身を数える近傍では、隣接数に生存している自身を含める。
+    /// Hensel 表記のルールでは、その隣接数の全ての配置で条件を満たす場合だけ真になる。
+    pub fn next_state(&self, alive: bool, alive_neighbor_count: usize) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        mask.contains(alive_neighbor_count)
+    }
+
+    /// ムーア近傍の生存している隣接セルの配置（`isotropic::CONFIGURATION_OFFSETS` の順のビット）から
+    /// 次世代のセル状態を決定する
+    ///
+    /// 隣接数だけで判定するルールでは、配置の隣接数で判定する。
+    pub fn next_state_with_configuration(&self, alive: bool, configuration: u8) -> bool {
+        match &self.isotropic {
+            Some(transitions) => transitions.next_state(alive, configuration),
+            None => self.next_state(alive, configuration.count_ones() as usize),
+        }
+    }
+
+    /// 指定した隣接数で死亡セルが誕生するかを返す
+    ///
+    /// Hensel 表記のルールでは、その隣接数の全ての配置で誕生する場合だけ真になる。
+    pub fn is_birth(&self, count: u16) -> bool {
+        self.birth.contains(count as usize)
+    }
+
+    /// 指定した隣接数で生存セルが生存し続けるかを返す
+    ///
+    /// Hensel 表記のルールでは、その隣接数の全ての配置で生存する場合だけ真になる。
+    pub fn is_survival(&self, count: u16) -> bool {
+        self.survival.contains(count as usize)
+    }
+
+    /// Life 1.05 などで使われる S/B 表記（`23/3`）の文字列を返す
+    ///
+    /// Generations ルールは状態数を付けた S/B/C 表記（`345/2/4`）になり、
+    /// ムーア近傍以外では末尾に近傍の接尾辞を付ける（`34/2H`）。
+    /// 多状態オートマトンはルール名を、範囲の広い近傍のルールは Larger than Life 表記を返す。
+    pub fn to_sb_notation(&self) -> String {
+        if let Some(rule) = self.multi_state() {
+            return rule.name().to_string();
+        }
+        if !self.neighborhood.is_outer_totalistic() {
+            return self.to_string();
+        }
+        let mut notation = format!("{}/{}", self.survival_digits(), self.birth_digits());
+        if self.is_generations() {
+            notation.push_str(&format!("/{}", self.states));
+        }
+        notation.push_str(neighborhood_suffix(self.neighborhood.shape()));
+        notation
+    }
+
+    /// 誕生の条件の数字列（Hensel 表記のルールは配置の英字付き）を返す
+    fn birth_digits(&self) -> String {
+        match &self.isotropic {
+            Some(transitions) => isotropic::format_transitions(transitions.birth()),
+            None => mask_to_digits(self.birth),
+        }
+    }
+
+    /// 生存の条件の数字列（Hensel 表記のルールは配置の英字付き）を返す
+    fn survival_digits(&self) -> String {
+        match &self.isotropic {
+            Some(transitions) => isotropic::format_transitions(transitions.survival()),
+            None => mask_to_digits(self.survival),
+        }
+    }
+
+    /// 該当するプリセットがあればそれを返す
+    pub fn preset(&self) -> Option<RulePreset> {
+        RulePreset::all()
+            .iter()
+            .copied()
+            .find(|p| p.rule() == *self)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// `B3/S23` 形式で出力する
+///
+/// Generations ルールは `B2/S/C3` 形式、ムーア近傍以外では末尾に近傍の接尾辞を付ける（`B2/S34H`）。
+/// 範囲の広い近傍や中心を数える近傍は Larger than Life 表記で、近傍数は最小〜最大の範囲で出力する。
+/// 多状態オートマトンはルール名を出力する。
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(rule) = self.multi_state() {
+            return write!(f, "{}", rule.name());
+        }
+        let neighborhood = self.neighborhood;
+        if !neighborhood.is_outer_totalistic() {
+            let states = if self.is_generations() {
+                self.states
+            } else {
+                0
+            };
+            let shape = match neighborhood.shape() {
+                NeighborhoodShape::VonNeumann => 'N',
+                shape => shape.symbol(),
+            };
+            return write!(
+                f,
+                "R{},C{states},M{},S{},B{},N{shape}",
+                neighborhood.range(),
+                u8::from(neighborhood.includes_center()),
+                mask_to_range(self.survival),
+                mask_to_range(self.birth)
+            );
+        }
+        write!(f, "B{}/S{}", self.birth_digits(), self.survival_digits())?;
+        if self.is_generations() {
+            write!(f, "/C{}", self.states)?;
+        }
+        write!(f, "{}", neighborhood_suffix(neighborhood.shape()))
+    }
+}
+
+/// 末尾の近傍の接尾辞（`H` / `V` / `M`）を取り除いた文字列と、近傍の形を返す
+///
+/// 接尾辞がなければムーア近傍とする。
+/// `N` は Hensel 表記の英字と区別できないため、接尾辞として扱わない。
+fn split_neighborhood_suffix(s: &str) -> (&str, NeighborhoodShape) {
+    let suffix = s.chars().last().filter(|c| !c.eq_ignore_ascii_case(&'N'));
+    match suffix.and_then(NeighborhoodShape::from_symbol) {
+        Some(shape) if s.len() > 1 => (s[..s.len() - 1].trim_end(), shape),
+        _ => (s, NeighborhoodShape::Moore),
+    }
+}
+
+/// 近傍の形に対応するルール文字列の接尾辞を返す（ムーア近傍は空文字列）
+fn neighborhood_suffix(shape: NeighborhoodShape) -> &'static str {
+    match shape {
+        NeighborhoodShape::Moore => "",
+        NeighborhoodShape::VonNeumann => "V",
+        NeighborhoodShape::Hexagonal => "H",
+    }
+}
+
+/// Larger than Life 表記（`R5,C0,M1,S34..58,B34..45,NM`）を解析する
+///
+/// `R`（範囲）と `S`・`B`（近傍数の範囲 `最小..最大`）は必須で、
+/// `C`（状態数、`0` は2状態）・`M`（中心を数えるなら `1`）・`N`（近傍の種類）は省略できる。
+fn parse_larger_than_life(s: &str) -> Result<Rule, RuleParseError> {
+    let mut fields: [Option<&str>; 6] = [None; 6];
+    for field in s.split(',') {
+        let field = field.trim();
+        let key = field.chars().next().ok_or(RuleParseError::MissingSection)?;
+        let index = "RCMSBN"
+            .find(key.to_ascii_uppercase())
+            .ok_or(RuleParseError::InvalidDigit(key))?;
+        if fields[index].replace(&field[key.len_utf8()..]).is_some() {
+            return Err(RuleParseError::MissingSection);
+        }
+    }
+    let [
+        Some(range),
+        states,
+        center,
+        Some(survival),
+        Some(birth),
+        shape,
+    ] = fields
+    else {
+        return Err(RuleParseError::MissingSection);
+    };
+
+    let range = range
+        .parse::<u8>()
+        .ok()
+        .filter(|range| (1..=NEIGHBORHOOD_MAX_RANGE).contains(range))
+        .ok_or(RuleParseError::InvalidRange)?;
+    let states = match states {
+        None | Some("0") => 2,
+        Some(states) => parse_states(states)?,
+    };
+    let center = match center {
+        None | Some("0") => false,
+        Some("1") => true,
+        Some(other) => {
+            let c = other.chars().find(|&c| c != '0').unwrap_or('?');
+            return Err(RuleParseError::InvalidDigit(c));
+        }
+    };
+    let shape = match shape {
+        None => NeighborhoodShape::Moore,
+        Some(symbol) => {
+            let mut chars = symbol.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => NeighborhoodShape::from_symbol(c)
+                    .ok_or(RuleParseError::UnknownNeighborhood(c))?,
+                (c, _) => return Err(RuleParseError::UnknownNeighborhood(c.unwrap_or('?'))),
+            }
+        }
+    };
+
+    let neighborhood = Neighborhood::new(shape, range).with_center(center);
+    let max = neighborhood.size();
+    let birth = parse_count_range(birth, max)?;
+    if birth.contains(0) {
+        return Err(RuleParseError::UnsupportedB0);
+    }
+    Ok(Rule {
+        birth,
+        survival: parse_count_range(survival, max)?,
+        states,
+        neighborhood,
+        isotropic: None,
+        automaton: None,
+    })
+}
+
+/// Larger than Life 表記の近傍数の範囲（`34..58`、`3`、空文字列）を解析する
+///
+/// 近傍数は `max` 以下でなければならない。
+fn parse_count_range(s: &str, max: u16) -> Result<CountMask, RuleParseError> {
+    if s.is_empty() {
+        return Ok(CountMask::EMPTY);
+    }
+    let parse_count = |digits: &str| {
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(RuleParseError::InvalidDigit(c));
+        }
+        match digits.parse::<u16>() {
+            Ok(count) if count <= max => Ok(count),
+            Ok(count) => Err(RuleParseError::CountOutOfRange(count)),
+            Err(_) => Err(RuleParseError::CountOutOfRange(u16::MAX)),
+        }
+    };
+    let (low, high) = match s.split_once("..") {
+        Some((low, high)) => (parse_count(low)?, parse_count(high)?),
+        None => {
+            let count = parse_count(s)?;
+            (count, count)
+        }
+    };
+    if low > high {
+        return Err(RuleParseError::CountOutOfRange(low));
+    }
+    Ok(CountMask::from_counts(low..=high))
+}
+
+/// B/S表記（`B36/S23`, `S23/B36`, `B36S23`, `B2/S/C3`）を誕生・生存の条件の文字列と状態数に分ける
+///
+/// `B` / `S` の中の `C` は Hensel 表記の英字とし、`/` の後の `C` だけを状態数とする。
+fn parse_bs_notation(s: &str) -> Result<(String, String, u8), RuleParseError> {
+    let mut birth: Option<String> = None;
+    let mut survival: Option<String> = None;
+    let mut states: Option<String> = None;
+    let mut current: Option<&mut String> = None;
+
+    for c in s.chars() {
+        match c.to_ascii_uppercase() {
+            'B' => {
+                if birth.is_some() {
+                    return Err(RuleParseError::MissingSection);
+                }
+                current = Some(birth.insert(String::new()));
+            }
+            'S' => {
+                if survival.is_some() {
+                    return Err(RuleParseError::MissingSection);
+                }
+                current = Some(survival.insert(String::new()));
+            }
+            'C' if current.is_none() => {
+                if states.is_some() {
+                    return Err(RuleParseError::MissingSection);
+                }
+                current = Some(states.insert(String::new()));
+            }
+            '/' => current = None,
+            _ => match current.as_mut() {
+                Some(digits) => digits.push(c),
+                None => return Err(RuleParseError::InvalidDigit(c)),
+            },
+        }
+    }
+
+    let states = states.as_deref().map_or(Ok(2), parse_states)?;
+    match (birth, survival) {
+        (Some(b), Some(s)) => Ok((b, s, states)),
+        _ => Err(RuleParseError::MissingSection),
+    }
+}
+
+/// 状態数の数字列を解析する
+fn parse_states(digits: &str) -> Result<u8, RuleParseError> {
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(RuleParseError::InvalidDigit(c));
+    }
+    digits
+        .parse::<u32>()
+        .ok()
+        .filter(|states| (2..=MAX_STATES).contains(states))
+        .map(|states| states as u8)
+        .ok_or(RuleParseError::InvalidStates)
+}
+
+/// 近傍数の数字列（各数字は `max` 以下）をビットマスクに変換する
+fn parse_digits(digits: &str, max: u16) -> Result<CountMask, RuleParseError> {
+    digits
+        .chars()
+        .try_fold(CountMask::EMPTY, |mask, c| match c.to_digit(10) {
+            Some(n) if n <= max as u32 => Ok(mask.with(n as usize)),
+            _ => Err(RuleParseError::InvalidDigit(c)),
+        })
+}
+
+/// 配置の集合のうち、全ての配置を含む近傍数のビットマスクを返す
+fn complete_counts(set: &isotropic::ConfigurationSet) -> CountMask {
+    CountMask::from_counts(
+        (0..=MAX_NEIGHBORS)
+            .filter(|&n| set.contains_count(n))
+            .map(|n| n as u16),
+    )
+}
+
+/// 近傍数のリストをビットマスクに変換する
+fn to_mask(counts: &[u8]) -> CountMask {
+    CountMask::from_counts(
+        counts
+            .iter()
+            .filter(|&&n| n as u32 <= MAX_NEIGHBORS)
+            .map(|&n| n as u16),
+    )
+}
+
+/// 誕生する近傍数のリストをビットマスクに変換する
+///
+/// 無限平面で扱えない0（B0）はデバッグビルドではパニックし、リリースビルドでは無視する。
+fn to_birth_mask(counts: &[u8]) -> CountMask {
+    debug_assert!(
+        !counts.contains(&0),
+        "B0 rules are not supported on an infinite plane"
+    );
+    CountMask::from_counts(
+        counts
+            .iter()
+            .filter(|&&n| n != 0 && n as u32 <= MAX_NEIGHBORS)
+            .map(|&n| n as u16),
+    )
+}
+
+/// ビットマスクを昇順の数字列に変換する
+fn mask_to_digits(mask: CountMask) -> String {
+    mask.counts()
+        .map(|n| char::from_digit(n as u32, 10).unwrap_or('?'))
+        .collect()
+}
+
+/// ビットマスクを Larger than Life 表記の `最小..最大` に変換する（空なら空文字列）
+fn mask_to_range(mask: CountMask) -> String {
+    match (mask.counts().next(), mask.counts().last()) {
+        (Some(min), Some(max)) => format!("{min}..{max}"),
+        _ => String::new(),
+    }
+}
+
+/// 誕生・生存する近傍数の集合（bit n = 近傍n個）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct CountMask([u64; COUNT_MASK_WORDS]);
+
+impl CountMask {
+    /// 空の集合
+    const EMPTY: Self = Self([0; COUNT_MASK_WORDS]);
+
+    /// 近傍数0〜63のビットマスクから集合を生成する
+    const fn from_bits(bits: u64) -> Self {
+        let mut words = [0; COUNT_MASK_WORDS];
+        words[0] = bits;
+        Self(words)
+    }
+
+    /// 近傍数の列から集合を生成する（数えられる最大値を超える近傍数は無視する）
+    fn from_counts(counts: impl IntoIterator<Item = u16>) -> Self {
+        counts
+            .into_iter()
+            .fold(Self::EMPTY, |mask, n| mask.with(n as usize))
+    }
+
+    /// 近傍数 `n` を加えた集合を返す
+    fn with(mut self, n: usize) -> Self {
+        if let Some(word) = self.0.get_mut(n / 64) {
+            *word |= 1 << (n % 64);
+        }
+        self
+    }
+
+    /// 近傍数 `n` を含むかを返す
+    fn contains(&self, n: usize) -> bool {
+        self.0
+            .get(n / 64)
+            .is_some_and(|word| word >> (n % 64) & 1 == 1)
+    }
+
+    /// 含まれる近傍数を昇順に列挙する
+    fn counts(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..COUNT_MASK_WORDS * 64)
+            .filter(|&n| self.contains(n))
+            .map(|n| n as u16)
+    }
+}
+
+/// 有名な Life-like ルールのプリセット
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum RulePreset {
+    /// コンウェイのライフゲーム
+    #[default]
+    Conway,
+    /// HighLife（自己複製パターンを持つ）
+    HighLife,
+    /// Day & Night（生死反転対称）
+    DayAndNight,
+    /// Seeds（全セルが毎世代死亡する爆発型）
+    Seeds,
+    /// Life without Death（セルが死なない）
+    LifeWithoutDeath,
+    /// Maze（迷路状に成長する）
+    Maze,
+    /// Replicator（全パターンが自己複製する）
+    Replicator,
+    /// 2x2（2x2ブロック単位で振る舞う）
+    TwoByTwo,
+    /// Brian's Brain（3状態の Generations、全てが移動物体になりやすい）
+    BriansBrain,
+    /// Star Wars（4状態の Generations、衰退中のセルが壁になる）
+    StarWars,
+    /// Wireworld（導線を電子が進む4状態のオートマトン）
+    Wireworld,
+    /// 六角形近傍の B2/S34
+    HexLife,
+    /// Bosco's Rule（範囲5の Larger than Life、滑らかな移動物体を持つ）
+    Bosco,
+    /// tlife（Hensel 表記、生存条件が隣接セルの配置で変わる）
+    Tlife,
+}
+
+impl RulePreset {
+    /// ルール文字列（B/S表記）を返す
+    pub fn rulestring(&self) -> &'static str {
+        match self {
+            Self::Conway => "B3/S23",
+            Self::HighLife => "B36/S23",
+            Self::DayAndNight => "B3678/S34678",
+            Self::Seeds => "B2/S",
+            Self::LifeWithoutDeath => "B3/S012345678",
+            Self::Maze => "B3/S12345",
+            Self::Replicator => "B1357/S1357",
+            Self::TwoByTwo => "B36/S125",
+            Self::BriansBrain => "B2/S/C3",
+            Self::StarWars => "B2/S345/C4",
+            Self::Wireworld => "Wireworld",
+            Self::HexLife => "B2/S34H",
+            Self::Bosco => "R5,C0,M1,S34..58,B34..45,NM",
+            Self::Tlife => "B3/S2-i34q",
+        }
+    }
+
+    /// UI表示用ラベルを返す
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Conway => "Conway",
+            Self::HighLife => "HighLife",
+            Self::DayAndNight => "Day & Night",
+            Self::Seeds => "Seeds",
+            Self::LifeWithoutDeath => "No Death",
+            Self::Maze => "Maze",
+            Self::Replicator => "Replicator",
+            Self::TwoByTwo => "2x2",
+            Self::BriansBrain => "Brian's Brain",
+            Self::StarWars => "Star Wars",
+            Self::Wireworld => "Wireworld",
+            Self::HexLife => "Hex Life",
+            Self::Bosco => "Bosco",
+            Self::Tlife => "tlife",
+        }
+    }
+
+    /// プリセットのルールを返す
+    pub fn rule(&self) -> Rule {
+        // プリセット文字列はテストで解析可能なことを保証している
+        Rule::parse(self.rulestring()).unwrap_or_default()
+    }
+
+    /// 全プリセット一覧を返す
+    pub fn all() -> &'static [RulePreset] {
+        &[
+            Self::Conway,
+            Self::HighLife,
+            Self::DayAndNight,
+            Self::Seeds,
+            Self::LifeWithoutDeath,
+            Self::Maze,
+            Self::Replicator,
+            Self::TwoByTwo,
+            Self::BriansBrain,
+            Self::StarWars,
+            Self::Wireworld,
+            Self::HexLife,
+            Self::Bosco,
+            Self::Tlife,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_conway() {
+        assert_eq!(Rule::default(), Rule::conway());
+    }
+
+    #[test]
+    fn conway_transitions() {
+        let rule = Rule::conway();
+        assert!(rule.next_state(true, 2));
+        assert!(rule.next_state(true, 3));
+        assert!(!rule.next_state(true, 1));
+        assert!(!rule.next_state(true, 4));
+        assert!(rule.next_state(false, 3));
+        assert!(!rule.next_state(false, 2));
+    }
+
+    #[test]
+    fn parse_bs_notation() {
+        assert_eq!(Rule::parse("B3/S23"), Ok(Rule::conway()));
+        assert_eq!(Rule::parse("b36/s23"), Ok(Rule::new(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn parse_bs_notation_without_slash() {
+        assert_eq!(Rule::parse("B36S23"), Ok(Rule::new(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn parse_sb_order_with_letters() {
+        assert_eq!(Rule::parse("S23/B3"), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn parse_sb_notation() {
+        // スラッシュの前が生存、後ろが誕生
+        assert_eq!(Rule::parse("23/3"), Ok(Rule::conway()));
+        assert_eq!(Rule::parse("23/36"), Ok(Rule::new(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn parse_empty_survival() {
+        let seeds = Rule::parse("B2/S").unwrap();
+        assert!(seeds.next_state(false, 2));
+        assert!(!seeds.next_state(true, 2));
+    }
+
+    #[test]
+    fn parse_trims_whitespace() {
+        assert_eq!(Rule::parse("  B3/S23 \n"), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn parse_rejects_empty() {
+        assert_eq!(Rule::parse(""), Err(RuleParseError::Empty));
+    }
+
+    #[test]
+    fn parse_rejects_digit_9() {
+        assert_eq!(
+            Rule::parse("B39/S23"),
+            Err(RuleParseError::InvalidDigit('9'))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_missing_section() {
+        assert_eq!(Rule::parse("B3"), Err(RuleParseError::MissingSection));
+        assert_eq!(Rule::parse("233"), Err(RuleParseError::MissingSection));
+    }
+
+    #[test]
+    fn parse_rejects_duplicate_section() {
+        assert_eq!(
+            Rule::parse("B3/B6/S23"),
+            Err(RuleParseError::MissingSection)
+        );
+    }
+
+    #[test]
+    fn parse_rejects_b0() {
+        assert_eq!(Rule::parse("B03/S23"), Err(RuleParseError::UnsupportedB0));
+    }
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        should_panic(expected = "B0 rules are not supported")
+    )]
+    fn constructors_reject_b0() {
+        // NOTE: リリースビルドでは B0 を無視した残りのルールになる
+        assert_eq!(Rule::new(&[0, 3], &[2, 3]), Rule::new(&[3], &[2, 3]));
+        assert_eq!(
+            Rule::generations(&[0, 2], &[], 3),
+            Rule::generations(&[2], &[], 3)
+        );
+    }
+
+    #[test]
+    fn display_uses_bs_notation() {
+        assert_eq!(Rule::conway().to_string(), "B3/S23");
+        assert_eq!(Rule::parse("23/63").unwrap().to_string(), "B36/S23");
+    }
+
+    #[test]
+    fn sb_notation_roundtrip() {
+        assert_eq!(Rule::conway().to_sb_notation(), "23/3");
+        for preset in RulePreset::all() {
+            let rule = preset.rule();
+            assert_eq!(Rule::parse(&rule.to_sb_notation()), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        for preset in RulePreset::all() {
+            let rule = preset.rule();
+            assert_eq!(Rule::parse(&rule.to_string()), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn all_presets_parse() {
+        for preset in RulePreset::all() {
+            assert!(
+                Rule::parse(preset.rulestring()).is_ok(),
+                "{:?} has invalid rulestring",
+                preset
+            );
+        }
+    }
+
+    #[test]
+    fn preset_lookup() {
+        assert_eq!(Rule::conway().preset(), Some(RulePreset::Conway));
+        assert_eq!(
+            Rule::parse("B36/S23").unwrap().preset(),
+            Some(RulePreset::HighLife)
+        );
+        assert_eq!(Rule::parse("B34/S34").unwrap().preset(), None);
+    }
+
+    #[test]
+    fn highlife_births_on_6() {
+        let rule = RulePreset::HighLife.rule();
+        assert!(rule.next_state(false, 6));
+        assert!(!Rule::conway().next_state(false, 6));
+    }
+
+    #[test]
+    fn parse_generations_notation() {
+        let brain = Rule::generations(&[2], &[], 3);
+        assert_eq!(Rule::parse("B2/S/C3"), Ok(brain));
+        assert_eq!(Rule::parse("b2/s/c3"), Ok(brain));
+        assert_eq!(Rule::parse("/2/3"), Ok(brain));
+        assert_eq!(
+            Rule::parse("345/2/4"),
+            Ok(Rule::generations(&[2], &[3, 4, 5], 4))
+        );
+        assert!(brain.is_generations());
+        assert_eq!(brain.states(), 3);
+        // 2状態の Generations は Life-like ルールと同じ
+        assert_eq!(Rule::parse("B3/S23/C2"), Ok(Rule::conway()));
+        assert_eq!(Rule::parse("23/3/2"), Ok(Rule::conway()));
+        assert!(!Rule::conway().is_generations());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_states() {
+        assert_eq!(Rule::parse("B2/S/C1"), Err(RuleParseError::InvalidStates));
+        assert_eq!(Rule::parse("B2/S/C256"), Err(RuleParseError::InvalidStates));
+        assert_eq!(Rule::parse("B2/S/C"), Err(RuleParseError::InvalidStates));
+        assert_eq!(Rule::parse("/2/x"), Err(RuleParseError::InvalidDigit('x')));
+        assert_eq!(
+            Rule::parse("B2/S/C3/C4"),
+            Err(RuleParseError::MissingSection)
+        );
+        assert_eq!(Rule::parse("1/2/3/4"), Err(RuleParseError::MissingSection));
+    }
+
+    #[test]
+    fn generations_notation_roundtrip() {
+        let star_wars = RulePreset::StarWars.rule();
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        assert_eq!(star_wars.to_sb_notation(), "345/2/4");
+        assert_eq!(Rule::parse(&star_wars.to_sb_notation()), Ok(star_wars));
+    }
+
+    #[test]
+    fn automaton_rule_is_named() {
+        let wireworld = Rule::parse(" WireWorld ").unwrap();
+        assert_eq!(wireworld, RulePreset::Wireworld.rule());
+        assert_eq!(wireworld.automaton(), Some(Automaton::WIREWORLD));
+        assert_eq!(wireworld.to_string(), "Wireworld");
+        assert_eq!(wireworld.to_sb_notation(), "Wireworld");
+        assert_eq!(wireworld.states(), 4);
+        assert!(!wireworld.is_generations());
+        assert!(Rule::conway().multi_state().is_none());
+    }
+
+    #[test]
+    fn parse_neighborhood_suffix() {
+        let hex = Rule::parse("B2/S34H").unwrap();
+        assert_eq!(hex, RulePreset::HexLife.rule());
+        assert_eq!(hex.neighborhood().shape(), NeighborhoodShape::Hexagonal);
+        assert_eq!(hex.to_string(), "B2/S34H");
+        assert_eq!(hex.to_sb_notation(), "34/2H");
+        assert_eq!(Rule::parse("34/2h"), Ok(hex));
+        let von_neumann = Rule::parse("B13/S012V").unwrap();
+        assert_eq!(von_neumann.neighborhood().size(), 4);
+        assert_eq!(Rule::parse("B3/S23M"), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn parse_rejects_count_beyond_neighborhood() {
+        assert_eq!(
+            Rule::parse("B27/S34H"),
+            Err(RuleParseError::InvalidDigit('7'))
+        );
+        assert_eq!(
+            Rule::parse("B5/S1V"),
+            Err(RuleParseError::InvalidDigit('5'))
+        );
+    }
+
+    #[test]
+    fn parse_larger_than_life_notation() {
+        let bosco = Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+        assert_eq!(bosco, RulePreset::Bosco.rule());
+        let neighborhood = bosco.neighborhood();
+        assert_eq!(neighborhood.range(), 5);
+        assert!(neighborhood.includes_center());
+        assert_eq!(neighborhood.size(), 121);
+        assert!(bosco.is_birth(34) && bosco.is_birth(45) && !bosco.is_birth(46));
+        assert!(bosco.is_survival(58) && !bosco.is_survival(33));
+        assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+        assert_eq!(bosco.to_sb_notation(), bosco.to_string());
+        let generations = Rule::parse("r2,c3,m0,s2..4,b3,nn").unwrap();
+        assert_eq!(generations.states(), 3);
+        assert_eq!(generations.to_string(), "R2,C3,M0,S2..4,B3..3,NN");
+    }
+
+    #[test]
+    fn parse_rejects_invalid_larger_than_life() {
+        assert_eq!(
+            Rule::parse("R0,C0,M0,S1..2,B3..3,NM"),
+            Err(RuleParseError::InvalidRange)
+        );
+        assert_eq!(
+            Rule::parse("R1,C0,M0,S1..9,B3..3,NM"),
+            Err(RuleParseError::CountOutOfRange(9))
+        );
+        assert_eq!(
+            Rule::parse("R2,C0,M0,S1..2,B3..3,NX"),
+            Err(RuleParseError::UnknownNeighborhood('X'))
+        );
+        assert_eq!(
+            Rule::parse("R2,C0,M0,S1..2"),
+            Err(RuleParseError::MissingSection)
+        );
+        assert_eq!(
+            Rule::parse("R2,C0,M0,S1..2,B0..3,NM"),
+            Err(RuleParseError::UnsupportedB0)
+        );
+    }
+
+    #[test]
+    fn parse_hensel_notation() {
+        let rule = Rule::parse("B2-a/S12").unwrap();
+        assert!(rule.isotropic().is_some());
+        assert!(!rule.is_moore_totalistic());
+        assert!(!rule.is_birth(2));
+        assert!(rule.is_survival(1) && rule.is_survival(2));
+        assert_eq!(rule.to_string(), "B2-a/S12");
+        assert_eq!(rule.to_sb_notation(), "12/2-a");
+        assert_eq!(Rule::parse("12/2ceikn"), Ok(rule));
+        assert_eq!(Rule::parse("b2CEIKN/s12"), Ok(rule));
+
+        let tlife = RulePreset::Tlife.rule();
+        assert_eq!(tlife.to_string(), "B3/S2-i34q");
+        assert!(tlife.is_birth(3) && tlife.is_survival(3) && !tlife.is_survival(2));
+        // 英字が近傍の接尾辞と重ならない
+        assert_eq!(
+            Rule::parse("B2n/S").unwrap().neighborhood(),
+            Neighborhood::MOORE
+        );
+    }
+
+    #[test]
+    fn hensel_with_every_letter_is_totalistic() {
+        let rule = Rule::parse("B3cekainyqjr/S2cekain3").unwrap();
+        assert_eq!(rule, Rule::conway());
+        assert!(rule.isotropic().is_none());
+        assert!(rule.is_moore_totalistic());
+    }
+
+    #[test]
+    fn hensel_generations_roundtrip() {
+        let rule = Rule::parse("B2-a/S12/C3").unwrap();
+        assert_eq!(rule.states(), 3);
+        assert!(rule.isotropic().is_some());
+        assert_eq!(rule.to_string(), "B2-a/S12/C3");
+        assert_eq!(rule.to_sb_notation(), "12/2-a/3");
+        assert_eq!(Rule::parse("12/2-a/3"), Ok(rule));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_hensel_letters() {
+        assert_eq!(
+            Rule::parse("B2x/S12"),
+            Err(RuleParseError::InvalidConfiguration('x'))
+        );
+        assert_eq!(
+            Rule::parse("B3/S1k"),
+            Err(RuleParseError::InvalidConfiguration('k'))
+        );
+        assert_eq!(
+            Rule::parse("B2a/S34H"),
+            Err(RuleParseError::InvalidDigit('a'))
+        );
+        assert_eq!(
+            Rule::parse("B0c/S"),
+            Err(RuleParseError::InvalidConfiguration('c'))
+        );
+    }
+
+    #[test]
+    fn next_state_out_of_range_is_dead() {
+        let rule = RulePreset::LifeWithoutDeath.rule();
+        assert!(!rule.next_state(true, 9));
+    }
+}
@@ -0,0 +1,106 @@
+//! ライフゲームのシミュレーションロジック（純粋関数）
+//!
+//! Bevyに依存しない純粋な計算ロジックを提供する。
+
+use crate::rules::Rule;
+
+/// ルールに基づき次世代のセル状態を決定する
+///
+/// - 生存セル: 隣接数が生存条件（`S`）に含まれれば生存、それ以外は死亡
+/// - 死亡セル: 隣接数が誕生条件（`B`）に含まれれば誕生
+pub fn next_cell_state(rule: &Rule, alive: bool, alive_neighbor_count: usize) -> bool {
+    rule.next_state(alive, alive_neighbor_count)
+}
+
+/// ルールに基づき、ムーア近傍の生存している隣接セルの配置から次世代のセル状態を決定する
+///
+/// 配置は `isotropic::CONFIGURATION_OFFSETS` の順に隣接セルの生死を並べた8ビットのマスクで、
+/// Hensel 表記のルールでは隣接数が同じでも配置によって結果が変わる。
+pub fn next_cell_state_with_configuration(rule: &Rule, alive: bool, configuration: u8) -> bool {
+    rule.next_state_with_configuration(alive, configuration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isotropic::CONFIGURATION_OFFSETS;
+    use crate::rules::RulePreset;
+
+    const CONWAY: Rule = Rule::conway();
+
+    #[test]
+    fn next_state_alive_with_2_survives() {
+        assert!(next_cell_state(&CONWAY, true, 2));
+    }
+
+    #[test]
+    fn next_state_alive_with_3_survives() {
+        assert!(next_cell_state(&CONWAY, true, 3));
+    }
+
+    #[test]
+    fn next_state_alive_with_1_dies() {
+        assert!(!next_cell_state(&CONWAY, true, 1));
+    }
+
+    #[test]
+    fn next_state_alive_with_4_dies() {
+        assert!(!next_cell_state(&CONWAY, true, 4));
+    }
+
+    #[test]
+    fn next_state_dead_with_3_becomes_alive() {
+        assert!(next_cell_state(&CONWAY, false, 3));
+    }
+
+    #[test]
+    fn next_state_dead_with_2_stays_dead() {
+        assert!(!next_cell_state(&CONWAY, false, 2));
+    }
+
+    #[test]
+    fn highlife_dead_with_6_becomes_alive() {
+        assert!(next_cell_state(&RulePreset::HighLife.rule(), false, 6));
+    }
+
+    #[test]
+    fn seeds_alive_always_dies() {
+        let seeds = RulePreset::Seeds.rule();
+        for count in 0..=8 {
+            assert!(!next_cell_state(&seeds, true, count));
+        }
+    }
+
+    #[test]
+    fn configuration_decides_hensel_birth() {
+        let rule = Rule::parse("B2-a/S12").unwrap();
+        let bit = |offset| {
+            1 << CONFIGURATION_OFFSETS
+                .iter()
+                .position(|&o| o == offset)
+                .unwrap()
+        };
+        // 北と北東（2a）では誕生せず、北と南（2i）では誕生する
+        assert!(!next_cell_state_with_configuration(
+            &rule,
+            false,
+            bit((0, -1)) | bit((1, -1))
+        ));
+        assert!(next_cell_state_with_configuration(
+            &rule,
+            false,
+            bit((0, -1)) | bit((0, 1))
+        ));
+        // 隣接数だけで判定するルールでは配置の隣接数で判定する
+        assert!(next_cell_state_with_configuration(
+            &CONWAY,
+            false,
+            0b0000_0111
+        ));
+        assert!(!next_cell_state_with_configuration(
+            &CONWAY,
+            true,
+            0b0000_1111
+        ));
+    }
+}
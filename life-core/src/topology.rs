@@ -0,0 +1,439 @@
+//! ワールドの位相（無限平面・有限の閉曲面）
+//!
+//! 有限のトポロジーでは原点を中心とした `width × height` の領域だけを使い、
+//! 領域の端をまたぐ近傍の参照を面の種類に応じて巻き戻す、または打ち切る。
+//! 文字列表記は Golly のルール接尾辞（`T128,128` など）に合わせる。
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bitboard::Bitboard;
+use crate::consts::TOPOLOGY_MAX_SIZE;
+use crate::neighborhood::Neighborhood;
+use crate::rect::GridRect;
+use crate::rules::Rule;
+
+/// 有限ワールドの面の種類
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Surface {
+    /// 端の外側を常に死亡セルとして扱う平面
+    Bounded,
+    /// 左右・上下の端がそれぞれつながった面
+    Torus,
+    /// 左右はそのまま、上下は左右を反転してつながった面
+    KleinBottle,
+    /// 左右・上下とも反転してつながった面（射影平面）
+    CrossSurface,
+}
+
+impl Surface {
+    /// 全ての面の種類を返す
+    pub fn all() -> &'static [Surface] {
+        &[
+            Surface::Bounded,
+            Surface::Torus,
+            Surface::KleinBottle,
+            Surface::CrossSurface,
+        ]
+    }
+
+    /// 表示名を返す
+    pub fn name(&self) -> &'static str {
+        match self {
+            Surface::Bounded => "Bounded",
+            Surface::Torus => "Torus",
+            Surface::KleinBottle => "Klein bottle",
+            Surface::CrossSurface => "Cross-surface",
+        }
+    }
+
+    /// Golly 表記の接頭辞を返す
+    fn prefix(&self) -> char {
+        match self {
+            Surface::Bounded => 'P',
+            Surface::Torus => 'T',
+            Surface::KleinBottle => 'K',
+            Surface::CrossSurface => 'C',
+        }
+    }
+}
+
+/// ワールドの位相
+///
+/// 有限のトポロジーの領域は、左上が `(-(width / 2), -(height / 2))` の矩形。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// 無限平面
+    #[default]
+    Infinite,
+    /// 有限の面
+    Finite {
+        /// 面の種類
+        surface: Surface,
+        /// 横方向のセル数
+        width: u32,
+        /// 縦方向のセル数
+        height: u32,
+    },
+}
+
+/// トポロジー文字列の解析エラー
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TopologyParseError {
+    /// `P` / `T` / `K` / `C` 以外の面の種類
+    UnknownSurface(char),
+    /// `幅,高さ` の形式になっていない
+    InvalidSize,
+    /// 幅・高さが `1`〜`TOPOLOGY_MAX_SIZE` の範囲外
+    SizeOutOfRange(u64),
+}
+
+impl fmt::Display for TopologyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSurface(c) => {
+                write!(f, "unknown surface '{c}' (expected P, T, K or C)")
+            }
+            Self::InvalidSize => write!(f, "topology size must be written as width,height"),
+            Self::SizeOutOfRange(n) => {
+                write!(
+                    f,
+                    "topology size {n} is out of range (1-{TOPOLOGY_MAX_SIZE})"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TopologyParseError {}
+
+impl Topology {
+    /// 指定した面と大きさの有限トポロジーを生成する
+    pub fn finite(surface: Surface, width: u32, height: u32) -> Self {
+        Self::Finite {
+            surface,
+            width: width.clamp(1, TOPOLOGY_MAX_SIZE),
+            height: height.clamp(1, TOPOLOGY_MAX_SIZE),
+        }
+    }
+
+    /// 有限の面の種類を返す（無限平面なら `None`）
+    pub fn surface(&self) -> Option<Surface> {
+        match self {
+            Self::Infinite => None,
+            Self::Finite { surface, .. } => Some(*surface),
+        }
+    }
+
+    /// 近傍の形に合わせた位相を返す
+    ///
+    /// 六角形近傍は奇数行をずらしたオフセット行で表すため、上下を貼り合わせる面の高さが奇数だと
+    /// 継ぎ目で行の偶奇が入れ替わる。その場合は高さを偶数に切り上げる。
+    pub fn for_neighborhood(self, neighborhood: Neighborhood) -> Self {
+        match self {
+            Self::Finite {
+                surface,
+                width,
+                height,
+            } if neighborhood.is_hexagonal() && surface != Surface::Bounded && height % 2 == 1 => {
+                Self::finite(surface, width, height + 1)
+            }
+            _ => self,
+        }
+    }
+
+    /// 有限の領域を返す（無限平面なら `None`）
+    pub fn bounds(&self) -> Option<GridRect> {
+        match *self {
+            Self::Infinite => None,
+            Self::Finite { width, height, .. } => Some(GridRect::from_origin_size(
+                (-((width / 2) as i32), -((height / 2) as i32)),
+                width,
+                height,
+            )),
+        }
+    }
+
+    /// 指定座標のセルが領域内にあるかを返す（無限平面では常に真）
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.bounds().is_none_or(|rect| rect.contains(x, y))
+    }
+
+    /// 領域の外側の座標を、面の貼り合わせに従って領域内の座標に移す
+    ///
+    /// 反転して貼り合わせる端は、またいだ回数が奇数のときだけ反転する。領域内の座標はそのまま返す。
+    /// 有界平面の外側や無限平面では `None` を返す。
+    pub fn wrap(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let Self::Finite { surface, .. } = *self else {
+            return None;
+        };
+        let rect = self.bounds()?;
+        let (w, h) = (rect.width() as i32, rect.height() as i32);
+        let (mut lx, mut ly) = (x - rect.min.0, y - rect.min.1);
+        // NOTE: 左右・上下の端をまたいだ回数
+        let (x_crossings, y_crossings) = (lx.div_euclid(w), ly.div_euclid(h));
+        let (x_flip, y_flip) = (x_crossings % 2 != 0, y_crossings % 2 != 0);
+        match surface {
+            Surface::Bounded if (x_crossings, y_crossings) != (0, 0) => return None,
+            Surface::Bounded | Surface::Torus => {}
+            Surface::KleinBottle => {
+                if y_flip {
+                    lx = w - 1 - lx;
+                }
+            }
+            Surface::CrossSurface => {
+                if x_flip {
+                    ly = h - 1 - ly;
+                }
+                if y_flip {
+                    lx = w - 1 - lx;
+                }
+            }
+        }
+        Some((rect.min.0 + lx.rem_euclid(w), rect.min.1 + ly.rem_euclid(h)))
+    }
+
+    /// 領域外のセルを取り除いたビットボードを返す
+    pub fn clip(&self, cells: &Bitboard) -> Bitboard {
+        let mut clipped = cells.clone();
+        if let Some(rect) = self.bounds() {
+            clipped.retain_rect(rect.min, rect.max);
+        }
+        clipped
+    }
+
+    /// 領域の外周 `margin` セルの座標と、貼り合わせ先の領域内の座標の組を列挙する
+    ///
+    /// 無限平面と有界平面では空になる。
+    pub fn halo(&self, margin: i32) -> impl Iterator<Item = ((i32, i32), (i32, i32))> + '_ {
+        self.bounds()
+            .into_iter()
+            .flat_map(move |rect| halo(rect, margin))
+            .filter_map(|(x, y)| Some(((x, y), self.wrap(x, y)?)))
+    }
+
+    /// 領域の外周 `margin` セルに貼り合わせ先のセルを複製したビットボードを返す
+    ///
+    /// 複製したビットボード上で近傍を数えると、領域内の各セルは貼り合わせ先の近傍を直接参照する。
+    pub fn pad(&self, cells: &Bitboard, margin: i32) -> Bitboard {
+        let mut padded = cells.clone();
+        for ((x, y), (sx, sy)) in self.halo(margin) {
+            if cells.get(sx, sy) {
+                padded.set(x, y, true);
+            }
+        }
+        padded
+    }
+
+    /// このトポロジー上で世代を1つ進めたビットボードを返す
+    ///
+    /// 領域の外周1セルに貼り合わせ先のセルを複製してから通常の世代計算を行い、
+    /// 結果を領域内に切り詰める。
+    pub fn step(&self, cells: &Bitboard, rule: &Rule) -> Bitboard {
+        let Some(rect) = self.bounds() else {
+            return cells.step(rule);
+        };
+        let mut next = self.pad(cells, 1).step(rule);
+        next.retain_rect(rect.min, rect.max);
+        next
+    }
+}
+
+/// 矩形を囲む外周 `margin` セルの座標を列挙する
+fn halo(rect: GridRect, margin: i32) -> impl Iterator<Item = (i32, i32)> {
+    let (left, right) = (rect.min.0 - margin, rect.max.0 + margin);
+    let rows = (1..=margin).flat_map(move |d| {
+        (left..=right).flat_map(move |x| [(x, rect.min.1 - d), (x, rect.max.1 + d)])
+    });
+    let columns = (1..=margin).flat_map(move |d| {
+        (rect.min.1..=rect.max.1).flat_map(move |y| [(rect.min.0 - d, y), (rect.max.0 + d, y)])
+    });
+    rows.chain(columns)
+}
+
+/// Golly のルール接尾辞の形式（`T128,128`、クラインの壺は `K128*,128`）で出力する
+///
+/// 無限平面は空文字列になる。
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Infinite => Ok(()),
+            Self::Finite {
+                surface: Surface::KleinBottle,
+                width,
+                height,
+            } => write!(f, "K{width}*,{height}"),
+            Self::Finite {
+                surface,
+                width,
+                height,
+            } => write!(f, "{}{width},{height}", surface.prefix()),
+        }
+    }
+}
+
+/// Golly のルール接尾辞の形式を解析する（空文字列は無限平面）
+impl FromStr for Topology {
+    type Err = TopologyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        let Some(prefix) = chars.next() else {
+            return Ok(Self::Infinite);
+        };
+        let surface = Surface::all()
+            .iter()
+            .copied()
+            .find(|surface| surface.prefix() == prefix.to_ascii_uppercase())
+            .ok_or(TopologyParseError::UnknownSurface(prefix))?;
+        let (width, height) = chars
+            .as_str()
+            .split_once(',')
+            .ok_or(TopologyParseError::InvalidSize)?;
+        // NOTE: `*` はひねりのある辺を示す。クラインの壺以外でも受け付けるが、面の種類は接頭辞で決まる
+        let width = parse_size(width.trim_end_matches('*'))?;
+        let height = parse_size(height.trim_end_matches('*'))?;
+        Ok(Self::Finite {
+            surface,
+            width,
+            height,
+        })
+    }
+}
+
+/// 幅・高さの数字を解析する
+fn parse_size(s: &str) -> Result<u32, TopologyParseError> {
+    let n: u64 = s
+        .trim()
+        .parse()
+        .map_err(|_| TopologyParseError::InvalidSize)?;
+    if (1..=TOPOLOGY_MAX_SIZE as u64).contains(&n) {
+        Ok(n as u32)
+    } else {
+        Err(TopologyParseError::SizeOutOfRange(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::neighborhood::NeighborhoodShape;
+    use crate::patterns::LifePattern;
+
+    fn sorted(board: &Bitboard) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = board.iter().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn bounds_are_centered_on_origin() {
+        let rect = Topology::finite(Surface::Torus, 8, 5).bounds().unwrap();
+        assert_eq!(rect.min, (-4, -2));
+        assert_eq!(rect.max, (3, 2));
+        assert!(Topology::Infinite.bounds().is_none());
+        assert!(Topology::Infinite.contains(i32::MAX, i32::MIN));
+    }
+
+    #[test]
+    fn wrap_follows_surface_gluing() {
+        // 領域は x: -2..=1, y: -2..=1
+        let torus = Topology::finite(Surface::Torus, 4, 4);
+        assert_eq!(torus.wrap(2, -1), Some((-2, -1)));
+        assert_eq!(torus.wrap(-3, -3), Some((1, 1)));
+        let klein = Topology::finite(Surface::KleinBottle, 4, 4);
+        assert_eq!(klein.wrap(2, -1), Some((-2, -1)));
+        assert_eq!(klein.wrap(-2, 2), Some((1, -2)));
+        // 上下の端を2回またぐと反転は打ち消される
+        assert_eq!(klein.wrap(-2, 6), Some((-2, -2)));
+        let cross = Topology::finite(Surface::CrossSurface, 4, 4);
+        assert_eq!(cross.wrap(2, -2), Some((-2, 1)));
+        assert_eq!(cross.wrap(-1, -3), Some((0, 1)));
+        let plane = Topology::finite(Surface::Bounded, 4, 4);
+        assert_eq!(plane.wrap(2, 0), None);
+        assert_eq!(plane.wrap(1, 0), Some((1, 0)));
+    }
+
+    #[test]
+    fn hexagonal_neighborhood_needs_even_height() {
+        let hex = Neighborhood::new(NeighborhoodShape::Hexagonal, 1);
+        let torus = Topology::finite(Surface::Torus, 7, 7);
+        assert_eq!(
+            torus.for_neighborhood(hex),
+            Topology::finite(Surface::Torus, 7, 8)
+        );
+        assert_eq!(torus.for_neighborhood(Neighborhood::MOORE), torus);
+        let plane = Topology::finite(Surface::Bounded, 7, 7);
+        assert_eq!(plane.for_neighborhood(hex), plane);
+        assert_eq!(Topology::Infinite.for_neighborhood(hex), Topology::Infinite);
+    }
+
+    #[test]
+    fn glider_returns_to_start_on_torus() {
+        let torus = Topology::finite(Surface::Torus, 8, 8);
+        let start: Bitboard = LifePattern::Glider.cells().iter().copied().collect();
+        let mut cells = start.clone();
+        // グライダーは4世代で1セル進むので、32世代で8セル幅の面を1周する
+        for _ in 0..32 {
+            cells = torus.step(&cells, &Rule::conway());
+        }
+        assert_eq!(sorted(&cells), sorted(&start));
+    }
+
+    #[test]
+    fn bounded_plane_stops_glider_at_edge() {
+        let plane = Topology::finite(Surface::Bounded, 8, 8);
+        let rect = plane.bounds().unwrap();
+        let mut cells: Bitboard = LifePattern::Glider.cells().iter().copied().collect();
+        for _ in 0..64 {
+            cells = plane.step(&cells, &Rule::conway());
+            assert!(cells.iter().all(|(x, y)| plane.contains(x, y)));
+        }
+        // 端にぶつかったグライダーはブロックになって止まる
+        assert_eq!(cells.len(), 4);
+        assert!(
+            cells
+                .iter()
+                .all(|(x, y)| x >= rect.max.0 - 1 && y >= rect.max.1 - 1)
+        );
+    }
+
+    #[test]
+    fn blinker_across_torus_seam_oscillates() {
+        let torus = Topology::finite(Surface::Torus, 6, 6);
+        // 領域は x: -3..=2 なので、(2,0) (-3,0) (-2,0) は端をまたいだ横一列
+        let start: Bitboard = [(2, 0), (-3, 0), (-2, 0)].into_iter().collect();
+        let next = torus.step(&start, &Rule::conway());
+        assert_eq!(sorted(&next), vec![(-3, -1), (-3, 0), (-3, 1)]);
+        assert_eq!(sorted(&torus.step(&next, &Rule::conway())), sorted(&start));
+    }
+
+    #[test]
+    fn notation_roundtrip() {
+        for text in ["", "P30,20", "T128,128", "K64*,32", "C10,10"] {
+            let topology: Topology = text.parse().unwrap();
+            assert_eq!(topology.to_string(), text);
+        }
+        assert_eq!(
+            "t5,7".parse::<Topology>(),
+            Ok(Topology::finite(Surface::Torus, 5, 7))
+        );
+    }
+
+    #[test]
+    fn invalid_notation_is_rejected() {
+        assert_eq!(
+            "X10,10".parse::<Topology>(),
+            Err(TopologyParseError::UnknownSurface('X'))
+        );
+        assert_eq!(
+            "T10".parse::<Topology>(),
+            Err(TopologyParseError::InvalidSize)
+        );
+        assert_eq!(
+            "T0,10".parse::<Topology>(),
+            Err(TopologyParseError::SizeOutOfRange(0))
+        );
+    }
+}
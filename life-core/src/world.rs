@@ -0,0 +1,1850 @@
+//! ライフゲームの無限ワールド
+
+use rustc_hash::FxHashSet;
+
+use crate::age::CellAges;
+use crate::analysis::census::{Census, take_census};
+use crate::bitboard::{Bitboard, ChunkRows};
+use crate::consts::CHUNK_SIZE;
+use crate::engine::{EngineKind, LifeEngine};
+use crate::generations::DecayingCells;
+use crate::heatmap::{ActivityHeatmap, HeatmapConfig};
+use crate::isotropic;
+use crate::multistate;
+use crate::neighborhood;
+use crate::rect::GridRect;
+use crate::rules::Rule;
+use crate::topology::Topology;
+
+pub use crate::bitboard::ChunkKey;
+
+/// 1回の編集操作による差分
+///
+/// 状態が反転したセルの座標を記録する。反転は自己逆なので、
+/// 同じ差分をもう一度適用すると編集前の状態に戻る。
+/// Generations ルールの衰退中の状態は、変化前後の状態（衰退中でなければ0）のXORで記録する。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditDiff {
+    /// 現在のセルで状態が反転した座標
+    pub cells: Vec<(i32, i32)>,
+    /// 初期パターンで状態が反転した座標
+    pub initial_cells: Vec<(i32, i32)>,
+    /// 現在のセルで衰退中の状態が変化した座標と、変化前後の状態のXOR
+    pub decaying: Vec<((i32, i32), u8)>,
+    /// 初期パターンで衰退中の状態が変化した座標と、変化前後の状態のXOR
+    pub initial_decaying: Vec<((i32, i32), u8)>,
+    /// 編集前の世代数（編集後は常に0）
+    pub generation_before: u64,
+}
+
+impl EditDiff {
+    /// セルの変化を伴わない編集かを返す
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+            && self.initial_cells.is_empty()
+            && self.decaying.is_empty()
+            && self.initial_decaying.is_empty()
+    }
+}
+
+/// 1回の世代進行で誕生・死亡したセルの数
+///
+/// 複数世代をまとめて進めた場合は、進める前後の状態の差分を数える。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepChange {
+    /// 誕生したセルの数
+    pub births: usize,
+    /// 死亡したセルの数
+    pub deaths: usize,
+}
+
+/// ライフゲームのワールド
+///
+/// 生存セルをチャンク単位の `Bitboard` で管理する。座標は `(i32, i32)` で無限に拡張可能。
+/// Generations ルールの衰退中のセルは、状態ごとの `Bitboard` で別に管理する。
+/// 有限の `Topology` を設定すると、その領域内だけでセルを扱う。
+/// `dirty_chunks` で変更のあったチャンクを追跡し、レンダリングの最適化に使用する。
+/// 世代計算は差し替え可能な `LifeEngine` に委譲する。
+#[derive(Debug)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::resource::Resource))]
+pub struct World {
+    /// 生存セル
+    cells: Bitboard,
+    /// ユーザーが配置した初期パターン（リセット時に復元）
+    initial_cells: Bitboard,
+    /// Generations ルールの衰退中のセル
+    decaying: DecayingCells,
+    /// 初期パターンの衰退中のセル
+    initial_decaying: DecayingCells,
+    /// 直前の操作で変更があったチャンクの集合
+    dirty_chunks: FxHashSet<ChunkKey>,
+    /// 世代進行に使用するルール
+    rule: Rule,
+    /// 世代計算エンジン
+    engine: Box<dyn LifeEngine>,
+    /// ワールドの位相
+    topology: Topology,
+    /// セルの年齢（追跡していなければ `None`）
+    ages: Option<CellAges>,
+    /// 状態変化の頻度（集計していなければ `None`）
+    heatmap: Option<ActivityHeatmap>,
+    /// 直前の世代進行で誕生・死亡したセルの数
+    last_step: StepChange,
+    /// セル状態を変更する操作ごとに増える通し番号
+    revision: u64,
+    /// 現在の世代数
+    pub generation_count: u64,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    /// 空の無限ワールドを生成する
+    pub fn new() -> Self {
+        Self {
+            cells: Bitboard::new(),
+            initial_cells: Bitboard::new(),
+            decaying: DecayingCells::new(),
+            initial_decaying: DecayingCells::new(),
+            dirty_chunks: FxHashSet::default(),
+            rule: Rule::default(),
+            engine: EngineKind::default().create(),
+            topology: Topology::default(),
+            ages: None,
+            heatmap: None,
+            last_step: StepChange::default(),
+            revision: 0,
+            generation_count: 0,
+        }
+    }
+
+    /// 世代進行に使用するルールを返す
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// 世代進行に使用するルールを設定する
+    ///
+    /// 世代数は変更しない。新しいルールの状態数では存在しない衰退中のセルは取り除き、
+    /// 位相は新しいルールの近傍に合わせる。
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.set_topology(self.topology);
+        let mut decaying = self.decaying.clone();
+        decaying.truncate(rule.states());
+        self.initial_decaying.truncate(rule.states());
+        self.replace_decaying(decaying);
+    }
+
+    /// 現在の衰退中のセルを置き換え、変化があればチャンクをdirtyにして通し番号を増やす
+    fn replace_decaying(&mut self, decaying: DecayingCells) {
+        let changed = self.decaying.diff_chunks(&decaying);
+        if !changed.is_empty() {
+            self.dirty_chunks.extend(changed);
+            self.decaying = decaying;
+            self.revision += 1;
+        }
+    }
+
+    /// ワールドの位相を返す
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// ワールドの位相を設定する
+    ///
+    /// 有限の領域外にある現在のセルと初期パターンは取り除き、変化のあったチャンクをdirtyにする。
+    /// 六角形近傍のルールでは `Topology::for_neighborhood` で高さを合わせる。世代数は変更しない。
+    pub fn set_topology(&mut self, topology: Topology) {
+        let topology = topology.for_neighborhood(self.rule.neighborhood());
+        if self.topology == topology {
+            return;
+        }
+        self.topology = topology;
+        let cells = topology.clip(&self.cells);
+        self.dirty_chunks.extend(self.cells.diff_chunks(&cells));
+        self.cells = cells;
+        self.initial_cells = topology.clip(&self.initial_cells);
+        if let Some(rect) = topology.bounds() {
+            let mut decaying = self.decaying.clone();
+            decaying.retain_rect(rect.min, rect.max);
+            self.dirty_chunks
+                .extend(self.decaying.diff_chunks(&decaying));
+            self.decaying = decaying;
+            self.initial_decaying.retain_rect(rect.min, rect.max);
+        }
+        self.sync_ages();
+        self.revision += 1;
+    }
+
+    /// セルの年齢を追跡しているかを返す
+    pub fn is_age_tracking(&self) -> bool {
+        self.ages.is_some()
+    }
+
+    /// セルの年齢の追跡を開始・停止する
+    ///
+    /// 開始時点の生存セルは全て年齢0になる。セルの色が変わるため、生存セルと
+    /// 死亡直後の軌跡を含むチャンクをdirtyにする。セル状態は変えないため通し番号は増やさない。
+    pub fn set_age_tracking(&mut self, enabled: bool) {
+        if self.ages.is_some() == enabled {
+            return;
+        }
+        if let Some(ages) = self.ages.take() {
+            self.dirty_chunks.extend(ages.trail_chunk_keys());
+        } else {
+            self.ages = Some(CellAges::new(&self.cells));
+        }
+        self.dirty_chunks.extend(self.cells.chunk_keys());
+    }
+
+    /// セルの年齢を返す（追跡していなければ `None`）
+    pub fn ages(&self) -> Option<&CellAges> {
+        self.ages.as_ref()
+    }
+
+    /// 状態変化の頻度のヒートマップを返す（集計していなければ `None`）
+    pub fn heatmap(&self) -> Option<&ActivityHeatmap> {
+        self.heatmap.as_ref()
+    }
+
+    /// ヒートマップの集計を開始・停止する
+    ///
+    /// `Some` を渡すと空のヒートマップから集計を始める（集計方法が同じなら何もしない）。
+    /// 色が変わるため、ヒートマップのあったチャンクをdirtyにする。
+    pub fn set_heatmap(&mut self, config: Option<HeatmapConfig>) {
+        let next = config.map(ActivityHeatmap::new);
+        if self.heatmap.as_ref().map(ActivityHeatmap::config)
+            == next.as_ref().map(ActivityHeatmap::config)
+        {
+            return;
+        }
+        if let Some(heatmap) = &self.heatmap {
+            self.dirty_chunks.extend(heatmap.chunk_keys());
+        }
+        self.heatmap = next;
+    }
+
+    /// 編集後のセルに年齢の表を合わせる
+    fn sync_ages(&mut self) {
+        if let Some(ages) = &mut self.ages {
+            ages.sync(&self.cells);
+        }
+    }
+
+    /// セル座標からチャンクキーを計算する
+    pub fn chunk_key(x: i32, y: i32) -> ChunkKey {
+        Bitboard::chunk_key(x, y)
+    }
+
+    /// 指定座標のセルが生きているかを返す
+    pub fn is_alive(&self, x: i32, y: i32) -> bool {
+        self.cells.get(x, y)
+    }
+
+    /// 指定座標のセルの状態を返す（0が死亡、1が生存、2以上が衰退中）
+    pub fn cell_state(&self, x: i32, y: i32) -> u8 {
+        if self.cells.get(x, y) {
+            1
+        } else {
+            self.decaying.get(x, y).unwrap_or(0)
+        }
+    }
+
+    /// Generations ルールの衰退中のセルを返す
+    pub fn decaying_cells(&self) -> &DecayingCells {
+        &self.decaying
+    }
+
+    /// 指定チャンクのセル行を返す（生存セルがなければ `None`）
+    pub fn chunk(&self, key: ChunkKey) -> Option<&ChunkRows> {
+        self.cells.chunk(key)
+    }
+
+    /// 生存セルを物体に分け、種類ごとの数を集計する
+    ///
+    /// 物体ごとに単独で世代を進めて分類するため、盤面が落ち着いてから呼び出す。
+    /// 多状態オートマトンやムーア近傍以外・Hensel 表記のルールでは物体を分類できないため、空の集計を返す。
+    pub fn census(&self) -> Census {
+        if self.rule.multi_state().is_some() || !self.rule.is_moore_totalistic() {
+            return Census::default();
+        }
+        take_census(&self.cells, &self.rule)
+    }
+
+    /// 直前の世代進行で誕生・死亡したセルの数を返す
+    pub fn last_step(&self) -> StepChange {
+        self.last_step
+    }
+
+    /// セル状態を変更する操作ごとに増える通し番号を返す
+    ///
+    /// 編集履歴が、記録時から状態が変わっていないかの判定に使用する。
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// 指定座標のセルの生死をトグルし、編集差分を返す
+    ///
+    /// 生存セルは死亡し、死亡・衰退中のセルは生存する。
+    /// 初期パターンも同時に更新し、世代カウントを0にリセットする。
+    /// 有限の領域外の座標では何もせず、空の差分を返す。
+    pub fn toggle_cell(&mut self, x: i32, y: i32) -> EditDiff {
+        let state = if self.cells.get(x, y) { 0 } else { 1 };
+        self.set_cell_state(x, y, state)
+    }
+
+    /// 指定座標のセルを状態 `state`（1が生存、2以上が衰退中）で塗り、編集差分を返す
+    ///
+    /// 既にその状態のセルは死亡させる（状態1ならトグルと同じ）。
+    pub fn paint_cell(&mut self, x: i32, y: i32, state: u8) -> EditDiff {
+        let state = state.clamp(1, self.rule.states() - 1);
+        let next = if self.cell_state(x, y) == state {
+            0
+        } else {
+            state
+        };
+        self.set_cell_state(x, y, next)
+    }
+
+    /// 指定座標のセルを状態 `state`（0が死亡、1が生存、2以上が衰退中またはオートマトンの状態）にし、編集差分を返す
+    ///
+    /// ルールの状態数を超える状態は最後の衰退中の状態に丸める。
+    /// 初期パターンも同時に更新し、世代カウントを0にリセットする。
+    /// 有限の領域外の座標では何もせず、空の差分を返す。
+    pub fn set_cell_state(&mut self, x: i32, y: i32, state: u8) -> EditDiff {
+        let mut diff = EditDiff {
+            generation_before: self.generation_count,
+            ..EditDiff::default()
+        };
+        if !self.topology.contains(x, y) {
+            return diff;
+        }
+        self.write_state(x, y, state.min(self.rule.states() - 1), &mut diff);
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+        diff
+    }
+
+    /// 指定座標のセルと初期パターンのセルを状態 `state` にし、変化を編集差分に追記する
+    fn write_state(&mut self, x: i32, y: i32, state: u8, diff: &mut EditDiff) {
+        if self.cells.set(x, y, state == 1) {
+            diff.cells.push((x, y));
+        }
+        if self.initial_cells.set(x, y, state == 1) {
+            diff.initial_cells.push((x, y));
+        }
+        let previous = self.decaying.set(x, y, Some(state)).unwrap_or(0);
+        let decaying = self.decaying.get(x, y).unwrap_or(0);
+        if previous != decaying {
+            diff.decaying.push(((x, y), previous ^ decaying));
+        }
+        let previous = self.initial_decaying.set(x, y, Some(state)).unwrap_or(0);
+        let decaying = self.initial_decaying.get(x, y).unwrap_or(0);
+        if previous != decaying {
+            diff.initial_decaying.push(((x, y), previous ^ decaying));
+        }
+        self.dirty_chunks.insert(Self::chunk_key(x, y));
+    }
+
+    /// 使用中の世代計算エンジンの種類を返す
+    pub fn engine_kind(&self) -> EngineKind {
+        self.engine.kind()
+    }
+
+    /// 世代計算エンジンを切り替える
+    ///
+    /// セル状態と世代数は変更しない。
+    pub fn set_engine(&mut self, kind: EngineKind) {
+        if self.engine.kind() != kind {
+            self.engine = kind.create();
+        }
+    }
+
+    /// 設定されたルールに従い世代を1つ進める
+    pub fn progress_generation(&mut self) {
+        self.advance_generations(1);
+    }
+
+    /// 設定されたルールに従い世代を `generations` だけ進める
+    ///
+    /// 変化のあったセルを含むチャンクのみをdirtyにする。
+    /// 無限平面のムーア近傍の Life-like ルールはエンジンでまとめて進め、それ以外は1世代ずつ計算する。
+    /// ヒートマップの表示中は、窓に入る直近の世代を1世代ずつ進めて反転を記録する。
+    pub fn advance_generations(&mut self, generations: u64) {
+        self.advance_and_take_previous(generations);
+    }
+
+    /// 世代を `generations` だけ進め、進める前後の生存セルと衰退中のセルのXOR差分を返す
+    ///
+    /// 巻き戻しの記録に使い、`apply_generation_diff` に渡すと進める前の状態に戻る。
+    /// 進める前のセルは複製せずに差分の計算に使う。
+    pub fn advance_generations_with_diff(&mut self, generations: u64) -> (Bitboard, DecayingCells) {
+        match self.advance_and_take_previous(generations) {
+            Some((cells, decaying)) => (
+                cells.xor(&self.cells),
+                decaying.map_or_else(DecayingCells::new, |decaying| decaying.xor(&self.decaying)),
+            ),
+            None => (Bitboard::new(), DecayingCells::new()),
+        }
+    }
+
+    /// 世代を進め、置き換えた進める前の生存セルと衰退中のセルを返す（進めなければ `None`）
+    ///
+    /// 衰退中のセルを持たないルールでは、進める前の衰退中のセルは `None` になる。
+    fn advance_and_take_previous(
+        &mut self,
+        generations: u64,
+    ) -> Option<(Bitboard, Option<DecayingCells>)> {
+        self.dirty_chunks.clear();
+        if generations == 0 {
+            return None;
+        }
+        // NOTE: エンジンを可変で借りるため、計算中は進める前の状態を取り出しておく
+        let cells = std::mem::take(&mut self.cells);
+        let decaying = std::mem::take(&mut self.decaying);
+        let (new_cells, new_decaying, flips) = match self
+            .heatmap
+            .as_ref()
+            .map(|heatmap| heatmap.config().window as u64)
+        {
+            None => {
+                let (new_cells, new_decaying) =
+                    self.compute_generations(&cells, &decaying, generations);
+                (new_cells, new_decaying, Vec::new())
+            }
+            Some(window) => {
+                // NOTE: 窓より古い世代の反転はヒートマップに残らないため、その分はまとめて進め、
+                // 窓に入る世代だけ1世代ずつ進めて反転を記録する
+                let skip = generations.saturating_sub(window);
+                let (mut new_cells, mut new_decaying) = if skip == 0 {
+                    (cells.clone(), None)
+                } else {
+                    self.compute_generations(&cells, &decaying, skip)
+                };
+                let mut flips = Vec::new();
+                for _ in skip..generations {
+                    let (next, next_decaying) = self.compute_generations(
+                        &new_cells,
+                        new_decaying.as_ref().unwrap_or(&decaying),
+                        1,
+                    );
+                    flips.push(new_cells.xor(&next));
+                    (new_cells, new_decaying) = (next, next_decaying);
+                }
+                (new_cells, new_decaying, flips)
+            }
+        };
+        self.cells = cells;
+        self.decaying = decaying;
+        self.dirty_chunks = self.cells.diff_chunks(&new_cells);
+        let previous_decaying = new_decaying.map(|decaying| {
+            self.dirty_chunks
+                .extend(self.decaying.diff_chunks(&decaying));
+            std::mem::replace(&mut self.decaying, decaying)
+        });
+        self.last_step = count_step_change(&self.cells, &new_cells, &self.dirty_chunks);
+        if let Some(heatmap) = &mut self.heatmap {
+            // NOTE: 減衰で全ての活動中のセルの色が変わるため、記録の前後のチャンクをdirtyにする
+            self.dirty_chunks.extend(heatmap.chunk_keys());
+            for flipped in flips {
+                heatmap.record(flipped);
+            }
+            self.dirty_chunks.extend(heatmap.chunk_keys());
+        }
+        if let Some(ages) = &mut self.ages {
+            // NOTE: 年を取った生存セルと薄れていく軌跡も色が変わるため、含まれるチャンクを全てdirtyにする
+            self.dirty_chunks.extend(ages.trail_chunk_keys());
+            ages.advance(&new_cells, generations);
+            self.dirty_chunks.extend(ages.trail_chunk_keys());
+            self.dirty_chunks.extend(new_cells.chunk_keys());
+        }
+        let previous = std::mem::replace(&mut self.cells, new_cells);
+        self.generation_count += generations;
+        self.revision += 1;
+        Some((previous, previous_decaying))
+    }
+
+    /// 生存セルと衰退中のセルから `generations` 世代後の状態を計算する
+    ///
+    /// 衰退中のセルを持たないルールでは、衰退中のセルは `None` になる。
+    fn compute_generations(
+        &mut self,
+        cells: &Bitboard,
+        decaying: &DecayingCells,
+        generations: u64,
+    ) -> (Bitboard, Option<DecayingCells>) {
+        if let Some(rule) = self.rule.multi_state() {
+            let (cells, others) = (0..generations)
+                .fold((cells.clone(), decaying.clone()), |(cells, others), _| {
+                    multistate::step(rule, &cells, &others, &self.topology)
+                });
+            (cells, Some(others))
+        } else if self.rule.is_generations() {
+            let mut decaying = decaying.clone();
+            let cells = (0..generations).fold(cells.clone(), |cells, _| {
+                let mut next = self.step_cells(&cells);
+                decaying.advance(&cells, &mut next, self.rule.states());
+                next
+            });
+            (cells, Some(decaying))
+        } else {
+            let cells = match self.topology {
+                Topology::Infinite if self.rule.is_moore_totalistic() => {
+                    self.engine.advance(cells, &self.rule, generations)
+                }
+                _ => (0..generations).fold(cells.clone(), |cells, _| self.step_cells(&cells)),
+            };
+            (cells, None)
+        }
+    }
+
+    /// 生存セルをトポロジー上でルールの近傍に従って1世代進める（衰退中のセルは考慮しない）
+    ///
+    /// Hensel 表記のルールでは、隣接セルの配置を求めて判定する。
+    fn step_cells(&self, cells: &Bitboard) -> Bitboard {
+        if self.rule.isotropic().is_some() {
+            isotropic::step(&self.rule, cells, &self.topology)
+        } else if self.rule.neighborhood().is_moore() {
+            self.topology.step(cells, &self.rule)
+        } else {
+            neighborhood::step(&self.rule, cells, &self.topology)
+        }
+    }
+
+    /// 指定した世代まで早送りする
+    ///
+    /// 現在の世代以前を指定した場合は何もしない。
+    pub fn fast_forward_to(&mut self, generation: u64) {
+        if generation > self.generation_count {
+            self.advance_generations(generation - self.generation_count);
+        }
+    }
+
+    /// 現在のセルに世代間の差分（生存セルと衰退中のセルのXOR）を当てて、世代数を `generation` にする
+    ///
+    /// 巻き戻しで過去の世代を復元するために使用する。
+    pub fn apply_generation_diff(
+        &mut self,
+        diff: &Bitboard,
+        decaying_diff: &DecayingCells,
+        generation: u64,
+    ) {
+        self.cells.xor_assign(diff);
+        self.decaying.xor_assign(decaying_diff);
+        self.dirty_chunks = diff.chunk_keys().collect();
+        self.dirty_chunks.extend(decaying_diff.chunk_keys());
+        self.generation_count = generation;
+        self.sync_ages();
+        self.revision += 1;
+    }
+
+    /// 保存したセッションの盤面でワールド全体を置き換える
+    ///
+    /// ルール・エンジン・トポロジーは変更しない（有限の領域外のセルと、
+    /// ルールの状態数では存在しない衰退中のセルは取り除く）。
+    /// 変化のあったチャンクをdirtyにする。
+    pub fn restore(
+        &mut self,
+        cells: &[(i32, i32)],
+        initial_cells: &[(i32, i32)],
+        decaying: &[((i32, i32), u8)],
+        initial_decaying: &[((i32, i32), u8)],
+        generation: u64,
+    ) {
+        let cells = self.topology.clip(&cells.iter().copied().collect());
+        self.dirty_chunks = self.cells.diff_chunks(&cells);
+        self.cells = cells;
+        self.initial_cells = self.topology.clip(&initial_cells.iter().copied().collect());
+        let decaying = self.clip_decaying(decaying);
+        self.dirty_chunks
+            .extend(self.decaying.diff_chunks(&decaying));
+        self.decaying = decaying;
+        self.initial_decaying = self.clip_decaying(initial_decaying);
+        self.generation_count = generation;
+        self.sync_ages();
+        self.revision += 1;
+    }
+
+    /// 衰退中のセルの一覧から、位相の領域とルールの状態数に収まるものだけを集める
+    fn clip_decaying(&self, cells: &[((i32, i32), u8)]) -> DecayingCells {
+        let mut decaying: DecayingCells = cells
+            .iter()
+            .copied()
+            .filter(|&((x, y), _)| self.topology.contains(x, y))
+            .collect();
+        decaying.truncate(self.rule.states());
+        decaying
+    }
+
+    /// 初期パターンの状態に復元し、世代カウントを0にリセットする
+    pub fn reset(&mut self) {
+        // 変更のあったチャンクを追跡
+        self.dirty_chunks = self.cells.diff_chunks(&self.initial_cells);
+        self.dirty_chunks
+            .extend(self.decaying.diff_chunks(&self.initial_decaying));
+        self.cells = self.initial_cells.clone();
+        self.decaying = self.initial_decaying.clone();
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+    }
+
+    /// 全セルを死んだ状態にし、初期パターンもクリアして編集差分を返す
+    pub fn clear(&mut self) -> EditDiff {
+        let diff = EditDiff {
+            cells: self.cells.iter().collect(),
+            initial_cells: self.initial_cells.iter().collect(),
+            decaying: self.decaying.iter().collect(),
+            initial_decaying: self.initial_decaying.iter().collect(),
+            generation_before: self.generation_count,
+        };
+        // 旧生存セルのチャンクをdirtyに
+        self.dirty_chunks.extend(self.cells.chunk_keys());
+        self.dirty_chunks.extend(self.initial_cells.chunk_keys());
+        self.dirty_chunks.extend(self.decaying.chunk_keys());
+        self.cells.clear();
+        self.initial_cells.clear();
+        self.decaying.clear();
+        self.initial_decaying.clear();
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+        diff
+    }
+
+    /// 矩形内の全セルを死亡状態にし、編集差分を返す
+    ///
+    /// 初期パターンからも削除し、世代カウントを0にリセットする。
+    pub fn erase_rect(&mut self, rect: GridRect) -> EditDiff {
+        let in_rect = |&((x, y), _): &((i32, i32), u8)| rect.contains(x, y);
+        let diff = EditDiff {
+            cells: self.cells.iter_rect(rect.min, rect.max).collect(),
+            initial_cells: self.initial_cells.iter_rect(rect.min, rect.max).collect(),
+            decaying: self.decaying.iter().filter(in_rect).collect(),
+            initial_decaying: self.initial_decaying.iter().filter(in_rect).collect(),
+            generation_before: self.generation_count,
+        };
+        for &(x, y) in &diff.cells {
+            self.cells.set(x, y, false);
+            self.dirty_chunks.insert(Self::chunk_key(x, y));
+        }
+        for &(x, y) in &diff.initial_cells {
+            self.initial_cells.set(x, y, false);
+        }
+        for &((x, y), _) in &diff.decaying {
+            self.decaying.set(x, y, None);
+            self.dirty_chunks.insert(Self::chunk_key(x, y));
+        }
+        for &((x, y), _) in &diff.initial_decaying {
+            self.initial_decaying.set(x, y, None);
+        }
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+        diff
+    }
+
+    /// 編集差分を適用し、世代数を `generation` にする
+    ///
+    /// 差分は反転したセルの集合なので、取り消しとやり直しのどちらにも使える。
+    /// `exact` が偽（記録後に世代が進んだなど）の場合は現在のセルに差分を当てず、
+    /// 初期パターンにのみ適用して現在のセルを初期パターンに戻す（世代数は0）。
+    pub fn apply_edit(&mut self, diff: &EditDiff, generation: u64, exact: bool) {
+        for &(x, y) in &diff.initial_cells {
+            let alive = !self.initial_cells.get(x, y);
+            self.initial_cells.set(x, y, alive);
+        }
+        for &((x, y), state) in &diff.initial_decaying {
+            let previous = self.initial_decaying.get(x, y).unwrap_or(0);
+            self.initial_decaying.set(x, y, Some(previous ^ state));
+        }
+        if !exact {
+            self.reset();
+            return;
+        }
+        for &(x, y) in &diff.cells {
+            let alive = !self.cells.get(x, y);
+            self.cells.set(x, y, alive);
+            self.dirty_chunks.insert(Self::chunk_key(x, y));
+        }
+        for &((x, y), state) in &diff.decaying {
+            let previous = self.decaying.get(x, y).unwrap_or(0);
+            self.decaying.set(x, y, Some(previous ^ state));
+            self.dirty_chunks.insert(Self::chunk_key(x, y));
+        }
+        self.generation_count = generation;
+        self.sync_ages();
+        self.revision += 1;
+    }
+
+    /// 生存セルのビットボードを返す
+    pub fn alive_cells(&self) -> &Bitboard {
+        &self.cells
+    }
+
+    /// 生存セルの座標を行優先（y, x の昇順）でソートして返す
+    ///
+    /// パターンファイルへの書き出しなど、決定的な順序が必要な場面で使用する。
+    pub fn live_cells(&self) -> Vec<(i32, i32)> {
+        let mut cells: Vec<_> = self.cells.iter().collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    /// 初期パターンにおいて指定座標のセルが生きているかを返す
+    pub fn is_initial_alive(&self, x: i32, y: i32) -> bool {
+        self.initial_cells.get(x, y)
+    }
+
+    /// 初期パターンの生存セルの座標を返す（順序は不定）
+    pub fn initial_live_cells(&self) -> Vec<(i32, i32)> {
+        self.initial_cells.iter().collect()
+    }
+
+    /// 初期パターンの衰退中のセルを返す
+    pub fn initial_decaying_cells(&self) -> &DecayingCells {
+        &self.initial_decaying
+    }
+
+    /// 矩形内の生存セルの座標を返す（順序は不定）
+    pub fn cells_in_rect(&self, rect: GridRect) -> Vec<(i32, i32)> {
+        self.cells.iter_rect(rect.min, rect.max).collect()
+    }
+
+    /// 直前の操作で変更があったチャンクの集合を返す
+    pub fn dirty_chunks(&self) -> &FxHashSet<ChunkKey> {
+        &self.dirty_chunks
+    }
+
+    /// dirtyチャンクの追跡をクリアする
+    pub fn clear_dirty_chunks(&mut self) {
+        self.dirty_chunks.clear();
+    }
+
+    /// 指定したセル群をそれぞれの状態でワールドに配置し、編集差分を返す
+    ///
+    /// `place_pattern` と同じく初期パターンにも記録し、世代カウントを0にリセットする。
+    /// 状態0のセル、ルールにない状態のセル、有限の領域外にはみ出したセルは配置しない。
+    pub fn place_states(&mut self, cells: &[((i32, i32), u8)]) -> EditDiff {
+        let mut diff = EditDiff {
+            generation_before: self.generation_count,
+            ..EditDiff::default()
+        };
+        for &((x, y), state) in cells {
+            if (1..self.rule.states()).contains(&state) && self.topology.contains(x, y) {
+                self.write_state(x, y, state, &mut diff);
+            }
+        }
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+        diff
+    }
+
+    /// 指定したセル群をワールドに配置し、編集差分を返す
+    ///
+    /// セルを生存状態にし、初期パターンにも記録する（衰退中だったセルも生存状態になる）。
+    /// 世代カウントを0にリセットし、対応チャンクをdirtyにする。
+    /// 有限の領域外にはみ出したセルは配置しない。
+    pub fn place_pattern(&mut self, cells: &[(i32, i32)]) -> EditDiff {
+        let mut diff = EditDiff {
+            generation_before: self.generation_count,
+            ..EditDiff::default()
+        };
+        for &(x, y) in cells {
+            if !self.topology.contains(x, y) {
+                continue;
+            }
+            if self.cells.set(x, y, true) {
+                diff.cells.push((x, y));
+            }
+            if self.initial_cells.set(x, y, true) {
+                diff.initial_cells.push((x, y));
+            }
+            if let Some(state) = self.decaying.set(x, y, None) {
+                diff.decaying.push(((x, y), state));
+            }
+            if let Some(state) = self.initial_decaying.set(x, y, None) {
+                diff.initial_decaying.push(((x, y), state));
+            }
+            self.dirty_chunks.insert(Self::chunk_key(x, y));
+        }
+        self.generation_count = 0;
+        self.sync_ages();
+        self.revision += 1;
+        diff
+    }
+}
+
+/// 変化のあったチャンクだけを走査し、誕生・死亡したセルを数える
+fn count_step_change(
+    before: &Bitboard,
+    after: &Bitboard,
+    changed: &FxHashSet<ChunkKey>,
+) -> StepChange {
+    let mut change = StepChange::default();
+    for &key in changed {
+        let before_rows = before.chunk(key);
+        let after_rows = after.chunk(key);
+        for y in 0..CHUNK_SIZE as usize {
+            let old = before_rows.map_or(0, |rows| rows[y]);
+            let new = after_rows.map_or(0, |rows| rows[y]);
+            change.births += (new & !old).count_ones() as usize;
+            change.deaths += (old & !new).count_ones() as usize;
+        }
+    }
+    change
+}
+
+#[cfg(test)]
+impl World {
+    /// 指定座標のセルの生死状態を設定する（テスト専用）
+    pub fn set_alive(&mut self, x: i32, y: i32, alive: bool) {
+        self.cells.set(x, y, alive);
+    }
+
+    /// 現在のセル状態を初期パターンとして保存する（テスト専用）
+    pub fn save_as_initial(&mut self) {
+        self.initial_cells = self.cells.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::age::CellAge;
+    use crate::consts::CHUNK_SIZE;
+    use crate::formats::{PatternFile, life105, life106, plaintext, rle};
+    use crate::multistate::Wireworld;
+    use crate::patterns::{LifePattern, WireworldCircuit};
+    use crate::rules::RulePreset;
+    use crate::topology::Surface;
+
+    // --- World::new ---
+
+    #[test]
+    fn new_world_is_empty() {
+        let world = World::new();
+        assert!(world.alive_cells().is_empty());
+    }
+
+    #[test]
+    fn new_world_has_zero_generation() {
+        let world = World::new();
+        assert_eq!(world.generation_count, 0);
+    }
+
+    // --- toggle_cell ---
+
+    #[test]
+    fn toggle_cell_switches_dead_to_alive() {
+        let mut world = World::new();
+        world.toggle_cell(1, 1);
+        assert!(world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn toggle_cell_switches_alive_to_dead() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.toggle_cell(1, 1);
+        assert!(!world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn toggle_cell_syncs_initial_cells() {
+        let mut world = World::new();
+        world.toggle_cell(1, 1);
+        assert!(world.is_initial_alive(1, 1));
+    }
+
+    #[test]
+    fn toggle_cell_resets_generation_count() {
+        let mut world = World::new();
+        world.generation_count = 5;
+        world.toggle_cell(1, 1);
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn toggle_cell_does_not_affect_other_initial_cells() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.save_as_initial();
+
+        world.toggle_cell(1, 1);
+
+        assert!(world.is_initial_alive(0, 0));
+        assert!(world.is_initial_alive(1, 1));
+    }
+
+    #[test]
+    fn toggle_cell_negative_coordinates() {
+        let mut world = World::new();
+        world.toggle_cell(-5, -10);
+        assert!(world.is_alive(-5, -10));
+        assert!(!world.is_alive(0, 0));
+    }
+
+    #[test]
+    fn toggle_cell_marks_dirty_chunk() {
+        let mut world = World::new();
+        world.toggle_cell(3, 5);
+        assert!(world.dirty_chunks().contains(&World::chunk_key(3, 5)));
+    }
+
+    // --- Conway's ルール (progress_generation 経由) ---
+
+    #[test]
+    fn alive_cell_with_0_neighbors_dies() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.progress_generation();
+        assert!(!world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn alive_cell_with_1_neighbor_dies() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.set_alive(0, 0, true);
+        world.progress_generation();
+        assert!(!world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn alive_cell_with_2_neighbors_survives() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+        world.progress_generation();
+        assert!(world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn alive_cell_with_3_neighbors_survives() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+        world.set_alive(2, 0, true);
+        world.progress_generation();
+        assert!(world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn alive_cell_with_4_neighbors_dies() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+        world.set_alive(2, 0, true);
+        world.set_alive(0, 1, true);
+        world.progress_generation();
+        assert!(!world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn dead_cell_with_3_neighbors_becomes_alive() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+        world.set_alive(0, 1, true);
+        world.progress_generation();
+        assert!(world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn dead_cell_with_2_neighbors_stays_dead() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+        world.progress_generation();
+        assert!(!world.is_alive(1, 1));
+    }
+
+    // --- 有名パターン ---
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut world = World::new();
+        world.set_alive(1, 2, true);
+        world.set_alive(2, 2, true);
+        world.set_alive(3, 2, true);
+
+        world.progress_generation();
+        assert!(!world.is_alive(1, 2));
+        assert!(world.is_alive(2, 1));
+        assert!(world.is_alive(2, 2));
+        assert!(world.is_alive(2, 3));
+        assert!(!world.is_alive(3, 2));
+
+        world.progress_generation();
+        assert!(world.is_alive(1, 2));
+        assert!(world.is_alive(2, 2));
+        assert!(world.is_alive(3, 2));
+        assert!(!world.is_alive(2, 1));
+        assert!(!world.is_alive(2, 3));
+    }
+
+    #[test]
+    fn block_is_stable() {
+        let mut world = World::new();
+        world.set_alive(1, 1, true);
+        world.set_alive(2, 1, true);
+        world.set_alive(1, 2, true);
+        world.set_alive(2, 2, true);
+
+        world.progress_generation();
+        assert!(world.is_alive(1, 1));
+        assert!(world.is_alive(2, 1));
+        assert!(world.is_alive(1, 2));
+        assert!(world.is_alive(2, 2));
+        assert_eq!(world.alive_cells().len(), 4);
+    }
+
+    // --- generation_count ---
+
+    #[test]
+    fn generation_count_increments() {
+        let mut world = World::new();
+        assert_eq!(world.generation_count, 0);
+        world.progress_generation();
+        assert_eq!(world.generation_count, 1);
+        world.progress_generation();
+        assert_eq!(world.generation_count, 2);
+    }
+
+    // --- engine / fast_forward ---
+
+    #[test]
+    fn new_world_uses_bitboard_engine() {
+        assert_eq!(World::new().engine_kind(), EngineKind::Bitboard);
+    }
+
+    #[test]
+    fn set_engine_keeps_cells_and_generation() {
+        let mut world = World::new();
+        world.place_pattern(LifePattern::Glider.cells());
+        world.progress_generation();
+        world.set_engine(EngineKind::HashLife);
+        assert_eq!(world.engine_kind(), EngineKind::HashLife);
+        assert_eq!(world.generation_count, 1);
+        assert_eq!(world.alive_cells().len(), 5);
+    }
+
+    #[test]
+    fn engines_agree_on_fast_forward() {
+        let worlds: Vec<World> = [
+            EngineKind::Bitboard,
+            EngineKind::Sparse,
+            EngineKind::HashLife,
+        ]
+        .into_iter()
+        .map(|kind| {
+            let mut world = World::new();
+            world.set_engine(kind);
+            assert_eq!(world.engine_kind(), kind);
+            world.place_pattern(LifePattern::Acorn.cells());
+            world.fast_forward_to(500);
+            world
+        })
+        .collect();
+        let (bitboard, others) = worlds.split_first().unwrap();
+        for world in others {
+            assert_eq!(world.generation_count, 500);
+            assert_eq!(world.live_cells(), bitboard.live_cells());
+            assert_eq!(world.dirty_chunks(), bitboard.dirty_chunks());
+        }
+    }
+
+    #[test]
+    fn fast_forward_to_past_generation_does_nothing() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        world.advance_generations(10);
+        world.fast_forward_to(3);
+        assert_eq!(world.generation_count, 10);
+    }
+
+    #[test]
+    fn advance_generations_marks_changed_chunks_only() {
+        let mut world = World::new();
+        world.set_engine(EngineKind::HashLife);
+        // 静物（ブロック）と振動子（ブリンカー）を別チャンクに配置
+        world.place_pattern(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        world.place_pattern(&[(200, 5), (201, 5), (202, 5)]);
+        world.advance_generations(3);
+        assert_eq!(world.dirty_chunks().len(), 1);
+        assert!(world.dirty_chunks().contains(&World::chunk_key(201, 5)));
+    }
+
+    // --- reset ---
+
+    #[test]
+    fn reset_restores_initial_cells() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.save_as_initial();
+
+        world.progress_generation();
+        world.reset();
+
+        assert!(world.is_alive(0, 0));
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn reset_marks_dirty_chunks() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.save_as_initial();
+        world.set_alive(50, 50, true);
+        world.clear_dirty_chunks();
+
+        world.reset();
+
+        // (50,50)が消えるのでそのチャンクがdirty
+        assert!(world.dirty_chunks().contains(&World::chunk_key(50, 50)));
+    }
+
+    // --- restore ---
+
+    #[test]
+    fn restore_replaces_cells_initial_and_generation() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0)]);
+        let revision = world.revision();
+
+        world.restore(&[(100, 100), (101, 100)], &[(5, 5)], &[], &[], 77);
+
+        assert!(!world.is_alive(0, 0));
+        assert!(world.is_alive(100, 100));
+        assert_eq!(world.generation_count, 77);
+        assert!(world.revision() > revision);
+        world.reset();
+        assert_eq!(world.live_cells(), vec![(5, 5)]);
+    }
+
+    #[test]
+    fn restore_marks_removed_and_added_chunks_dirty() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0)]);
+        world.clear_dirty_chunks();
+
+        world.restore(&[(100, 100)], &[], &[], &[], 0);
+
+        assert!(world.dirty_chunks().contains(&World::chunk_key(0, 0)));
+        assert!(world.dirty_chunks().contains(&World::chunk_key(100, 100)));
+    }
+
+    #[test]
+    fn restore_keeps_decaying_cells_within_rule_states() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C4").unwrap());
+        world.restore(&[], &[], &[((0, 0), 3), ((1, 0), 9)], &[((2, 0), 2)], 5);
+
+        assert_eq!(world.cell_state(0, 0), 3);
+        assert_eq!(world.cell_state(1, 0), 0);
+        assert!(world.dirty_chunks().contains(&World::chunk_key(0, 0)));
+        world.reset();
+        assert_eq!(world.cell_state(0, 0), 0);
+        assert_eq!(world.cell_state(2, 0), 2);
+    }
+
+    // --- clear ---
+
+    #[test]
+    fn erase_rect_removes_cells_inside_only() {
+        let mut world = World::new();
+        world.place_pattern(&[(-1, -1), (0, 0), (3, 3), (4, 4)]);
+        world.clear_dirty_chunks();
+        let diff = world.erase_rect(GridRect::from_corners((-1, -1), (3, 3)));
+        assert_eq!(world.live_cells(), vec![(4, 4)]);
+        assert!(!world.is_initial_alive(0, 0));
+        assert!(world.is_initial_alive(4, 4));
+        assert_eq!(diff.cells.len(), 3);
+        assert!(world.dirty_chunks().contains(&(-1, -1)));
+        assert!(world.dirty_chunks().contains(&(0, 0)));
+    }
+
+    #[test]
+    fn clear_sets_all_cells_dead() {
+        let mut world = World::new();
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 1, true);
+        world.generation_count = 5;
+
+        world.clear();
+
+        assert!(world.alive_cells().is_empty());
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn clear_marks_dirty_chunks() {
+        let mut world = World::new();
+        world.set_alive(5, 5, true);
+        world.clear_dirty_chunks();
+
+        world.clear();
+
+        assert!(world.dirty_chunks().contains(&World::chunk_key(5, 5)));
+    }
+
+    // --- chunk_key ---
+
+    #[test]
+    fn chunk_key_positive_coordinates() {
+        assert_eq!(World::chunk_key(0, 0), (0, 0));
+        assert_eq!(World::chunk_key(63, 63), (0, 0));
+        assert_eq!(World::chunk_key(64, 0), (1, 0));
+        assert_eq!(World::chunk_key(127, 63), (1, 0));
+    }
+
+    #[test]
+    fn chunk_key_negative_coordinates() {
+        assert_eq!(World::chunk_key(-1, -1), (-1, -1));
+        assert_eq!(World::chunk_key(-64, -64), (-1, -1));
+        assert_eq!(World::chunk_key(-65, -65), (-2, -2));
+    }
+
+    // --- dirty_chunks ---
+
+    #[test]
+    fn progress_generation_marks_dirty_chunks() {
+        let mut world = World::new();
+        // Blinker at origin
+        world.set_alive(0, -1, true);
+        world.set_alive(0, 0, true);
+        world.set_alive(0, 1, true);
+        world.clear_dirty_chunks();
+
+        world.progress_generation();
+
+        // 変化があるので dirty_chunks は空でない
+        assert!(!world.dirty_chunks().is_empty());
+    }
+
+    #[test]
+    fn clear_dirty_chunks_works() {
+        let mut world = World::new();
+        world.toggle_cell(0, 0);
+        assert!(!world.dirty_chunks().is_empty());
+        world.clear_dirty_chunks();
+        assert!(world.dirty_chunks().is_empty());
+    }
+
+    // --- 負の座標でのシミュレーション ---
+
+    #[test]
+    fn blinker_at_negative_coordinates() {
+        let mut world = World::new();
+        world.set_alive(-1, 0, true);
+        world.set_alive(0, 0, true);
+        world.set_alive(1, 0, true);
+
+        world.progress_generation();
+        assert!(world.is_alive(0, -1));
+        assert!(world.is_alive(0, 0));
+        assert!(world.is_alive(0, 1));
+        assert!(!world.is_alive(-1, 0));
+        assert!(!world.is_alive(1, 0));
+    }
+
+    #[test]
+    fn pattern_across_chunk_boundary() {
+        // チャンク境界(31,32)をまたぐblinker
+        let mut world = World::new();
+        world.set_alive(31, 0, true);
+        world.set_alive(32, 0, true);
+        world.set_alive(33, 0, true);
+
+        world.progress_generation();
+        assert!(world.is_alive(32, -1));
+        assert!(world.is_alive(32, 0));
+        assert!(world.is_alive(32, 1));
+    }
+
+    // --- rule ---
+
+    #[test]
+    fn new_world_uses_conway_rule() {
+        let world = World::new();
+        assert_eq!(world.rule(), Rule::conway());
+    }
+
+    #[test]
+    fn highlife_rule_births_with_6_neighbors() {
+        // (1,1) の周囲に6セル配置: コンウェイでは誕生しないがHighLifeでは誕生する
+        let neighbors = [(0, 0), (1, 0), (2, 0), (0, 2), (1, 2), (2, 2)];
+
+        let mut conway = World::new();
+        let mut highlife = World::new();
+        highlife.set_rule(Rule::parse("B36/S23").unwrap());
+        for &(x, y) in &neighbors {
+            conway.set_alive(x, y, true);
+            highlife.set_alive(x, y, true);
+        }
+
+        conway.progress_generation();
+        highlife.progress_generation();
+
+        assert!(!conway.is_alive(1, 1));
+        assert!(highlife.is_alive(1, 1));
+    }
+
+    #[test]
+    fn set_rule_keeps_cells_and_generation() {
+        let mut world = World::new();
+        world.toggle_cell(0, 0);
+        world.generation_count = 3;
+        world.set_rule(Rule::parse("B2/S").unwrap());
+        assert!(world.is_alive(0, 0));
+        assert_eq!(world.generation_count, 3);
+    }
+
+    #[test]
+    fn place_pattern_sets_cells_alive() {
+        let mut world = World::new();
+        let cells = &[(0, 0), (1, 0), (0, 1)];
+        world.place_pattern(cells);
+        assert!(world.is_alive(0, 0));
+        assert!(world.is_alive(1, 0));
+        assert!(world.is_alive(0, 1));
+        assert!(!world.is_alive(1, 1));
+    }
+
+    #[test]
+    fn place_pattern_records_initial_cells() {
+        let mut world = World::new();
+        let cells = &[(0, 0), (1, 0)];
+        world.place_pattern(cells);
+        assert!(world.is_initial_alive(0, 0));
+        assert!(world.is_initial_alive(1, 0));
+    }
+
+    #[test]
+    fn place_pattern_resets_generation_count() {
+        let mut world = World::new();
+        world.toggle_cell(0, 0);
+        world.clear_dirty_chunks();
+        world.progress_generation();
+        assert!(world.generation_count > 0);
+        world.place_pattern(&[(5, 5)]);
+        assert_eq!(world.generation_count, 0);
+    }
+
+    #[test]
+    fn place_pattern_marks_dirty_chunks() {
+        let mut world = World::new();
+        world.clear_dirty_chunks();
+        world.place_pattern(&[(0, 0), (100, 100)]);
+        let dirty = world.dirty_chunks();
+        assert!(dirty.contains(&World::chunk_key(0, 0)));
+        assert!(dirty.contains(&World::chunk_key(100, 100)));
+    }
+
+    // --- live_cells / RLE ---
+
+    #[test]
+    fn live_cells_sorted_row_major() {
+        let mut world = World::new();
+        world.place_pattern(&[(2, 1), (-1, 1), (0, -3)]);
+        assert_eq!(world.live_cells(), vec![(0, -3), (-1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn rle_roundtrip_through_world() {
+        let rle_text = rle::write(&PatternFile::from_cells(
+            LifePattern::GosperGliderGun.cells().to_vec(),
+        ));
+        let mut world = World::new();
+        world.place_pattern(&rle::parse(&rle_text).unwrap().cells);
+
+        let dumped = rle::write(&PatternFile::from_cells(world.live_cells()));
+        assert_eq!(dumped, rle_text);
+    }
+
+    #[test]
+    fn rle_roundtrip_preserves_position_after_generations() {
+        let mut world = World::new();
+        world.place_pattern(LifePattern::Acorn.cells());
+        for _ in 0..30 {
+            world.progress_generation();
+        }
+
+        let rle_text = rle::write(&PatternFile::from_cells(world.live_cells()));
+        let mut restored = World::new();
+        restored.place_pattern(&rle::parse(&rle_text).unwrap().cells);
+
+        assert_eq!(restored.live_cells(), world.live_cells());
+    }
+    #[test]
+    fn life106_pattern_across_negative_chunk_boundary() {
+        // 原点のチャンク境界をまたぐグライダー
+        let text = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+        let mut world = World::new();
+        world.place_pattern(&life106::parse(text).unwrap().cells);
+
+        for chunk in [(0, -1), (-1, 0), (0, 0)] {
+            assert!(world.dirty_chunks().contains(&chunk));
+        }
+        for _ in 0..4 {
+            world.progress_generation();
+        }
+        let dumped = life106::write(&PatternFile::from_cells(world.live_cells()));
+        assert_eq!(dumped, "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+    }
+
+    #[test]
+    fn life105_and_plaintext_place_same_shape() {
+        let text = life105::write(&PatternFile::from_cells(
+            LifePattern::Pulsar
+                .cells()
+                .iter()
+                .map(|&(x, y)| (x - CHUNK_SIZE / 2, y - CHUNK_SIZE - 5))
+                .collect(),
+        ));
+        let mut world = World::new();
+        world.place_pattern(&life105::parse(&text).unwrap().cells);
+
+        let cells_text = plaintext::write(&PatternFile::from_cells(world.live_cells()));
+        let mut restored = World::new();
+        restored.place_pattern(&plaintext::parse(&cells_text).unwrap().cells);
+        assert_eq!(restored.live_cells().len(), world.live_cells().len());
+    }
+
+    #[test]
+    fn step_change_counts_births_and_deaths() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(world.last_step(), StepChange::default());
+        world.advance_generations(1);
+        assert_eq!(
+            world.last_step(),
+            StepChange {
+                births: 2,
+                deaths: 2
+            }
+        );
+
+        // チャンク境界をまたぐパターンでも数え漏れがない
+        let mut world = World::new();
+        world.place_pattern(&[(-1, 0), (0, 0), (1, 0), (5, 5)]);
+        world.advance_generations(1);
+        assert_eq!(
+            world.last_step(),
+            StepChange {
+                births: 2,
+                deaths: 3
+            }
+        );
+    }
+
+    #[test]
+    fn hexagonal_rule_rounds_topology_height_to_even() {
+        let mut world = World::new();
+        world.set_topology(Topology::finite(Surface::Torus, 7, 7));
+        assert_eq!(world.topology(), Topology::finite(Surface::Torus, 7, 7));
+        world.set_rule(Rule::parse("B2/S34H").unwrap());
+        assert_eq!(world.topology(), Topology::finite(Surface::Torus, 7, 8));
+        world.set_topology(Topology::finite(Surface::KleinBottle, 9, 9));
+        assert_eq!(
+            world.topology(),
+            Topology::finite(Surface::KleinBottle, 9, 10)
+        );
+    }
+
+    #[test]
+    fn set_topology_clips_cells_outside_area() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (100, 0), (-5, -5)]);
+        world.clear_dirty_chunks();
+        let revision = world.revision();
+        world.set_topology(Topology::finite(Surface::Torus, 8, 8));
+        assert_eq!(world.live_cells(), vec![(0, 0)]);
+        assert!(!world.is_initial_alive(100, 0));
+        assert!(!world.is_initial_alive(-5, -5) && world.is_initial_alive(0, 0));
+        assert!(world.dirty_chunks().contains(&World::chunk_key(100, 0)));
+        assert!(world.revision() > revision);
+    }
+
+    #[test]
+    fn edits_outside_finite_area_are_ignored() {
+        let mut world = World::new();
+        world.set_topology(Topology::finite(Surface::Bounded, 4, 4));
+        let revision = world.revision();
+        assert!(world.toggle_cell(10, 0).is_empty());
+        assert_eq!(world.revision(), revision);
+        let diff = world.place_pattern(&[(1, 1), (2, 1)]);
+        assert_eq!(diff.cells, vec![(1, 1)]);
+        assert_eq!(world.live_cells(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn torus_world_wraps_blinker_across_edge() {
+        let mut world = World::new();
+        world.set_topology(Topology::finite(Surface::Torus, 6, 6));
+        world.place_pattern(&[(0, 1), (0, 2), (0, -3)]);
+        world.progress_generation();
+        assert_eq!(world.live_cells(), vec![(-1, 2), (0, 2), (1, 2)]);
+        world.advance_generations(3);
+        assert_eq!(world.live_cells(), vec![(0, -3), (0, 1), (0, 2)]);
+        assert_eq!(world.generation_count, 4);
+    }
+
+    #[test]
+    fn age_tracking_follows_generations_and_edits() {
+        let mut world = World::new();
+        world.place_pattern(&[(0, 0), (1, 0), (0, 1), (1, 1), (200, 0)]);
+        assert!(world.ages().is_none());
+        world.set_age_tracking(true);
+        world.advance_generations(2);
+        let ages = world.ages().unwrap();
+        assert_eq!(ages.get(0, 0), CellAge::Alive(2));
+        assert_eq!(ages.get(200, 0), CellAge::Fading(1));
+        // 年を取ったブロックのチャンクも、軌跡のチャンクもdirty
+        assert!(world.dirty_chunks().contains(&World::chunk_key(0, 0)));
+        assert!(world.dirty_chunks().contains(&World::chunk_key(200, 0)));
+
+        world.toggle_cell(5, 5);
+        assert_eq!(world.ages().unwrap().get(5, 5), CellAge::Alive(0));
+        assert_eq!(world.ages().unwrap().get(1, 1), CellAge::Alive(2));
+
+        world.clear_dirty_chunks();
+        world.set_age_tracking(false);
+        assert!(world.ages().is_none());
+        assert!(world.dirty_chunks().contains(&World::chunk_key(200, 0)));
+    }
+
+    #[test]
+    fn heatmap_records_flips_of_each_generation() {
+        let mut world = World::new();
+        world.place_pattern(&[(-1, 0), (0, 0), (1, 0)]);
+        world.set_heatmap(Some(HeatmapConfig {
+            window: 2,
+            decay: 1.0,
+        }));
+        world.progress_generation();
+        world.progress_generation();
+        let heatmap = world.heatmap().unwrap();
+        assert_eq!(heatmap.intensity(-1, 0), 1.0);
+        assert_eq!(heatmap.intensity(0, 0), 0.0);
+        assert!(world.dirty_chunks().contains(&World::chunk_key(-1, 0)));
+
+        world.clear_dirty_chunks();
+        world.set_heatmap(None);
+        assert!(world.heatmap().is_none());
+        assert!(world.dirty_chunks().contains(&World::chunk_key(-1, 0)));
+    }
+
+    #[test]
+    fn heatmap_after_multi_generation_advance_matches_single_steps() {
+        let config = HeatmapConfig {
+            window: 8,
+            decay: 0.9,
+        };
+        // 窓より短い跳躍と長い跳躍、エンジンで進めるルールと1世代ずつ計算するルール
+        for (rule, generations) in [("B3/S23", 5), ("B3/S23", 30), ("B2/S/C3", 30)] {
+            let mut worlds: [World; 2] = std::array::from_fn(|_| {
+                let mut world = World::new();
+                world.set_rule(Rule::parse(rule).unwrap());
+                world.place_pattern(LifePattern::Acorn.cells());
+                world.set_heatmap(Some(config));
+                world.progress_generation();
+                world
+            });
+            worlds[0].advance_generations(generations);
+            for _ in 0..generations {
+                worlds[1].progress_generation();
+            }
+            let [jumped, stepped] = &worlds;
+            assert_eq!(jumped.live_cells(), stepped.live_cells());
+            for y in -40..40 {
+                for x in -40..40 {
+                    let (a, b) = (
+                        jumped.heatmap().unwrap().intensity(x, y),
+                        stepped.heatmap().unwrap().intensity(x, y),
+                    );
+                    assert!(
+                        (a - b).abs() < 1e-4,
+                        "{rule} +{generations} ({x}, {y}): {a} != {b}"
+                    );
+                }
+            }
+        }
+    }
+
+    // --- Generations ---
+
+    #[test]
+    fn generations_rule_decays_cells_before_death() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C3").unwrap());
+        world.place_pattern(&[(0, 0), (2, 0)]);
+        world.progress_generation();
+
+        assert_eq!(world.cell_state(0, 0), 2);
+        assert_eq!(world.cell_state(1, -1), 1);
+        assert_eq!(world.cell_state(1, 0), 1);
+        assert_eq!(
+            world.last_step(),
+            StepChange {
+                births: 3,
+                deaths: 2
+            }
+        );
+
+        world.progress_generation();
+        assert_eq!(world.cell_state(0, 0), 0);
+        assert_eq!(world.cell_state(1, 0), 2);
+        assert_eq!(world.cell_state(0, -1), 1);
+        assert_eq!(world.decaying_cells().len(), 3);
+    }
+
+    #[test]
+    fn generations_rule_gives_same_result_on_every_engine() {
+        let rule = Rule::parse("B2/S345/C4").unwrap();
+        let run = |kind: EngineKind| {
+            let mut world = World::new();
+            world.set_engine(kind);
+            world.set_rule(rule);
+            world.place_pattern(LifePattern::Acorn.cells());
+            world.advance_generations(20);
+            let mut decaying: Vec<_> = world.decaying_cells().iter().collect();
+            decaying.sort_unstable();
+            (world.live_cells(), decaying)
+        };
+        let expected = run(EngineKind::Bitboard);
+        assert!(!expected.1.is_empty());
+        assert_eq!(run(EngineKind::HashLife), expected);
+        assert_eq!(run(EngineKind::Sparse), expected);
+    }
+
+    #[test]
+    fn set_cell_state_paints_decaying_state_and_undoes() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C4").unwrap());
+        world.place_pattern(&[(0, 0)]);
+        let diff = world.set_cell_state(0, 0, 3);
+        assert_eq!(world.cell_state(0, 0), 3);
+        assert!(!world.is_initial_alive(0, 0));
+        assert_eq!(diff.cells, vec![(0, 0)]);
+        assert_eq!(diff.decaying, vec![((0, 0), 3)]);
+
+        // 状態数を超える状態は最後の衰退中の状態に丸める
+        world.set_cell_state(0, 0, 200);
+        assert_eq!(world.cell_state(0, 0), 3);
+        world.toggle_cell(0, 0);
+        assert_eq!(world.cell_state(0, 0), 1);
+
+        let toggle = world.toggle_cell(0, 0);
+        world.apply_edit(&toggle, 0, true);
+        assert_eq!(world.cell_state(0, 0), 1);
+        world.apply_edit(&diff, 0, true);
+        assert_eq!(world.cell_state(0, 0), 3);
+        world.reset();
+        assert_eq!(world.cell_state(0, 0), 3);
+    }
+
+    #[test]
+    fn paint_cell_toggles_between_state_and_dead() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C4").unwrap());
+        world.paint_cell(0, 0, 3);
+        assert_eq!(world.cell_state(0, 0), 3);
+        world.paint_cell(0, 0, 2);
+        assert_eq!(world.cell_state(0, 0), 2);
+        world.paint_cell(0, 0, 2);
+        assert_eq!(world.cell_state(0, 0), 0);
+        world.paint_cell(0, 0, 0);
+        assert_eq!(world.cell_state(0, 0), 1);
+    }
+
+    #[test]
+    fn clear_and_erase_rect_remove_decaying_cells() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C5").unwrap());
+        world.set_cell_state(0, 0, 2);
+        world.set_cell_state(9, 9, 4);
+        let diff = world.erase_rect(GridRect::from_corners((-1, -1), (1, 1)));
+        assert_eq!(diff.decaying, vec![((0, 0), 2)]);
+        assert_eq!(world.cell_state(0, 0), 0);
+        assert_eq!(world.cell_state(9, 9), 4);
+
+        let diff = world.clear();
+        assert!(world.decaying_cells().is_empty());
+        world.apply_edit(&diff, 0, true);
+        assert_eq!(world.cell_state(9, 9), 4);
+    }
+
+    #[test]
+    fn advance_with_diff_can_be_undone() {
+        for rule in [Rule::conway(), Rule::parse("B2/S/C3").unwrap()] {
+            let mut world = World::new();
+            world.set_rule(rule);
+            world.place_pattern(&[(0, 0), (1, 0), (2, 0), (2, -1), (1, -2)]);
+            world.advance_generations(3);
+            let cells = world.alive_cells().clone();
+            let decaying = world.decaying_cells().clone();
+            let (diff, decaying_diff) = world.advance_generations_with_diff(5);
+            assert_eq!(world.generation_count, 8);
+            world.apply_generation_diff(&diff, &decaying_diff, 3);
+            assert_eq!(world.alive_cells(), &cells);
+            assert_eq!(world.decaying_cells(), &decaying);
+        }
+    }
+
+    #[test]
+    fn generation_diff_restores_decaying_cells() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C3").unwrap());
+        world.place_pattern(&[(0, 0), (1, 0)]);
+        world.progress_generation();
+        let cells = world.alive_cells().clone();
+        let decaying = world.decaying_cells().clone();
+        world.progress_generation();
+
+        let diff = cells.xor(world.alive_cells());
+        let decaying_diff = decaying.xor(world.decaying_cells());
+        world.apply_generation_diff(&diff, &decaying_diff, 1);
+        assert_eq!(world.alive_cells(), &cells);
+        assert_eq!(world.decaying_cells(), &decaying);
+    }
+
+    #[test]
+    fn switching_rule_drops_states_it_does_not_have() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("B2/S/C5").unwrap());
+        world.set_cell_state(0, 0, 2);
+        world.set_cell_state(1, 0, 4);
+        world.clear_dirty_chunks();
+        let revision = world.revision();
+
+        world.set_rule(Rule::parse("B2/S/C3").unwrap());
+        assert_eq!(world.cell_state(0, 0), 2);
+        assert_eq!(world.cell_state(1, 0), 0);
+        assert!(world.revision() > revision);
+
+        world.set_rule(Rule::conway());
+        assert!(world.decaying_cells().is_empty());
+        assert!(world.dirty_chunks().contains(&World::chunk_key(0, 0)));
+        world.reset();
+        assert_eq!(world.cell_state(0, 0), 0);
+    }
+
+    #[test]
+    fn wireworld_circuits_send_electrons_forward_only() {
+        let arrivals = |circuit: WireworldCircuit| {
+            let mut world = World::new();
+            world.set_rule(Rule::parse("Wireworld").unwrap());
+            let cells = circuit.cells();
+            world.place_states(&cells);
+            let end = cells.iter().map(|&((x, _), _)| x).max().unwrap();
+            (1..=40)
+                .filter(|_| {
+                    world.progress_generation();
+                    world.cell_state(end, 1) == Wireworld::HEAD
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(arrivals(WireworldCircuit::Clock), vec![11, 21, 31]);
+        assert_eq!(arrivals(WireworldCircuit::Diode), vec![16, 26, 36]);
+        assert!(arrivals(WireworldCircuit::ReverseDiode).is_empty());
+    }
+
+    #[test]
+    fn place_states_places_each_state_and_undoes() {
+        let mut world = World::new();
+        world.set_rule(Rule::parse("Wireworld").unwrap());
+        let diff = world.place_states(&[
+            ((0, 0), Wireworld::CONDUCTOR),
+            ((1, 0), Wireworld::HEAD),
+            ((2, 0), 7),
+            ((3, 0), Wireworld::EMPTY),
+        ]);
+        assert_eq!(world.cell_state(0, 0), Wireworld::CONDUCTOR);
+        assert_eq!(world.cell_state(1, 0), Wireworld::HEAD);
+        assert_eq!(world.cell_state(2, 0), Wireworld::EMPTY);
+        assert_eq!(world.census(), Census::default());
+
+        world.progress_generation();
+        world.reset();
+        assert_eq!(world.cell_state(0, 0), Wireworld::CONDUCTOR);
+        world.apply_edit(&diff, 0, true);
+        assert_eq!(world.cell_state(0, 0), Wireworld::EMPTY);
+        assert_eq!(world.cell_state(1, 0), Wireworld::EMPTY);
+    }
+
+    // --- 近傍 ---
+
+    #[test]
+    fn non_moore_rule_gives_same_result_on_every_engine() {
+        let rule = Rule::parse("B2/S34H").unwrap();
+        let run = |kind: EngineKind| {
+            let mut world = World::new();
+            world.set_engine(kind);
+            world.set_rule(rule);
+            world.place_pattern(LifePattern::Glider.cells());
+            world.advance_generations(12);
+            world.live_cells()
+        };
+        let expected = run(EngineKind::Bitboard);
+        let cells: Bitboard = LifePattern::Glider.cells().iter().copied().collect();
+        let mut sparse = cells;
+        for _ in 0..12 {
+            sparse = neighborhood::step(&rule, &sparse, &Topology::Infinite);
+        }
+        let mut sparse: Vec<_> = sparse.iter().collect();
+        sparse.sort_unstable_by_key(|&(x, y)| (y, x));
+        assert!(!expected.is_empty());
+        assert_eq!(expected, sparse);
+        assert_eq!(run(EngineKind::HashLife), expected);
+        assert_eq!(run(EngineKind::Sparse), expected);
+    }
+
+    #[test]
+    fn hensel_rule_gives_same_result_on_every_engine() {
+        let rule = RulePreset::Tlife.rule();
+        let run = |kind: EngineKind| {
+            let mut world = World::new();
+            world.set_engine(kind);
+            world.set_rule(rule);
+            world.place_pattern(LifePattern::RPentomino.cells());
+            world.advance_generations(30);
+            world.live_cells()
+        };
+        let expected = run(EngineKind::Bitboard);
+        let mut cells: Bitboard = LifePattern::RPentomino.cells().iter().copied().collect();
+        for _ in 0..30 {
+            cells = isotropic::step(&rule, &cells, &Topology::Infinite);
+        }
+        let mut cells: Vec<_> = cells.iter().collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        assert!(!expected.is_empty());
+        assert_eq!(expected, cells);
+        assert_eq!(run(EngineKind::HashLife), expected);
+        assert_eq!(run(EngineKind::Sparse), expected);
+
+        let mut conway = World::new();
+        conway.place_pattern(LifePattern::RPentomino.cells());
+        conway.advance_generations(30);
+        assert_ne!(conway.live_cells(), expected);
+    }
+
+    #[test]
+    fn census_is_skipped_for_non_moore_rules() {
+        let mut world = World::new();
+        world.set_rule(RulePreset::Bosco.rule());
+        world.place_pattern(LifePattern::Glider.cells());
+        assert_eq!(world.census(), Census::default());
+    }
+}
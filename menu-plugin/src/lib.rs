@@ -2,20 +2,29 @@
 //!
 //! ゲーム中にEscapeキーで遷移するメニュー画面を提供する。
 //! Back（タイトルに戻る）とQuit（アプリ終了）のボタンを表示する。
-//! パターンボタンでライフゲームの有名パターンをワールドに配置できる。
+//! パターンボタンでライフゲームの有名パターンを選ぶと、ゲーム画面のスタンプモードで配置できる。
+//! 回路ボタンで Wireworld の回路の例を選ぶと、ルールを Wireworld にして同じくスタンプモードで配置できる。
+//! ルールボタンでシミュレーションに使用する Life-like ルールを選択できる。
+//! トポロジーボタンでワールドを無限平面か有限の面（トーラスなど）にするかと、その大きさを選択できる。
+//! Save/Loadボタンで現在のワールドをセッションとして保存・読み込みできる。
 
 use bevy::prelude::*;
 
 use common::{
     consts::{
-        BG_BUTTON, BG_DARK, BORDER_RADIUS, BORDER_SUBTLE, FONT_SIZE_MEDIUM, FONT_SIZE_SMALL,
-        PATTERN_BUTTON_HEIGHT, PATTERN_BUTTON_WIDTH, SPACING_LG, SPACING_SM, TEXT_MUTED,
-        TEXT_PRIMARY, TITLE_BUTTON_WIDTH,
+        ACCENT_GREEN, BG_BUTTON, BG_DARK, BORDER_RADIUS, BORDER_SUBTLE, FONT_SIZE_MEDIUM,
+        FONT_SIZE_SMALL, PATTERN_BUTTON_HEIGHT, PATTERN_BUTTON_WIDTH, SPACING_LG, SPACING_SM,
+        TEXT_MUTED, TEXT_PRIMARY, TITLE_BUTTON_WIDTH, TOPOLOGY_SIZE_PRESETS,
     },
-    patterns::LifePattern,
-    resources::{AudioMuted, GameAssets, SelectedPattern},
+    patterns::{LifePattern, WireworldCircuit},
+    resources::{
+        AudioMuted, GameAssets, SelectedCircuit, SelectedPattern, SelectedRule, SelectedTopology,
+    },
+    rules::RulePreset,
+    session::{SessionRequest, SessionStatus},
     states::GameState,
     systems::{despawn_entity, setup_camera},
+    topology::Surface,
     ui::{
         handle_screen_button_out, handle_screen_button_over, spawn_screen_button,
         spawn_screen_container, spawn_screen_title,
@@ -37,7 +46,8 @@ impl Plugin for MenuPlugin {
         );
         app.add_systems(
             Update,
-            menu_input_keyboard_handling.run_if(in_state(GameState::Menu)),
+            (menu_input_keyboard_handling, update_session_status_text)
+                .run_if(in_state(GameState::Menu)),
         );
     }
 }
@@ -63,6 +73,26 @@ enum MenuButtonAction {
 #[derive(Component)]
 struct PatternButton(LifePattern);
 
+/// Wireworld の回路ボタンのマーカー（配置する回路を保持）
+#[derive(Component)]
+struct CircuitButton(WireworldCircuit);
+
+/// ルールボタンのマーカー（選択するルールプリセットを保持）
+#[derive(Component)]
+struct RuleButton(RulePreset);
+
+/// トポロジーボタンのマーカー（選択する面の種類を保持し、`None` は無限平面）
+#[derive(Component)]
+struct TopologyButton(Option<Surface>);
+
+/// 有限トポロジーの大きさを切り替えるボタンのマーカー
+#[derive(Component)]
+struct TopologySizeButton;
+
+/// セッションの保存・読み込み結果を表示するテキストのマーカー
+#[derive(Component)]
+struct SessionStatusText;
+
 /// メニュー画面用カメラを生成する
 fn setup_menu_camera(commands: Commands) {
     setup_camera(commands, MenuCamera);
@@ -82,7 +112,11 @@ fn setup_menu_screen(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
     audio_muted: Res<AudioMuted>,
+    selected_rule: Res<SelectedRule>,
+    selected_topology: Res<SelectedTopology>,
+    mut session_status: ResMut<SessionStatus>,
 ) {
+    session_status.0 = None;
     let mute_label = mute_button_label(audio_muted.0);
     spawn_screen_container(&mut commands, OnMenuScreen, BG_DARK).with_children(|parent| {
         spawn_screen_title(
@@ -99,36 +133,59 @@ fn setup_menu_screen(
                 ..default()
             })
             .with_children(|p| {
-                // パターンセクション
-                spawn_pattern_section(p, game_assets.font_bold.clone());
+                // パターン・ルール・トポロジーセクション（横並び）
+                p.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::FlexStart,
+                    column_gap: Val::Px(SPACING_LG),
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_pattern_section(row, game_assets.font_bold.clone());
+                    spawn_rule_section(
+                        row,
+                        game_assets.font_bold.clone(),
+                        selected_rule.0.preset(),
+                    );
+                    spawn_topology_section(row, game_assets.font_bold.clone(), *selected_topology);
+                });
 
-                // 既存ボタン群
+                // 既存ボタン群とセッション保存・読み込み（横並び）
                 p.spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::SpaceBetween,
-                    width: Val::Px(TITLE_BUTTON_WIDTH),
-                    height: Val::Px(TITLE_BUTTON_WIDTH),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::FlexStart,
+                    column_gap: Val::Px(SPACING_LG),
                     ..default()
                 })
-                .with_children(|q| {
-                    spawn_mute_button(q, game_assets.font_bold.clone(), &mute_label);
-                    spawn_screen_button(q, game_assets.font_bold.clone(), "Back")
-                        .insert(MenuButtonAction::Back)
-                        .observe(on_back_button_click)
-                        .observe(handle_screen_button_over)
-                        .observe(handle_screen_button_out);
-                    spawn_screen_button(q, game_assets.font_bold.clone(), "Quit")
-                        .insert(MenuButtonAction::Quit)
-                        .observe(on_quit_button_click)
-                        .observe(handle_screen_button_over)
-                        .observe(handle_screen_button_out);
+                .with_children(|row| {
+                    row.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::SpaceBetween,
+                        width: Val::Px(TITLE_BUTTON_WIDTH),
+                        height: Val::Px(TITLE_BUTTON_WIDTH),
+                        ..default()
+                    })
+                    .with_children(|q| {
+                        spawn_mute_button(q, game_assets.font_bold.clone(), &mute_label);
+                        spawn_screen_button(q, game_assets.font_bold.clone(), "Back")
+                            .insert(MenuButtonAction::Back)
+                            .observe(on_back_button_click)
+                            .observe(handle_screen_button_over)
+                            .observe(handle_screen_button_out);
+                        spawn_screen_button(q, game_assets.font_bold.clone(), "Quit")
+                            .insert(MenuButtonAction::Quit)
+                            .observe(on_quit_button_click)
+                            .observe(handle_screen_button_over)
+                            .observe(handle_screen_button_out);
+                    });
+                    spawn_session_section(row, &game_assets);
                 });
             });
     });
 }
 
-/// パターンセクション（ラベル + 2列グリッド）を生成する
+/// パターンセクション（ラベル + 2列グリッド + Wireworld の回路の2列グリッド）を生成する
 fn spawn_pattern_section(parent: &mut ChildSpawnerCommands<'_>, font: Handle<Font>) {
     parent
         .spawn(Node {
@@ -163,6 +220,34 @@ fn spawn_pattern_section(parent: &mut ChildSpawnerCommands<'_>, font: Handle<Fon
                     spawn_pattern_button(grid, font.clone(), pattern);
                 }
             });
+
+            // Wireworld の回路ラベル
+            p.spawn((
+                Text::new("Circuits"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: FONT_SIZE_MEDIUM,
+                    ..default()
+                },
+                TextColor(TEXT_MUTED),
+            ));
+
+            // 回路の2列グリッド
+            p.spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(), GridTrack::auto()],
+                column_gap: Val::Px(SPACING_SM),
+                row_gap: Val::Px(SPACING_SM),
+                justify_content: JustifyContent::Center,
+                ..default()
+            })
+            .with_children(|grid| {
+                for &circuit in WireworldCircuit::all() {
+                    spawn_menu_option_button(grid, font.clone(), circuit.label(), BORDER_SUBTLE)
+                        .insert(CircuitButton(circuit))
+                        .observe(on_circuit_button_click);
+                }
+            });
         });
 }
 
@@ -224,6 +309,339 @@ fn on_pattern_button_click(
     }
 }
 
+/// 回路ボタンのクリックハンドラ: ルールを Wireworld にして回路を選択し、ゲーム画面に遷移する
+fn on_circuit_button_click(
+    click: On<Pointer<Click>>,
+    circuit_query: Query<&CircuitButton>,
+    mut selected_circuit: ResMut<SelectedCircuit>,
+    mut selected_rule: ResMut<SelectedRule>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if let Ok(circuit_button) = circuit_query.get(click.entity) {
+        selected_circuit.0 = circuit_button.0;
+        selected_rule.0 = RulePreset::Wireworld.rule();
+        state.set(GameState::Game);
+    }
+}
+
+/// ルール選択セクション（ラベル + 2列グリッド）を生成する
+///
+/// `selected` に一致するプリセットのボタンはアクセント色の枠線で強調する。
+fn spawn_rule_section(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: Handle<Font>,
+    selected: Option<RulePreset>,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SPACING_SM),
+            ..default()
+        })
+        .with_children(|p| {
+            // セクションラベル
+            p.spawn((
+                Text::new("Rules"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: FONT_SIZE_MEDIUM,
+                    ..default()
+                },
+                TextColor(TEXT_MUTED),
+            ));
+
+            // 2列グリッド
+            p.spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(), GridTrack::auto()],
+                column_gap: Val::Px(SPACING_SM),
+                row_gap: Val::Px(SPACING_SM),
+                justify_content: JustifyContent::Center,
+                ..default()
+            })
+            .with_children(|grid| {
+                for &preset in RulePreset::all() {
+                    spawn_rule_button(grid, font.clone(), preset, selected == Some(preset));
+                }
+            });
+        });
+}
+
+/// ルール選択状態に応じたボタン枠線色を返す
+fn rule_button_border(selected: bool) -> Color {
+    if selected {
+        ACCENT_GREEN
+    } else {
+        BORDER_SUBTLE
+    }
+}
+
+/// ルールボタンを生成する（パターンボタンと同サイズ）
+fn spawn_rule_button(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: Handle<Font>,
+    preset: RulePreset,
+    selected: bool,
+) {
+    let label = preset.label().to_string();
+    parent
+        .spawn((
+            Node {
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                width: Val::Px(PATTERN_BUTTON_WIDTH),
+                height: Val::Px(PATTERN_BUTTON_HEIGHT),
+                border: UiRect::all(Val::Px(1.0)),
+                border_radius: BorderRadius::px(
+                    BORDER_RADIUS,
+                    BORDER_RADIUS,
+                    BORDER_RADIUS,
+                    BORDER_RADIUS,
+                ),
+                ..default()
+            },
+            Button,
+            BackgroundColor(BG_BUTTON),
+            BorderColor::all(rule_button_border(selected)),
+            RuleButton(preset),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(label),
+                TextFont {
+                    font,
+                    font_size: FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(TEXT_PRIMARY),
+                Pickable::IGNORE,
+            ));
+        })
+        .observe(on_rule_button_click)
+        .observe(handle_screen_button_over)
+        .observe(handle_screen_button_out);
+}
+
+/// ルールボタンのクリックハンドラ: ルールを選択し、選択中ボタンの枠線を更新する
+fn on_rule_button_click(
+    click: On<Pointer<Click>>,
+    mut rule_query: Query<(Entity, &RuleButton, &mut BorderColor)>,
+    mut selected_rule: ResMut<SelectedRule>,
+) {
+    let Ok((_, rule_button, _)) = rule_query.get(click.entity) else {
+        return;
+    };
+    selected_rule.0 = rule_button.0.rule();
+
+    for (entity, _, mut border) in &mut rule_query {
+        *border = BorderColor::all(rule_button_border(entity == click.entity));
+    }
+}
+
+/// トポロジー選択セクション（ラベル + 面の種類の1列 + 大きさの切り替えボタン）を生成する
+///
+/// 選択中の面の種類のボタンはルールボタンと同じくアクセント色の枠線で強調する。
+fn spawn_topology_section(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: Handle<Font>,
+    selected: SelectedTopology,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SPACING_SM),
+            ..default()
+        })
+        .with_children(|p| {
+            // セクションラベル
+            p.spawn((
+                Text::new("Topology"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: FONT_SIZE_MEDIUM,
+                    ..default()
+                },
+                TextColor(TEXT_MUTED),
+            ));
+
+            let surfaces = std::iter::once(None).chain(Surface::all().iter().copied().map(Some));
+            for surface in surfaces {
+                spawn_menu_option_button(
+                    p,
+                    font.clone(),
+                    topology_button_label(surface),
+                    rule_button_border(selected.surface == surface),
+                )
+                .insert(TopologyButton(surface))
+                .observe(on_topology_button_click);
+            }
+            spawn_menu_option_button(
+                p,
+                font,
+                topology_size_label(selected.width, selected.height),
+                BORDER_SUBTLE,
+            )
+            .insert(TopologySizeButton)
+            .observe(on_topology_size_button_click);
+        });
+}
+
+/// トポロジーボタンのラベルを返す
+fn topology_button_label(surface: Option<Surface>) -> &'static str {
+    surface.map_or("Infinite", |surface| surface.name())
+}
+
+/// 有限トポロジーの大きさボタンのラベルを返す
+fn topology_size_label(width: u32, height: u32) -> String {
+    format!("Size: {width}x{height}")
+}
+
+/// プリセットのうち `current` の次に大きい1辺のセル数を返す（最大の次は最小に戻る）
+fn next_topology_size(current: u32) -> u32 {
+    TOPOLOGY_SIZE_PRESETS
+        .iter()
+        .copied()
+        .find(|&size| size > current)
+        .unwrap_or(TOPOLOGY_SIZE_PRESETS[0])
+}
+
+/// トポロジーセクションの選択肢ボタンを生成する（パターンボタンと同サイズ）
+fn spawn_menu_option_button<'a>(
+    parent: &'a mut ChildSpawnerCommands<'_>,
+    font: Handle<Font>,
+    label: impl Into<String>,
+    border: Color,
+) -> EntityCommands<'a> {
+    let mut button = parent.spawn((
+        Node {
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            width: Val::Px(PATTERN_BUTTON_WIDTH),
+            height: Val::Px(PATTERN_BUTTON_HEIGHT),
+            border: UiRect::all(Val::Px(1.0)),
+            border_radius: BorderRadius::px(
+                BORDER_RADIUS,
+                BORDER_RADIUS,
+                BORDER_RADIUS,
+                BORDER_RADIUS,
+            ),
+            ..default()
+        },
+        Button,
+        BackgroundColor(BG_BUTTON),
+        BorderColor::all(border),
+    ));
+    button
+        .with_children(|p| {
+            p.spawn((
+                Text::new(label),
+                TextFont {
+                    font,
+                    font_size: FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(TEXT_PRIMARY),
+                Pickable::IGNORE,
+            ));
+        })
+        .observe(handle_screen_button_over)
+        .observe(handle_screen_button_out);
+    button
+}
+
+/// トポロジーボタンのクリックハンドラ: 面の種類を選択し、選択中ボタンの枠線を更新する
+fn on_topology_button_click(
+    click: On<Pointer<Click>>,
+    mut topology_query: Query<(Entity, &TopologyButton, &mut BorderColor)>,
+    mut selected_topology: ResMut<SelectedTopology>,
+) {
+    let Ok((_, topology_button, _)) = topology_query.get(click.entity) else {
+        return;
+    };
+    selected_topology.surface = topology_button.0;
+
+    for (entity, _, mut border) in &mut topology_query {
+        *border = BorderColor::all(rule_button_border(entity == click.entity));
+    }
+}
+
+/// 大きさボタンのクリックハンドラ: 有限トポロジーの1辺のセル数を次のプリセットに切り替える
+fn on_topology_size_button_click(
+    click: On<Pointer<Click>>,
+    mut selected_topology: ResMut<SelectedTopology>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
+) {
+    let size = next_topology_size(selected_topology.width);
+    selected_topology.width = size;
+    selected_topology.height = size;
+
+    if let Ok(children) = children_query.get(click.entity) {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = topology_size_label(size, size);
+            }
+        }
+    }
+}
+
+/// セッションの保存・読み込みボタンと結果表示を生成する
+fn spawn_session_section(parent: &mut ChildSpawnerCommands<'_>, game_assets: &GameAssets) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(SPACING_SM),
+            width: Val::Px(TITLE_BUTTON_WIDTH),
+            ..default()
+        })
+        .with_children(|q| {
+            spawn_screen_button(q, game_assets.font_bold.clone(), "Save")
+                .observe(on_save_button_click)
+                .observe(handle_screen_button_over)
+                .observe(handle_screen_button_out);
+            spawn_screen_button(q, game_assets.font_bold.clone(), "Load")
+                .observe(on_load_button_click)
+                .observe(handle_screen_button_over)
+                .observe(handle_screen_button_out);
+            q.spawn((
+                Text::new(""),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: FONT_SIZE_SMALL,
+                    ..default()
+                },
+                TextColor(TEXT_MUTED),
+                SessionStatusText,
+            ));
+        });
+}
+
+/// Saveボタンのクリックハンドラ: 現在のワールドの保存を要求する
+fn on_save_button_click(_click: On<Pointer<Click>>, mut requests: MessageWriter<SessionRequest>) {
+    requests.write(SessionRequest::Save);
+}
+
+/// Loadボタンのクリックハンドラ: 保存したワールドの読み込みを要求する
+fn on_load_button_click(_click: On<Pointer<Click>>, mut requests: MessageWriter<SessionRequest>) {
+    requests.write(SessionRequest::Load);
+}
+
+/// セッションの保存・読み込み結果をテキストに反映するシステム
+fn update_session_status_text(
+    status: Res<SessionStatus>,
+    mut query: Query<&mut Text, With<SessionStatusText>>,
+) {
+    if !status.is_changed() {
+        return;
+    }
+    for mut text in &mut query {
+        **text = status.0.clone().unwrap_or_default();
+    }
+}
+
 /// ミュートトグルボタンを生成する
 fn spawn_mute_button(parent: &mut ChildSpawnerCommands<'_>, font: Handle<Font>, label: &str) {
     spawn_screen_button(parent, font, label)
@@ -7,7 +7,8 @@ use bevy::{asset::AssetMetaCheck, prelude::*, window::WindowResolution};
 
 use common::{
     consts::{MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH},
-    resources::{GameAssets, SelectedPattern},
+    resources::{GameAssets, SelectedCircuit, SelectedPattern, SelectedRule, SelectedTopology},
+    settings::SettingsPlugin,
     states::GameState,
 };
 
@@ -43,11 +44,15 @@ fn main() {
                     ..default()
                 }),
         )
+        .add_plugins(SettingsPlugin)
         .add_plugins(TitlePlugin)
         .add_plugins(GamePlugin)
         .add_plugins(MenuPlugin)
         .init_resource::<GameAssets>()
         .init_resource::<SelectedPattern>()
+        .init_resource::<SelectedCircuit>()
+        .init_resource::<SelectedRule>()
+        .init_resource::<SelectedTopology>()
         .init_state::<GameState>()
         .run();
 }
@@ -102,6 +102,16 @@ fn setup_title_screen(mut commands: Commands, game_assets: Res<GameAssets>) {
                     ("Q / E", "Zoom in / out"),
                     ("Space", "Tap: step one generation"),
                     ("", "Hold: start / stop simulation"),
+                    ("B", "Step back one generation"),
+                    ("I", "Toggle statistics panel"),
+                    ("G", "Toggle age colouring"),
+                    ("H", "Toggle activity heatmap"),
+                    ("N", "Cycle paint state (Generations, Wireworld)"),
+                    ("Ctrl+Z", "Undo edit (+Shift: redo)"),
+                    ("Shift+Drag", "Select area"),
+                    ("Ctrl+C / X / V", "Copy / cut / paste"),
+                    ("Delete", "Delete selection"),
+                    ("R / F / V / T", "Pasting: rotate / flip / transpose"),
                     ("Esc", "Menu"),
                 ];
 